@@ -0,0 +1,632 @@
+language-name = Deutsch
+
+error-title = Fehler
+restarting = Ich starte gerade neu, versuch es gleich noch einmal
+role-managed = { $role } kann ich nicht vergeben
+role-too-high = Ich kann { $role } nicht vergeben, sie muss unter meiner höchsten Rolle sein
+role-above-you = Du kannst { $role } nicht vergeben, sie muss unter deiner höchsten Rolle sein
+
+cooldown-title = Nicht so schnell!
+cooldown-description = Du kannst `/{ $command }` { $time } wieder benutzen
+
+## Moderation
+
+no-reason = Kein Grund angegeben
+mod-error = Fehler beim { $action ->
+    [ban] Bannen
+    [kick] Kicken
+    [mute] Stummschalten
+   *[warn] Verwarnen
+}
+mod-higher-role = { $user } hat eine höhere Rolle als { $who ->
+    [both] du und ich
+    [author] du
+   *[bot] ich
+} oder ist der Besitzer des Servers, daher kann ich { $action ->
+    [ban] diese Person nicht bannen
+    [kick] diese Person nicht kicken
+    [mute] diese Person nicht stummschalten
+   *[warn] diese Person nicht verwarnen
+}.
+mod-dm-title = Du wurdest auf { $server } { $action ->
+    [ban] gebannt
+    [kick] gekickt
+    [mute] stummgeschaltet
+   *[warn] verwarnt
+}
+mod-action-title = { $user } { $action ->
+    [ban] gebannt
+    [kick] gekickt
+    [mute] stummgeschaltet
+   *[warn] verwarnt
+}
+mod-field-user = Nutzer
+mod-field-reason = Grund
+mod-field-duration = Dauer
+invalid-duration = `{ $value }` ist keine gültige Dauer, versuche etwas wie 10m, 2h oder 1d 12h
+mute-too-long = Mitglieder können höchstens 28 Tage stummgeschaltet werden
+
+## Info
+
+userinfo-title = Über { $user }
+serverinfo-title = Über { $server }
+roleinfo-title = Über { $role }
+info-none = Keine
+info-yes-no = { $value ->
+    [true] Ja
+   *[false] Nein
+}
+info-created = Erstellt
+info-joined = Beigetreten
+info-highest-role = Höchste Rolle
+info-roles = Rollen ({ $count })
+info-key-permissions = Wichtige Berechtigungen
+info-timeout = Timeout
+info-not-timed-out = Kein Timeout
+info-timed-out-until = Endet { $time }
+info-infractions = Verstöße
+info-owner = Eigentümer
+info-members = Mitglieder
+info-cached-members = Mitglieder (im Cache)
+info-channels = Kanäle
+info-channel-counts = { $text } Text, { $voice } Sprache, { $categories } Kategorien
+info-emojis = Emojis
+info-boosts = Boosts
+info-boost-summary = { $count } (Stufe { $tier })
+info-features = Funktionen
+info-color = Farbe
+info-position = Position
+info-mentionable = Erwähnbar
+info-hoisted = Separat angezeigt
+info-permissions = Berechtigungen
+
+## Help
+
+help-title = Befehle
+help-description = Mit `/help <befehl>` erfährst du mehr über einen Befehl. Textbefehle beginnen mit `{ $prefix }`.
+help-category-fun = Spaß
+help-category-general = Allgemein
+help-category-moderation = Moderation
+help-category-config = Einstellungen
+help-category-permissions = Berechtigungen
+help-category-reminders = Erinnerungen
+help-category-polls = Umfragen
+help-category-giveaways = Gewinnspiele
+help-category-tags = Tags
+help-category-roles = Rollen
+help-category-levels = Levels
+help-usage = Verwendung
+help-parameters = Optionen
+help-optional = (optional)
+help-permissions = Berechtigungen
+help-anyone = Alle
+help-cooldowns = Abklingzeiten
+help-no-cooldown = Keine
+help-cooldown = { $seconds }s pro { $bucket ->
+    [user] Nutzer
+    [channel] Kanal
+   *[guild] Server
+}
+help-subcommands = Unterbefehle
+
+## Permissions
+
+permission-denied = Du kannst `/{ $command }` hier nicht benutzen
+permission-missing = Du brauchst die Berechtigung { $permissions }, um `/{ $command }` zu benutzen
+permission-one-target = Wähle genau eine Rolle, einen Nutzer oder einen Kanal
+permission-protected = Der Zugriff auf `/{ $command }` kann nicht geändert werden
+permissions-title = Angepasste Berechtigungen
+permissions-updated = Berechtigungen aktualisiert
+permissions-empty = Noch keine Anpassungen
+permissions-rule = `/{ $command }` für { $target } { $allow ->
+    [true] erlaubt
+   *[false] verboten
+}
+
+## Reminders
+
+reminder-title = Erinnerung
+reminder-set = Gestellt { $time }
+reminder-late = Ich war offline, als die Erinnerung { $time } fällig war, entschuldige die Wartezeit
+reminder-added = Ich erinnere dich { $time } (Erinnerung #{ $id })
+reminder-add-failed = Ich konnte die Erinnerung nicht speichern, versuche es später noch einmal
+reminder-too-long = Erinnerungen können höchstens ein Jahr im Voraus gestellt werden
+reminder-limit = Du kannst höchstens { $max } Erinnerungen gleichzeitig haben, lösche zuerst eine
+reminder-not-found = Du hast keine Erinnerung #{ $id }
+reminder-cancelled = Erinnerung #{ $id } gelöscht
+reminders-title = Deine Erinnerungen
+reminders-empty = Du hast keine Erinnerungen, stelle eine mit `/remind in`
+reminders-entry = `#{ $id }` { $time }: { $text }
+
+## Tags
+
+tag-created = Tag { $name } erstellt
+tag-updated = Tag { $name } aktualisiert
+tag-deleted = Tag { $name } gelöscht
+tag-not-found = Es gibt keinen Tag `{ $name }`
+tag-exists = Es gibt schon einen Tag `{ $name }`
+tag-invalid-name = `{ $name }` kann kein Tag-Name sein, nutze bis zu { $max } Buchstaben, Ziffern, - und _
+tag-command-name = `{ $name }` ist schon ein Befehl
+tags-title = Tags ({ $count })
+tags-empty = Dieser Server hat noch keine Tags, Moderatoren können sie mit `/tag create` erstellen
+tag-info-title = Tag { $name }
+tag-owner = Ersteller
+tag-uses = Benutzungen
+tag-format = Format
+tag-format-value = { $embed ->
+    [true] Embed
+   *[false] Text
+}
+tag-created-at = Erstellt
+
+## Role panels
+
+rolepanel-created = Rollenpanel #{ $id } erstellt, füge mit `/rolepanel add` Rollen hinzu
+rolepanel-updated = Rollenpanel #{ $id } aktualisiert
+rolepanel-deleted = Rollenpanel #{ $id } gelöscht
+rolepanel-not-found = Es gibt kein Rollenpanel #{ $id }
+rolepanel-send-failed = Ich konnte nicht in { $channel } posten
+rolepanel-create-failed = Ich konnte das Rollenpanel nicht speichern, versuche es später noch einmal
+rolepanel-message-missing = Ich konnte die Nachricht des Panels nicht aktualisieren, vielleicht wurde sie gelöscht
+rolepanel-full = Rollenpanels können höchstens { $max } Rollen haben
+rolepanel-role-missing = { $role } ist nicht auf Rollenpanel #{ $id }
+rolepanel-invalid-emoji = `{ $emoji }` ist kein Emoji
+rolepanel-no-roles = Noch keine Rollen
+rolepanel-placeholder = Wähle Rollen zum Hinzufügen oder Entfernen
+rolepanel-gone = Dieses Rollenpanel gibt es nicht mehr
+rolepanel-your-roles = Deine Rollen
+rolepanel-added = Du hast { $role } bekommen
+rolepanel-removed = { $role } wurde dir weggenommen
+rolepanel-refused = Ich konnte { $role } nicht ändern
+rolepanel-nothing = Nichts hat sich geändert
+rolepanels-title = Rollenpanels
+rolepanels-empty = Dieser Server hat noch keine Rollenpanels
+rolepanels-entry = `#{ $id }` [{ $title }]({ $link }), { $count } Rollen
+
+## Levels
+
+rank-title = Level von { $user }
+rank-progress = { $xp } / { $needed } XP bis Level { $next }
+rank-level = Level
+rank-rank = Rang
+rank-xp = XP insgesamt
+rank-position = #{ $rank }
+rank-unranked = Ohne Rang
+leaderboard-title = Bestenliste, Seite { $page } von { $pages }
+leaderboard-empty = Hier hat noch niemand XP gesammelt
+leaderboard-entry = **#{ $rank }** <@{ $user }>, Level { $level } ({ $xp } XP)
+levels-title = Level-Einstellungen
+levels-updated-rewards = Level-Belohnungen aktualisiert
+levels-updated-channels = Kanäle ohne XP aktualisiert
+levels-summary = Mitglieder bekommen höchstens alle { $seconds } Sekunden für eine Nachricht XP
+levels-rewards = Rollenbelohnungen
+levels-reward = Level { $level }: { $role }
+levels-no-xp-channels = Kanäle ohne XP
+levels-no-reward = { $role } ist keine Level-Belohnung
+
+## Starboard
+
+starboard-stars = ⭐ **{ $stars }** in { $channel }
+starboard-jump = [Zur Nachricht]({ $link })
+
+## Polls
+
+poll-title = 📊 Umfrage
+poll-results-title = 📊 Umfrageergebnis
+poll-closes = Endet { $time }
+poll-closed = Beendet { $time }
+poll-voters = { $count } haben abgestimmt
+poll-multi-choice = Du kannst mehrere Antworten wählen
+poll-anonymous = Die Abstimmung ist anonym
+poll-result = { $bar } { $count } ({ $percent } %)
+poll-invalid-options = Gib 2 bis { $max } verschiedene Antworten an, getrennt durch `|` und jeweils höchstens { $length } Zeichen lang
+poll-too-long = Umfragen können höchstens { $max } laufen
+poll-create-failed = Ich konnte die Umfrage nicht speichern, versuche es später noch einmal
+poll-gone = Diese Umfrage ist beendet
+poll-your-vote = Deine Stimme
+poll-voted = Du hast für { $options } gestimmt, klicke noch einmal, um die Stimme zurückzunehmen
+poll-vote-removed = Du hast deine Stimme zurückgenommen
+poll-vote-failed = Ich konnte deine Stimme nicht speichern, versuche es später noch einmal
+
+## Giveaways
+
+giveaway-title = 🎉 Gewinnspiel
+giveaway-enter = Teilnehmen
+giveaway-ends = Endet { $time }
+giveaway-ended = Beendet { $time }
+giveaway-winner-count = { $count ->
+    [one] 1 Gewinner
+   *[other] { $count } Gewinner
+}
+giveaway-winners = Gewinner: { $winners }
+giveaway-no-winners = Niemand hat teilgenommen, daher gibt es keine Gewinner
+giveaway-host = Veranstaltet von { $host }
+giveaway-entries = { $count } nehmen teil
+giveaway-requires-role = Nur Mitglieder mit { $role } können teilnehmen
+giveaway-requires-age = Nur Konten, die älter als { $age } sind, können teilnehmen
+giveaway-seed = Ausgelost mit Seed `{ $seed }`
+giveaway-winners-announcement = 🎉 Glückwunsch { $winners }, ihr habt **{ $prize }** gewonnen! { $link }
+giveaway-no-entries-announcement = Niemand hat am Gewinnspiel um **{ $prize }** teilgenommen, daher gibt es keine Gewinner. { $link }
+giveaway-reroll-announcement = 🎲 { $winners } hat **{ $prize }** bei einer Neuauslosung gewonnen! { $link }
+giveaway-too-long = Gewinnspiele können höchstens { $max } laufen
+giveaway-send-failed = Ich konnte das Gewinnspiel nicht in { $channel } posten, prüfe meine Berechtigungen dort
+giveaway-create-failed = Ich konnte das Gewinnspiel nicht speichern, versuche es später noch einmal
+giveaway-started = Gewinnspiel #{ $id } in { $channel } gestartet
+giveaway-not-found = Auf diesem Server gibt es kein Gewinnspiel #{ $id }
+giveaway-already-ended = Gewinnspiel #{ $id } ist schon beendet
+giveaway-ended-now = Gewinnspiel #{ $id } beendet und die Gewinner ausgelost
+giveaway-not-ended = Gewinnspiel #{ $id } läuft noch, beende es zuerst
+giveaway-nobody-left = Alle Teilnehmer von Gewinnspiel #{ $id } haben schon gewonnen
+giveaway-rerolled = { $count } neue Gewinner für Gewinnspiel #{ $id } ausgelost
+giveaway-gone = Dieses Gewinnspiel ist beendet
+giveaway-your-entry = Deine Teilnahme
+giveaway-entered = Du nimmst am Gewinnspiel um **{ $prize }** teil, klicke noch einmal, um zurückzutreten
+giveaway-withdrawn = Du bist vom Gewinnspiel zurückgetreten
+giveaway-missing-role = Du brauchst { $role }, um an diesem Gewinnspiel teilzunehmen
+giveaway-account-too-new = Dein Konto muss älter als { $age } sein, um an diesem Gewinnspiel teilzunehmen
+giveaways-title = 🎉 Gewinnspiele
+giveaways-empty = Dieser Server hat noch keine Gewinnspiele
+giveaways-entry = `#{ $id }` [{ $prize }]({ $link }) { $ended ->
+    [true] beendet
+   *[other] endet
+} { $time }
+
+## Fun
+
+image-not-configured = Dieser Befehl ist nicht mehr eingerichtet
+image-request-failed = Fehler bei der Anfrage an die { $api }-API, versuch es später noch einmal
+
+## General
+
+avatar-title = Avatar von { $user }
+avatar-server-title = Server-Avatar von { $user }
+avatar-banner-title = Banner von { $user }
+avatar-downloads = Herunterladen
+avatar-no-server-avatar = { $user } hat keinen Server-Avatar, daher wird der Konto-Avatar gezeigt
+avatar-default = { $user } hat keinen eigenen Avatar, daher wird der Standard-Avatar von Discord gezeigt
+avatar-no-banner = { $user } hat kein Banner
+avatar-not-animated = Dieses Bild ist nicht animiert, daher gibt es kein GIF davon
+language-updated = Ich spreche ab jetzt { language-name } mit dir
+language-reset = Ich spreche wieder die Sprache des Servers mit dir
+unknown-language = Ich spreche `{ $locale }` noch nicht
+
+## Config
+
+settings-title = Servereinstellungen
+settings-updated-modlog = Modlog-Kanal aktualisiert
+settings-updated-starboard = Starboard aktualisiert
+settings-updated-cooldowns = Abklingzeiten aktualisiert
+settings-updated-language = Serversprache aktualisiert
+settings-updated-theme = Design aktualisiert
+settings-updated-prefix = Präfix aktualisiert
+settings-save-failed = Ich konnte die Einstellungen nicht speichern, versuche es später noch einmal
+settings-prefix = Präfix
+settings-prefix-summary = `{ $prefix }`{ $mention ->
+    [true] {" "}oder eine Erwähnung
+   *[false] {""}
+}
+invalid-prefix = `{ $value }` ist kein gültiges Präfix, es darf nicht leer sein oder Leerzeichen enthalten
+settings-modlog = Modlog-Kanal
+settings-starboard = Starboard
+settings-starboard-summary = { $channel }, ab { $threshold } ⭐
+settings-language = Sprache
+settings-cooldowns = Angepasste Abklingzeiten
+settings-theme = Design
+settings-theme-summary = Primär { $primary }, Erfolg { $success }, Fehler { $error }
+    Fußzeile: { $footer }
+    Zeitstempel: { $timestamps ->
+        [true] an
+       *[false] aus
+    }
+settings-not-set = Nicht gesetzt
+settings-none = Keine
+settings-cooldown-override = `/{ $command }` pro { $bucket ->
+    [user] Nutzer
+    [channel] Kanal
+   *[guild] Server
+}: { $seconds } s
+unknown-command = Es gibt keinen Befehl `/{ $command }`
+invalid-color = `{ $value }` ist keine Farbe, nutze einen Hex-Code wie `#5865F2`
+invalid-url = `{ $value }` ist kein Link
+
+## Commands
+
+cmd-ban = bannen
+    .description = Banne ein Mitglied! 🔨
+    .user = nutzer
+    .user-description = Der Nutzer, der gebannt werden soll
+    .reason = grund
+    .reason-description = Der Grund für den Bann
+cmd-kick = kicken
+    .description = Kicke ein Mitglied! 🔨
+    .user = nutzer
+    .user-description = Der Nutzer, der gekickt werden soll
+    .reason = grund
+    .reason-description = Der Grund für den Kick
+cmd-mute = stummschalten
+    .description = Schalte ein Mitglied stumm! 🔨
+    .user = nutzer
+    .user-description = Der Nutzer, der stummgeschaltet werden soll
+    .duration = dauer
+    .duration-description = Wie lange er stummgeschaltet wird, etwa 10m, 2h oder 1d
+    .reason = grund
+    .reason-description = Der Grund für die Stummschaltung
+cmd-avatar = avatar
+    .description = Zeigt deinen oder den Avatar eines anderen Nutzers! 🖼️
+    .user = nutzer
+    .user-description = Der Nutzer, dessen Avatar angezeigt werden soll
+    .show = zeigen
+    .show-description = Konto-Avatar, Server-Avatar oder Banner
+    .size = größe
+    .size-description = Bildgröße in Pixeln, aufgerundet auf eine Zweierpotenz
+    .format = format
+    .format-description = Bildformat, GIF gibt es nur für animierte Bilder
+cmd-help = hilfe
+    .description = Zeigt, was ich kann, oder die Details eines Befehls! ❓
+    .command = befehl
+    .command-description = Der Befehl, dessen Details angezeigt werden sollen
+cmd-userinfo = nutzerinfo
+    .description = Zeigt Informationen über ein Mitglied! 🪪
+    .user = nutzer
+    .user-description = Der Nutzer, der angezeigt werden soll
+cmd-serverinfo = serverinfo
+    .description = Zeigt Informationen über diesen Server! 🏠
+cmd-roleinfo = rolleninfo
+    .description = Zeigt Informationen über eine Rolle! 🏷️
+    .role = rolle
+    .role-description = Die Rolle, die angezeigt werden soll
+cmd-language = sprache
+    .description = Wähle die Sprache, in der ich mit dir spreche! 🌍
+    .locale = sprache
+    .locale-description = Deine Sprache, leer lassen für die Sprache des Servers
+cmd-config = einstellungen
+    .description = Richte den Bot für diesen Server ein! ⚙️
+cmd-config-show = anzeigen
+    .description = Zeigt die Einstellungen dieses Servers! ⚙️
+cmd-config-modlog = modlog
+    .description = Setzt oder entfernt den Kanal für Moderationsprotokolle! 📝
+    .channel = kanal
+    .channel-description = Der Modlog-Kanal, leer lassen um das Modlog zu deaktivieren
+cmd-config-starboard = starboard
+    .description = Setzt oder entfernt den Kanal, in dem Nachrichten mit Sternen geteilt werden! ⭐
+    .channel = kanal
+    .channel-description = Der Starboard-Kanal, leer lassen um das Starboard zu deaktivieren
+    .threshold = schwelle
+    .threshold-description = Wie viele Sterne eine Nachricht braucht, um geteilt zu werden
+cmd-config-cooldown = abklingzeit
+    .description = Ändert die Abklingzeit eines Befehls auf diesem Server! ⏱️
+    .command = befehl
+    .command-description = Der Befehl, dessen Abklingzeit geändert werden soll
+    .bucket = bereich
+    .bucket-description = Ob die Abklingzeit pro Nutzer, Kanal oder für den ganzen Server gilt
+    .seconds = sekunden
+    .seconds-description = Abklingzeit in Sekunden, 0 deaktiviert sie, leer lassen für den Standard
+cmd-config-language = sprache
+    .description = Setzt die Sprache dieses Servers! 🌍
+    .locale = sprache
+    .locale-description = Die Sprache des Servers, leer lassen für Englisch
+cmd-config-prefix = präfix
+    .description = Ändert das Präfix für Textbefehle auf diesem Server! ❗
+    .prefix = präfix
+    .prefix-description = Das neue Präfix, leer lassen für das Standardpräfix
+    .mention = erwähnung
+    .mention-description = Ob eine Erwähnung von mir auch als Präfix funktioniert
+cmd-config-theme = design
+    .description = Ändert das Aussehen der Embeds dieses Servers! 🎨
+cmd-config-theme-set = setzen
+    .description = Ändert Farben, Fußzeile oder Zeitstempel der Embeds dieses Servers! 🎨
+    .primary = primär
+    .primary-description = Farbe für Informationen, als Hex-Code wie #5865F2
+    .success = erfolg
+    .success-description = Farbe für ausgeführte Aktionen, als Hex-Code
+    .error = fehler
+    .error-description = Farbe für Fehler, als Hex-Code
+    .footer_text = fußzeile
+    .footer_text-description = Text am unteren Rand jedes Embeds
+    .footer_icon = fußzeilensymbol
+    .footer_icon-description = Link zu einem Symbol neben der Fußzeile
+    .timestamps = zeitstempel
+    .timestamps-description = Ob Embeds zeigen, wann sie gesendet wurden
+cmd-config-theme-reset = zurücksetzen
+    .description = Setzt das Aussehen der Embeds dieses Servers zurück! 🎨
+cmd-permissions = berechtigungen
+    .description = Lege fest, wer welche Befehle auf diesem Server benutzen darf! 🔐
+cmd-permissions-allow = erlauben
+    .description = Erlaubt einer Rolle, einem Nutzer oder Kanal einen Befehl! ✅
+    .command = befehl
+    .command-description = Der Befehl, der erlaubt werden soll
+    .role = rolle
+    .role-description = Die Rolle, für die er erlaubt wird
+    .user = nutzer
+    .user-description = Der Nutzer, für den er erlaubt wird
+    .channel = kanal
+    .channel-description = Der Kanal, in dem er erlaubt wird
+cmd-permissions-deny = verbieten
+    .description = Verbietet einer Rolle, einem Nutzer oder Kanal einen Befehl! ⛔
+    .command = befehl
+    .command-description = Der Befehl, der verboten werden soll
+    .role = rolle
+    .role-description = Die Rolle, für die er verboten wird
+    .user = nutzer
+    .user-description = Der Nutzer, für den er verboten wird
+    .channel = kanal
+    .channel-description = Der Kanal, in dem er verboten wird
+cmd-permissions-reset = zurücksetzen
+    .description = Entfernt eine Anpassung, sodass wieder die Standardberechtigungen gelten! ↩️
+    .command = befehl
+    .command-description = Der Befehl, der zurückgesetzt werden soll
+    .role = rolle
+    .role-description = Die Rolle, für die er zurückgesetzt wird
+    .user = nutzer
+    .user-description = Der Nutzer, für den er zurückgesetzt wird
+    .channel = kanal
+    .channel-description = Der Kanal, in dem er zurückgesetzt wird
+cmd-permissions-list = liste
+    .description = Zeigt die angepassten Berechtigungen dieses Servers! 🔐
+    .command = befehl
+    .command-description = Nur Anpassungen für diesen Befehl anzeigen
+cmd-remind = erinnern
+    .description = Erinnert dich später an etwas! ⏰
+cmd-remind-in = in
+    .description = Stellt eine Erinnerung! ⏰
+    .duration = dauer
+    .duration-description = Wann du erinnert werden willst, zum Beispiel 10m oder 1h30m
+    .text = text
+    .text-description = Woran du erinnert werden willst
+cmd-remind-list = liste
+    .description = Zeigt deine Erinnerungen! 📋
+cmd-remind-cancel = löschen
+    .description = Löscht eine deiner Erinnerungen! 🗑️
+    .id = id
+    .id-description = Die Erinnerung, die gelöscht werden soll
+cmd-poll = umfrage
+    .description = Stellt dem Server eine Frage! 📊
+    .question = frage
+    .question-description = Was gefragt wird
+    .options = antworten
+    .options-description = Die Antworten zur Auswahl, getrennt durch |
+    .duration = dauer
+    .duration-description = Wie lange sie läuft, standardmäßig einen Tag
+    .multi_choice = mehrfachauswahl
+    .multi_choice-description = Ob Mitglieder mehr als eine Antwort wählen können
+    .anonymous = anonym
+    .anonymous-description = Ob verborgen wird, wer wofür gestimmt hat
+cmd-giveaway = gewinnspiel
+    .description = Verlost Dinge an Mitglieder, die teilnehmen! 🎉
+cmd-giveaway-start = starten
+    .description = Startet ein Gewinnspiel, an dem Mitglieder per Knopf teilnehmen! 🎉
+    .prize = preis
+    .prize-description = Was verlost wird
+    .duration = dauer
+    .duration-description = Wie lange es läuft, zum Beispiel 1h oder 3d
+    .winners = gewinner
+    .winners-description = Wie viele Mitglieder gewinnen, standardmäßig einer
+    .channel = kanal
+    .channel-description = Der Kanal, in dem es gepostet wird
+    .required_role = benötigte_rolle
+    .required_role-description = Eine Rolle, die Mitglieder zum Teilnehmen brauchen
+    .min_account_age = mindestalter_konto
+    .min_account_age-description = Wie alt Konten zum Teilnehmen sein müssen, zum Beispiel 7d
+cmd-giveaway-end = beenden
+    .description = Beendet ein Gewinnspiel sofort und lost die Gewinner aus! 🏁
+    .giveaway = gewinnspiel
+    .giveaway-description = Das Gewinnspiel, das beendet werden soll
+cmd-giveaway-reroll = neu_auslosen
+    .description = Lost neue Gewinner für ein beendetes Gewinnspiel aus! 🎲
+    .giveaway = gewinnspiel
+    .giveaway-description = Das Gewinnspiel, das neu ausgelost werden soll
+    .winners = gewinner
+    .winners-description = Wie viele neue Gewinner ausgelost werden, standardmäßig einer
+cmd-giveaway-list = liste
+    .description = Zeigt die Gewinnspiele dieses Servers! 📋
+cmd-tag = tag
+    .description = Textbausteine, die jeder auf diesem Server posten kann! 🏷️
+cmd-tag-create = erstellen
+    .description = Erstellt einen Tag! 🏷️
+    .name = name
+    .name-description = Der Name des Tags
+    .format = format
+    .format-description = Als einfachen Text oder in einem Embed posten
+    .content = inhalt
+    .content-description = Was gepostet wird, {"{"}user{"}"}, {"{"}user.name{"}"}, {"{"}server{"}"} und {"{"}channel{"}"} werden ersetzt
+cmd-tag-edit = bearbeiten
+    .description = Ändert, was ein Tag postet! ✏️
+    .name = name
+    .name-description = Der Tag, der bearbeitet werden soll
+    .format = format
+    .format-description = Als einfachen Text oder in einem Embed posten
+    .content = inhalt
+    .content-description = Was gepostet wird, {"{"}user{"}"}, {"{"}user.name{"}"}, {"{"}server{"}"} und {"{"}channel{"}"} werden ersetzt
+cmd-tag-delete = löschen
+    .description = Löscht einen Tag! 🗑️
+    .name = name
+    .name-description = Der Tag, der gelöscht werden soll
+cmd-tag-list = liste
+    .description = Zeigt die Tags dieses Servers! 📋
+cmd-tag-info = info
+    .description = Zeigt, wer einen Tag erstellt hat und wie oft er benutzt wird! ℹ️
+    .name = name
+    .name-description = Der Tag, über den Infos angezeigt werden sollen
+cmd-tag-show = zeigen
+    .description = Postet einen Tag! 🏷️
+    .name = name
+    .name-description = Der Tag, der gepostet werden soll
+cmd-rolepanel = rollenpanel
+    .description = Rollenpanels zum Selbstbedienen! 🎭
+cmd-rolepanel-create = erstellen
+    .description = Postet ein neues Rollenpanel, füge mit /rolepanel add Rollen hinzu! 🎭
+    .kind = art
+    .kind-description = Knöpfe oder ein Auswahlmenü
+    .title = titel
+    .title-description = Der Titel des Panels
+    .channel = kanal
+    .channel-description = Der Kanal, in dem es gepostet wird
+    .description = beschreibung
+    .description-description = Text über den Rollen
+cmd-rolepanel-add = hinzufügen
+    .description = Fügt einem Panel eine Rolle hinzu oder ändert, wie sie angezeigt wird! ➕
+    .panel = panel
+    .panel-description = Das Panel, zu dem sie hinzugefügt wird
+    .role = rolle
+    .role-description = Die Rolle, die Mitglieder umschalten können
+    .label = beschriftung
+    .label-description = Der Text des Knopfs oder der Option, standardmäßig der Name der Rolle
+    .emoji = emoji
+    .emoji-description = Ein Emoji, das daneben angezeigt wird
+cmd-rolepanel-remove = entfernen
+    .description = Entfernt eine Rolle von einem Panel! ➖
+    .panel = panel
+    .panel-description = Das Panel, von dem sie entfernt wird
+    .role = rolle
+    .role-description = Die Rolle, die entfernt werden soll
+cmd-rolepanel-delete = löschen
+    .description = Löscht ein Panel samt Nachricht! 🗑️
+    .panel = panel
+    .panel-description = Das Panel, das gelöscht werden soll
+cmd-rolepanel-list = liste
+    .description = Zeigt die Rollenpanels dieses Servers! 📋
+cmd-rank = rang
+    .description = Zeigt dein Level oder das von jemand anderem! 📈
+    .user = nutzer
+    .user-description = Das Mitglied, das gezeigt wird
+cmd-leaderboard = bestenliste
+    .description = Zeigt, wer auf diesem Server die meisten XP hat! 🏆
+cmd-levels = levels
+    .description = Stellt XP und Level-Belohnungen auf diesem Server ein! 📈
+cmd-levels-show = anzeigen
+    .description = Zeigt die Level-Belohnungen und Kanäle ohne XP dieses Servers! 📈
+cmd-levels-reward = belohnung
+    .description = Gibt Mitgliedern eine Rolle, wenn sie ein Level erreichen, oder hört damit auf! 🎁
+    .role = rolle
+    .role-description = Die Rolle, die vergeben wird
+    .level = level
+    .level-description = Das Level, bei dem sie vergeben wird, leer lassen, um sie nicht mehr zu vergeben
+cmd-levels-channel = kanal
+    .description = Legt fest, ob Nachrichten in einem Kanal XP geben! 💬
+    .channel = kanal
+    .channel-description = Der Kanal, der geändert wird
+    .xp = xp
+    .xp-description = Ob Nachrichten dort XP geben
+cmd-cat = katze
+    .description = Zeigt eine süße Katze! 😻
+    .title = Hier ist eine Katze! 😻
+cmd-dog = hund
+    .description = Zeigt einen lustigen Hund! 🐶
+    .title = Hier ist ein Hund! 🐶
+cmd-duck = ente
+    .description = Zeigt eine schlaue Ente! 🦆
+    .title = Hier ist eine Ente! 🦆
+cmd-fox = fuchs
+    .description = Zeigt einen flauschigen Fuchs! 🦊
+    .title = Hier ist ein Fuchs! 🦊
+
+choice-user = Nutzer
+choice-channel = Kanal
+choice-guild = Server
+choice-account = Konto
+choice-server = Server
+choice-banner = Banner
+choice-text = Text
+choice-embed = Embed
+choice-buttons = Knöpfe
+choice-menu = Menü
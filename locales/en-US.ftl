@@ -0,0 +1,333 @@
+# Messages are looked up here when the user's language has no translation.
+# Slash command names and descriptions are taken from the code, other
+# catalogs translate them with `cmd-` messages.
+
+language-name = English
+
+error-title = Error
+restarting = I'm restarting right now, try again in a moment
+role-managed = { $role } can't be given out by me
+role-too-high = I can't give out { $role }, it has to be below my highest role
+role-above-you = You can't give out { $role }, it has to be below your highest role
+
+cooldown-title = Slow down!
+cooldown-description = You can use `/{ $command }` again { $time }
+
+## Moderation
+
+no-reason = No reason provided
+mod-error = Error handling { $action ->
+    [ban] ban
+    [kick] kick
+    [mute] mute
+   *[warn] warn
+}
+mod-higher-role = { $user } has a higher role than { $who ->
+    [both] you and I
+    [author] you
+   *[bot] me
+}, or is the owner of the server, so I cannot { $action ->
+    [ban] ban
+    [kick] kick
+    [mute] mute
+   *[warn] warn
+} them.
+mod-dm-title = You have been { $action ->
+    [ban] banned
+    [kick] kicked
+    [mute] muted
+   *[warn] warned
+} from { $server }
+mod-action-title = { $action ->
+    [ban] Banned
+    [kick] Kicked
+    [mute] Muted
+   *[warn] Warned
+} { $user }
+mod-field-user = User
+mod-field-reason = Reason
+mod-field-duration = Duration
+invalid-duration = `{ $value }` is not a valid duration, try something like 10m, 2h or 1d 12h
+mute-too-long = Members can be muted for at most 28 days
+
+## Info
+
+userinfo-title = About { $user }
+serverinfo-title = About { $server }
+roleinfo-title = About { $role }
+info-none = None
+info-yes-no = { $value ->
+    [true] Yes
+   *[false] No
+}
+info-created = Created
+info-joined = Joined
+info-highest-role = Highest role
+info-roles = Roles ({ $count })
+info-key-permissions = Key permissions
+info-timeout = Timeout
+info-not-timed-out = Not timed out
+info-timed-out-until = Ends { $time }
+info-infractions = Infractions
+info-owner = Owner
+info-members = Members
+info-cached-members = Members (cached)
+info-channels = Channels
+info-channel-counts = { $text } text, { $voice } voice, { $categories } categories
+info-emojis = Emojis
+info-boosts = Boosts
+info-boost-summary = { $count } (level { $tier })
+info-features = Features
+info-color = Color
+info-position = Position
+info-mentionable = Mentionable
+info-hoisted = Shown separately
+info-permissions = Permissions
+
+## Help
+
+help-title = Commands
+help-description = Use `/help <command>` to see more about a command. Text commands start with `{ $prefix }`.
+help-category-fun = Fun
+help-category-general = General
+help-category-moderation = Moderation
+help-category-config = Configuration
+help-category-permissions = Permissions
+help-category-reminders = Reminders
+help-category-polls = Polls
+help-category-giveaways = Giveaways
+help-category-tags = Tags
+help-category-roles = Roles
+help-category-levels = Levels
+help-usage = Usage
+help-parameters = Options
+help-optional = (optional)
+help-permissions = Permissions
+help-anyone = Anyone
+help-cooldowns = Cooldowns
+help-no-cooldown = None
+help-cooldown = { $seconds }s per { $bucket }
+help-subcommands = Subcommands
+
+## Permissions
+
+permission-denied = You can't use `/{ $command }` here
+permission-missing = You need the { $permissions } permission to use `/{ $command }`
+permission-one-target = Pick exactly one role, user or channel
+permission-protected = Access to `/{ $command }` can't be changed
+permissions-title = Permission overrides
+permissions-updated = Updated permission overrides
+permissions-empty = No overrides yet
+permissions-rule = { $allow ->
+    [true] Allowed
+   *[false] Denied
+} `/{ $command }` for { $target }
+
+## Reminders
+
+reminder-title = Reminder
+reminder-set = Set { $time }
+reminder-late = I was offline when this was due { $time }, sorry for the wait
+reminder-added = I'll remind you { $time } (reminder #{ $id })
+reminder-add-failed = I couldn't save that reminder, try again later
+reminder-too-long = Reminders can be set at most a year ahead
+reminder-limit = You can have at most { $max } reminders at once, cancel one first
+reminder-not-found = You have no reminder #{ $id }
+reminder-cancelled = Cancelled reminder #{ $id }
+reminders-title = Your reminders
+reminders-empty = You have no reminders, set one with `/remind in`
+reminders-entry = `#{ $id }` { $time }: { $text }
+
+## Tags
+
+tag-created = Created tag { $name }
+tag-updated = Updated tag { $name }
+tag-deleted = Deleted tag { $name }
+tag-not-found = There is no `{ $name }` tag
+tag-exists = There already is a `{ $name }` tag
+tag-invalid-name = `{ $name }` can't be a tag name, use up to { $max } letters, digits, - and _
+tag-command-name = `{ $name }` is already a command
+tags-title = Tags ({ $count })
+tags-empty = This server has no tags yet, moderators can create them with `/tag create`
+tag-info-title = Tag { $name }
+tag-owner = Owner
+tag-uses = Uses
+tag-format = Format
+tag-format-value = { $embed ->
+    [true] Embed
+   *[false] Text
+}
+tag-created-at = Created
+
+## Role panels
+
+rolepanel-created = Created role panel #{ $id }, add roles to it with `/rolepanel add`
+rolepanel-updated = Updated role panel #{ $id }
+rolepanel-deleted = Deleted role panel #{ $id }
+rolepanel-not-found = There is no role panel #{ $id }
+rolepanel-send-failed = I couldn't post in { $channel }
+rolepanel-create-failed = I couldn't save the role panel, try again later
+rolepanel-message-missing = I couldn't update the panel's message, it may have been deleted
+rolepanel-full = Role panels can have at most { $max } roles
+rolepanel-role-missing = { $role } isn't on role panel #{ $id }
+rolepanel-invalid-emoji = `{ $emoji }` isn't an emoji
+rolepanel-no-roles = No roles yet
+rolepanel-placeholder = Pick roles to add or remove
+rolepanel-gone = This role panel doesn't exist anymore
+rolepanel-your-roles = Your roles
+rolepanel-added = Gave you { $role }
+rolepanel-removed = Took away { $role }
+rolepanel-refused = I couldn't change { $role }
+rolepanel-nothing = Nothing changed
+rolepanels-title = Role panels
+rolepanels-empty = This server has no role panels yet
+rolepanels-entry = `#{ $id }` [{ $title }]({ $link }), { $count } roles
+
+## Levels
+
+rank-title = { $user }'s level
+rank-progress = { $xp } / { $needed } XP to level { $next }
+rank-level = Level
+rank-rank = Rank
+rank-xp = Total XP
+rank-position = #{ $rank }
+rank-unranked = Unranked
+leaderboard-title = Leaderboard, page { $page } of { $pages }
+leaderboard-empty = Nobody has earned XP here yet
+leaderboard-entry = **#{ $rank }** <@{ $user }>, level { $level } ({ $xp } XP)
+levels-title = Level settings
+levels-updated-rewards = Updated level rewards
+levels-updated-channels = Updated channels without XP
+levels-summary = Members earn XP for at most one message every { $seconds } seconds
+levels-rewards = Role rewards
+levels-reward = Level { $level }: { $role }
+levels-no-xp-channels = Channels without XP
+levels-no-reward = { $role } isn't a level reward
+
+## Starboard
+
+starboard-stars = ⭐ **{ $stars }** in { $channel }
+starboard-jump = [Jump to message]({ $link })
+
+## Polls
+
+poll-title = 📊 Poll
+poll-results-title = 📊 Poll results
+poll-closes = Closes { $time }
+poll-closed = Closed { $time }
+poll-voters = { $count } voted
+poll-multi-choice = You can pick more than one answer
+poll-anonymous = Votes are anonymous
+poll-result = { $bar } { $count } ({ $percent }%)
+poll-invalid-options = Give 2 to { $max } different answers separated by `|`, each up to { $length } characters
+poll-too-long = Polls can run for at most { $max }
+poll-create-failed = I couldn't save the poll, try again later
+poll-gone = This poll is closed
+poll-your-vote = Your vote
+poll-voted = You voted for { $options }, click again to take it back
+poll-vote-removed = You took back your vote
+poll-vote-failed = I couldn't save your vote, try again later
+
+## Giveaways
+
+giveaway-title = 🎉 Giveaway
+giveaway-enter = Enter
+giveaway-ends = Ends { $time }
+giveaway-ended = Ended { $time }
+giveaway-winner-count = { $count ->
+    [one] 1 winner
+   *[other] { $count } winners
+}
+giveaway-winners = Winners: { $winners }
+giveaway-no-winners = Nobody entered, so nobody won
+giveaway-host = Hosted by { $host }
+giveaway-entries = { $count } entered
+giveaway-requires-role = Only members with { $role } can enter
+giveaway-requires-age = Only accounts older than { $age } can enter
+giveaway-seed = Drawn with seed `{ $seed }`
+giveaway-winners-announcement = 🎉 Congratulations { $winners }, you won **{ $prize }**! { $link }
+giveaway-no-entries-announcement = Nobody entered the giveaway for **{ $prize }**, so there are no winners. { $link }
+giveaway-reroll-announcement = 🎲 { $winners } won **{ $prize }** in a reroll! { $link }
+giveaway-too-long = Giveaways can run for at most { $max }
+giveaway-send-failed = I couldn't post the giveaway in { $channel }, check my permissions there
+giveaway-create-failed = I couldn't save the giveaway, try again later
+giveaway-started = Started giveaway #{ $id } in { $channel }
+giveaway-not-found = There is no giveaway #{ $id } on this server
+giveaway-already-ended = Giveaway #{ $id } has already ended
+giveaway-ended-now = Ended giveaway #{ $id } and drew its winners
+giveaway-not-ended = Giveaway #{ $id } hasn't ended yet, end it first
+giveaway-nobody-left = Everyone who entered giveaway #{ $id } has already won
+giveaway-rerolled = Drew { $count } new { $count ->
+    [one] winner
+   *[other] winners
+} for giveaway #{ $id }
+giveaway-gone = This giveaway has ended
+giveaway-your-entry = Your entry
+giveaway-entered = You entered the giveaway for **{ $prize }**, click again to withdraw
+giveaway-withdrawn = You withdrew from the giveaway
+giveaway-missing-role = You need { $role } to enter this giveaway
+giveaway-account-too-new = Your account has to be older than { $age } to enter this giveaway
+giveaways-title = 🎉 Giveaways
+giveaways-empty = This server has no giveaways yet
+giveaways-entry = `#{ $id }` [{ $prize }]({ $link }) { $ended ->
+    [true] ended
+   *[other] ends
+} { $time }
+
+## Fun
+
+image-not-configured = This command is not configured anymore
+image-request-failed = Error handling web request to { $api } API, try again later
+
+## General
+
+avatar-title = { $user }'s avatar
+avatar-server-title = { $user }'s server avatar
+avatar-banner-title = { $user }'s banner
+avatar-downloads = Download
+avatar-no-server-avatar = { $user } has no server avatar, so this is their account avatar
+avatar-default = { $user } has no custom avatar, so this is Discord's default one
+avatar-no-banner = { $user } has no banner
+avatar-not-animated = This picture isn't animated, so there is no GIF of it
+language-updated = I'll talk to you in { language-name } from now on
+language-reset = I'll use the server's language with you again
+unknown-language = I don't speak `{ $locale }` yet
+
+## Config
+
+settings-title = Server settings
+settings-updated-modlog = Updated modlog channel
+settings-updated-starboard = Updated starboard
+settings-updated-cooldowns = Updated cooldowns
+settings-updated-language = Updated server language
+settings-updated-theme = Updated theme
+settings-updated-prefix = Updated the prefix
+settings-save-failed = I couldn't save the settings, try again later
+settings-prefix = Prefix
+settings-prefix-summary = `{ $prefix }`{ $mention ->
+    [true] {" "}or a mention
+   *[false] {""}
+}
+invalid-prefix = `{ $value }` is not a valid prefix, it can't be empty or contain spaces
+settings-modlog = Modlog channel
+settings-starboard = Starboard
+settings-starboard-summary = { $channel }, at { $threshold } ⭐
+settings-language = Language
+settings-cooldowns = Cooldown overrides
+settings-theme = Theme
+settings-theme-summary = Primary { $primary }, success { $success }, error { $error }
+    Footer: { $footer }
+    Timestamps: { $timestamps ->
+        [true] on
+       *[false] off
+    }
+settings-not-set = Not set
+settings-none = None
+settings-cooldown-override = `/{ $command }` per { $bucket ->
+    [user] user
+    [channel] channel
+   *[guild] server
+}: { $seconds }s
+unknown-command = There is no `/{ $command }` command
+invalid-color = `{ $value }` isn't a color, use a hex code like `#5865F2`
+invalid-url = `{ $value }` isn't a link
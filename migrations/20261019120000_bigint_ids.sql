@@ -0,0 +1,21 @@
+ALTER TABLE guilds
+    ALTER COLUMN id TYPE BIGINT USING id::BIGINT,
+    ALTER COLUMN mod_id TYPE BIGINT USING mod_id::BIGINT,
+    ALTER COLUMN audit_id TYPE BIGINT USING audit_id::BIGINT,
+    ALTER COLUMN welcome_id TYPE BIGINT USING welcome_id::BIGINT;
+
+ALTER TABLE infractions
+    ALTER COLUMN guild_id TYPE BIGINT USING guild_id::BIGINT,
+    ALTER COLUMN member_id TYPE BIGINT USING member_id::BIGINT,
+    ALTER COLUMN moderator_id TYPE BIGINT USING moderator_id::BIGINT;
+
+-- Older rows may reference guilds that were never inserted
+INSERT INTO guilds (id)
+SELECT DISTINCT guild_id FROM infractions
+ON CONFLICT (id) DO NOTHING;
+
+ALTER TABLE infractions
+    ADD CONSTRAINT infractions_guild_id_fkey
+    FOREIGN KEY (guild_id) REFERENCES guilds (id);
+
+CREATE INDEX infractions_guild_id_member_id_idx ON infractions (guild_id, member_id);
@@ -0,0 +1,21 @@
+CREATE TYPE cooldown_bucket AS ENUM ('user', 'channel', 'guild');
+
+CREATE TABLE cooldown_policies (
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    command VARCHAR(100) NOT NULL,
+    bucket cooldown_bucket NOT NULL,
+    seconds INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, command, bucket)
+);
+
+-- Only cooldowns long enough to matter across restarts are stored here.
+-- scope_id is the guild the command was used in, or 0 in DMs. target_id is
+-- the user, channel or guild ID, depending on the bucket.
+CREATE TABLE cooldowns (
+    scope_id BIGINT NOT NULL,
+    command VARCHAR(100) NOT NULL,
+    bucket cooldown_bucket NOT NULL,
+    target_id BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (scope_id, command, bucket, target_id)
+);
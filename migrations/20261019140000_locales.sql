@@ -0,0 +1,6 @@
+ALTER TABLE guilds ADD COLUMN locale VARCHAR(10);
+
+CREATE TABLE user_settings (
+    id BIGINT PRIMARY KEY,
+    locale VARCHAR(10)
+);
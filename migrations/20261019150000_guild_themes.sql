@@ -0,0 +1,8 @@
+-- NULL colors use the bot's defaults
+ALTER TABLE guilds
+    ADD COLUMN primary_color INTEGER,
+    ADD COLUMN success_color INTEGER,
+    ADD COLUMN error_color INTEGER,
+    ADD COLUMN footer_text VARCHAR(2048),
+    ADD COLUMN footer_icon VARCHAR(2048),
+    ADD COLUMN timestamps BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,10 @@
+CREATE TYPE permission_target AS ENUM ('role', 'user', 'channel');
+
+CREATE TABLE permission_overrides (
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    command VARCHAR(100) NOT NULL,
+    target permission_target NOT NULL,
+    target_id BIGINT NOT NULL,
+    allow BOOLEAN NOT NULL,
+    PRIMARY KEY (guild_id, command, target, target_id)
+);
@@ -0,0 +1,4 @@
+-- NULL uses the bot's default prefix
+ALTER TABLE guilds
+    ADD COLUMN prefix VARCHAR(32),
+    ADD COLUMN mention_prefix BOOLEAN NOT NULL DEFAULT TRUE;
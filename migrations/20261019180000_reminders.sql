@@ -0,0 +1,14 @@
+CREATE TABLE reminders (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    -- Not a foreign key, reminders can be set in DMs
+    guild_id BIGINT,
+    -- NULL delivers the reminder by DM
+    channel_id BIGINT,
+    text VARCHAR(1000) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    remind_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX reminders_user_id ON reminders (user_id);
+CREATE INDEX reminders_remind_at ON reminders (remind_at);
@@ -0,0 +1,10 @@
+CREATE TABLE tags (
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    name VARCHAR(32) NOT NULL,
+    content VARCHAR(2000) NOT NULL,
+    embed BOOLEAN NOT NULL DEFAULT FALSE,
+    owner_id BIGINT NOT NULL,
+    uses BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (guild_id, name)
+);
@@ -0,0 +1,22 @@
+CREATE TYPE role_panel_kind AS ENUM ('buttons', 'menu');
+
+CREATE TABLE role_panels (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    channel_id BIGINT NOT NULL,
+    message_id BIGINT NOT NULL,
+    kind role_panel_kind NOT NULL,
+    title VARCHAR(256) NOT NULL,
+    description VARCHAR(2000)
+);
+
+CREATE INDEX role_panels_guild_id ON role_panels (guild_id);
+
+CREATE TABLE role_panel_roles (
+    panel_id BIGINT NOT NULL REFERENCES role_panels (id) ON DELETE CASCADE,
+    role_id BIGINT NOT NULL,
+    label VARCHAR(80) NOT NULL,
+    emoji VARCHAR(100),
+    position INTEGER NOT NULL,
+    PRIMARY KEY (panel_id, role_id)
+);
@@ -0,0 +1,19 @@
+-- Messages in these channels give no XP
+ALTER TABLE guilds
+    ADD COLUMN no_xp_channels BIGINT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE levels (
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    user_id BIGINT NOT NULL,
+    xp BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE INDEX levels_leaderboard ON levels (guild_id, xp DESC);
+
+CREATE TABLE level_rewards (
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    role_id BIGINT NOT NULL,
+    level INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, role_id)
+);
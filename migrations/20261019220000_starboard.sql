@@ -0,0 +1,14 @@
+-- NULL turns the starboard off
+ALTER TABLE guilds
+    ADD COLUMN starboard_id BIGINT,
+    ADD COLUMN starboard_threshold INTEGER NOT NULL DEFAULT 3;
+
+CREATE TABLE starboard_posts (
+    message_id BIGINT PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    channel_id BIGINT NOT NULL,
+    -- Where the repost went, which stays the same if the starboard is moved
+    starboard_channel_id BIGINT NOT NULL,
+    starboard_message_id BIGINT NOT NULL,
+    stars INTEGER NOT NULL
+);
@@ -0,0 +1,28 @@
+CREATE TABLE polls (
+    -- Votes come in as button clicks on this message
+    message_id BIGINT PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    channel_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    question VARCHAR(256) NOT NULL,
+    options VARCHAR(80)[] NOT NULL,
+    multi_choice BOOLEAN NOT NULL,
+    anonymous BOOLEAN NOT NULL,
+    closes_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (message_id, multi_choice)
+);
+
+CREATE INDEX polls_closes_at ON polls (closes_at);
+
+CREATE TABLE poll_votes (
+    message_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    option SMALLINT NOT NULL,
+    -- Votes carry their poll's kind so the schema can allow one vote per member
+    -- on single choice polls
+    multi_choice BOOLEAN NOT NULL,
+    PRIMARY KEY (message_id, user_id, option),
+    FOREIGN KEY (message_id, multi_choice) REFERENCES polls (message_id, multi_choice) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX poll_votes_single_choice ON poll_votes (message_id, user_id) WHERE NOT multi_choice;
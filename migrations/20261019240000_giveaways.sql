@@ -0,0 +1,38 @@
+CREATE TABLE giveaways (
+    id BIGSERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL REFERENCES guilds (id),
+    channel_id BIGINT NOT NULL,
+    -- Entries come in as button clicks on this message
+    message_id BIGINT NOT NULL UNIQUE,
+    host_id BIGINT NOT NULL,
+    prize VARCHAR(256) NOT NULL,
+    winner_count INTEGER NOT NULL,
+    -- Entry requirements, NULL for none
+    required_role_id BIGINT,
+    min_account_age_secs BIGINT,
+    ends_at TIMESTAMPTZ NOT NULL,
+    -- Ended giveaways are kept so they can be rerolled
+    ended BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX giveaways_guild_id ON giveaways (guild_id);
+CREATE INDEX giveaways_ends_at ON giveaways (ends_at) WHERE NOT ended;
+
+CREATE TABLE giveaway_entries (
+    giveaway_id BIGINT NOT NULL REFERENCES giveaways (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL,
+    PRIMARY KEY (giveaway_id, user_id)
+);
+
+-- Every draw is kept, so any of them can be reproduced from its seed
+CREATE TABLE giveaway_draws (
+    id BIGSERIAL PRIMARY KEY,
+    giveaway_id BIGINT NOT NULL REFERENCES giveaways (id) ON DELETE CASCADE,
+    seed BIGINT NOT NULL,
+    -- Winners of earlier draws, who couldn't be drawn again
+    excluded_ids BIGINT[] NOT NULL,
+    winner_ids BIGINT[] NOT NULL,
+    drawn_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX giveaway_draws_giveaway_id ON giveaway_draws (giveaway_id);
@@ -0,0 +1,79 @@
+use poise::serenity_prelude::{GuildId, ImageHash, UserId};
+
+const CDN_URL: &str = "https://cdn.discordapp.com";
+
+/// Formats Discord's CDN serves user images in.
+#[derive(poise::ChoiceParameter, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[name = "PNG"]
+    Png,
+    #[name = "WEBP"]
+    Webp,
+    #[name = "GIF"]
+    Gif,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Webp => "webp",
+            Self::Gif => "gif",
+        }
+    }
+}
+
+/// Rounds a requested size up to one the CDN accepts, a power of two from 16 to 4096.
+pub fn image_size(size: u16) -> u16 {
+    size.clamp(16, 4096).next_power_of_two()
+}
+
+/// A custom image a user has uploaded.
+pub enum UserImage {
+    Avatar(UserId, ImageHash),
+    /// An avatar only shown in one guild.
+    MemberAvatar(GuildId, UserId, ImageHash),
+    Banner(UserId, ImageHash),
+}
+
+impl UserImage {
+    fn hash(&self) -> &ImageHash {
+        match self {
+            Self::Avatar(_, hash) | Self::MemberAvatar(_, _, hash) | Self::Banner(_, hash) => hash,
+        }
+    }
+
+    /// The formats the image is available in. Only animated images have a GIF.
+    pub fn formats(&self) -> Vec<ImageFormat> {
+        if self.hash().is_animated() {
+            vec![ImageFormat::Png, ImageFormat::Webp, ImageFormat::Gif]
+        } else {
+            vec![ImageFormat::Png, ImageFormat::Webp]
+        }
+    }
+
+    /// GIF for animated images, PNG otherwise.
+    pub fn default_format(&self) -> ImageFormat {
+        if self.hash().is_animated() {
+            ImageFormat::Gif
+        } else {
+            ImageFormat::Png
+        }
+    }
+
+    pub fn url(&self, format: ImageFormat, size: u16) -> String {
+        let path = match self {
+            Self::Avatar(user_id, hash) => format!("avatars/{user_id}/{hash}"),
+            Self::MemberAvatar(guild_id, user_id, hash) => {
+                format!("guilds/{guild_id}/users/{user_id}/avatars/{hash}")
+            }
+            Self::Banner(user_id, hash) => format!("banners/{user_id}/{hash}"),
+        };
+
+        format!(
+            "{CDN_URL}/{path}.{}?size={}",
+            format.extension(),
+            image_size(size)
+        )
+    }
+}
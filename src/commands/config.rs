@@ -0,0 +1,476 @@
+use super::{autocomplete_command, command_names};
+use crate::{
+    database::get_database,
+    embeds::{hex, EmbedKind},
+    i18n::{autocomplete_locale, get_lang, Lang, DEFAULT_LOCALE},
+    structs::{Command, Context, CooldownBucket, CooldownPolicy, Error, Guild, Theme},
+    utils::{get_cooldowns, get_locales, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{ChannelId, Color, CreateEmbed, CreateEmbedAuthor, GuildChannel},
+    ChoiceParameter, CreateReply,
+};
+use std::time::Duration;
+
+fn channel_mention(lang: &Lang, channel_id: Option<ChannelId>) -> String {
+    channel_id.map_or_else(|| lang.tr("settings-not-set"), |id| format!("<#{id}>"))
+}
+
+fn cooldown_list(lang: &Lang, policies: &[CooldownPolicy]) -> String {
+    if policies.is_empty() {
+        return lang.tr("settings-none");
+    }
+
+    policies
+        .iter()
+        .map(|p| {
+            lang.tr_with(
+                "settings-cooldown-override",
+                &fluent_args![
+                    "command" => p.command.as_str(),
+                    "bucket" => p.bucket.name().to_lowercase(),
+                    "seconds" => p.duration.as_secs(),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn theme_summary(lang: &Lang, theme: &Theme) -> String {
+    lang.tr_with(
+        "settings-theme-summary",
+        &fluent_args![
+            "primary" => hex(theme.color(EmbedKind::Primary)),
+            "success" => hex(theme.color(EmbedKind::Success)),
+            "error" => hex(theme.color(EmbedKind::Error)),
+            "footer" => theme.footer_text.clone().unwrap_or_else(|| lang.tr("settings-none")),
+            "timestamps" => theme.timestamps.to_string(),
+        ],
+    )
+}
+
+/// Parses hex colors such as `#5865F2` or `5865f2`.
+pub fn parse_color(value: &str) -> Option<Color> {
+    let digits = value.trim().trim_start_matches('#');
+
+    if digits.len() != 6 {
+        return None;
+    }
+
+    u32::from_str_radix(digits, 16).ok().map(Color::new)
+}
+
+/// Saves the settings, telling the user if that failed so they don't think the
+/// change went through.
+pub async fn save_settings(ctx: Context<'_>, guild: &Guild) -> bool {
+    let db = get_database(ctx.serenity_context()).await;
+
+    if db.update_settings(guild).await {
+        return true;
+    }
+
+    let lang = get_lang(ctx).await;
+    send_error_msg(ctx, &lang.tr("settings-save-failed")).await;
+    false
+}
+
+/// Settings embeds are drawn with the guild's theme, so changes to it show right away.
+async fn settings_embed(
+    ctx: Context<'_>,
+    kind: EmbedKind,
+    title: &str,
+    guild: &Guild,
+) -> CreateEmbed {
+    let cooldowns = get_cooldowns(ctx.serenity_context()).await;
+    let policies = cooldowns.policies(guild.id).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+    let lang = get_lang(ctx).await;
+
+    let locale = guild.locale.as_deref().unwrap_or(DEFAULT_LOCALE);
+
+    guild
+        .theme
+        .embed(kind)
+        .author(CreateEmbedAuthor::new(lang.tr(title)).icon_url(ctx.author().face()))
+        .field(
+            lang.tr("settings-modlog"),
+            channel_mention(&lang, guild.mod_id),
+            true,
+        )
+        .field(
+            lang.tr("settings-starboard"),
+            lang.tr_with(
+                "settings-starboard-summary",
+                &fluent_args![
+                    "channel" => channel_mention(&lang, guild.starboard_id),
+                    "threshold" => guild.starboard_threshold,
+                ],
+            ),
+            true,
+        )
+        .field(
+            lang.tr("settings-language"),
+            locales.get(locale, "language-name", None),
+            true,
+        )
+        .field(
+            lang.tr("settings-prefix"),
+            lang.tr_with(
+                "settings-prefix-summary",
+                &fluent_args![
+                    "prefix" => guild.prefix.as_deref().unwrap_or(&ctx.data().default_prefix),
+                    "mention" => guild.mention_prefix.to_string(),
+                ],
+            ),
+            true,
+        )
+        .field(
+            lang.tr("settings-cooldowns"),
+            cooldown_list(&lang, &policies),
+            false,
+        )
+        .field(
+            lang.tr("settings-theme"),
+            theme_summary(&lang, &guild.theme),
+            false,
+        )
+}
+
+/// Configure the bot for this server! ⚙️
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands(
+        "show",
+        "modlog",
+        "starboard",
+        "cooldown",
+        "language",
+        "prefix",
+        "theme"
+    ),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn config(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Shows this server's settings! ⚙️
+#[poise::command(slash_command, prefix_command)]
+async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    let db = get_database(ctx.serenity_context()).await;
+    let guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(settings_embed(ctx, EmbedKind::Primary, "settings-title", &guild).await),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets or clears the channel moderation actions are logged to! 📝
+#[poise::command(slash_command, prefix_command)]
+async fn modlog(
+    ctx: Context<'_>,
+    #[description = "The modlog channel, leave empty to disable the modlog"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    guild.mod_id = channel.map(|c| c.id);
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            settings_embed(ctx, EmbedKind::Success, "settings-updated-modlog", &guild).await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets or clears the channel starred messages are reposted to! ⭐
+#[poise::command(slash_command, prefix_command)]
+async fn starboard(
+    ctx: Context<'_>,
+    #[description = "The starboard channel, leave empty to disable the starboard"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+    #[description = "Stars a message needs to be reposted"]
+    #[min = 1]
+    #[max = 100]
+    threshold: Option<i32>,
+) -> Result<(), Error> {
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    guild.starboard_id = channel.map(|c| c.id);
+    if let Some(threshold) = threshold {
+        guild.starboard_threshold = threshold;
+    }
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            settings_embed(
+                ctx,
+                EmbedKind::Success,
+                "settings-updated-starboard",
+                &guild,
+            )
+            .await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Overrides or resets a command's cooldown in this server! ⏱️
+#[poise::command(slash_command, prefix_command)]
+async fn cooldown(
+    ctx: Context<'_>,
+    #[description = "The command to change the cooldown of"]
+    #[autocomplete = "autocomplete_command"]
+    command: String,
+    #[description = "Whether the cooldown applies per user, channel or the whole server"]
+    bucket: CooldownBucket,
+    #[description = "Cooldown in seconds, 0 disables it, leave empty to use the default"]
+    #[max = 604_800]
+    seconds: Option<u32>,
+) -> Result<(), Error> {
+    if !command_names(&ctx.framework().options().commands).contains(&command) {
+        let lang = get_lang(ctx).await;
+        let msg = lang.tr_with("unknown-command", &fluent_args!["command" => command]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().unwrap();
+    let cooldowns = get_cooldowns(ctx.serenity_context()).await;
+
+    match seconds {
+        Some(seconds) => {
+            cooldowns
+                .set_policy(&CooldownPolicy {
+                    guild_id,
+                    command,
+                    bucket,
+                    duration: Duration::from_secs(seconds.into()),
+                })
+                .await;
+        }
+        None => cooldowns.remove_policy(guild_id, &command, bucket).await,
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    let guild = db.get_settings(guild_id).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            settings_embed(
+                ctx,
+                EmbedKind::Success,
+                "settings-updated-cooldowns",
+                &guild,
+            )
+            .await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets the language for this server! 🌍
+#[poise::command(slash_command, prefix_command)]
+async fn language(
+    ctx: Context<'_>,
+    #[description = "The server's language, leave empty to use English"]
+    #[autocomplete = "autocomplete_locale"]
+    locale: Option<String>,
+) -> Result<(), Error> {
+    let locales = get_locales(ctx.serenity_context()).await;
+
+    if let Some(locale) = locale.as_deref().filter(|l| !locales.is_supported(l)) {
+        let lang = get_lang(ctx).await;
+        let msg = lang.tr_with("unknown-language", &fluent_args!["locale" => locale]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    guild.locale = locale;
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            settings_embed(ctx, EmbedKind::Success, "settings-updated-language", &guild).await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Changes the prefix for text commands in this server! ❗
+#[poise::command(slash_command, prefix_command)]
+async fn prefix(
+    ctx: Context<'_>,
+    #[description = "The new prefix, leave empty to use the default"]
+    #[max_length = 32]
+    prefix: Option<String>,
+    #[description = "Whether mentioning me also works as a prefix"] mention: Option<bool>,
+) -> Result<(), Error> {
+    if let Some(prefix) = prefix
+        .as_deref()
+        .filter(|p| p.is_empty() || p.chars().count() > 32 || p.contains(char::is_whitespace))
+    {
+        let lang = get_lang(ctx).await;
+        let msg = lang.tr_with("invalid-prefix", &fluent_args!["value" => prefix]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    guild.prefix = prefix;
+
+    if let Some(mention) = mention {
+        guild.mention_prefix = mention;
+    }
+
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            settings_embed(ctx, EmbedKind::Success, "settings-updated-prefix", &guild).await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Changes how this server's embeds look! 🎨
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("theme_set", "theme_reset"),
+    subcommand_required
+)]
+#[allow(clippy::unused_async)]
+async fn theme(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Changes the colors, footer or timestamps of this server's embeds! 🎨
+#[poise::command(slash_command, prefix_command, rename = "set")]
+async fn theme_set(
+    ctx: Context<'_>,
+    #[description = "Color for information, as a hex code like #5865F2"] primary: Option<String>,
+    #[description = "Color for completed actions, as a hex code"] success: Option<String>,
+    #[description = "Color for errors, as a hex code"] error: Option<String>,
+    #[description = "Text shown at the bottom of every embed"]
+    #[max_length = 2048]
+    footer_text: Option<String>,
+    #[description = "Link to an icon shown next to the footer text"] footer_icon: Option<String>,
+    #[description = "Whether embeds show when they were sent"] timestamps: Option<bool>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    for (value, slot) in [
+        (primary, &mut guild.theme.primary),
+        (success, &mut guild.theme.success),
+        (error, &mut guild.theme.error),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+
+        let Some(color) = parse_color(&value) else {
+            let msg = lang.tr_with("invalid-color", &fluent_args!["value" => value]);
+            send_error_msg(ctx, &msg).await;
+            return Ok(());
+        };
+
+        *slot = Some(color);
+    }
+
+    if let Some(icon) = footer_icon {
+        if !icon.starts_with("https://") && !icon.starts_with("http://") {
+            let msg = lang.tr_with("invalid-url", &fluent_args!["value" => icon]);
+            send_error_msg(ctx, &msg).await;
+            return Ok(());
+        }
+
+        guild.theme.footer_icon = Some(icon);
+    }
+
+    if footer_text.is_some() {
+        guild.theme.footer_text = footer_text;
+    }
+
+    if let Some(timestamps) = timestamps {
+        guild.theme.timestamps = timestamps;
+    }
+
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(settings_embed(ctx, EmbedKind::Success, "settings-updated-theme", &guild).await),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Goes back to the default look for this server's embeds! 🎨
+#[poise::command(slash_command, prefix_command, rename = "reset")]
+async fn theme_reset(ctx: Context<'_>) -> Result<(), Error> {
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(ctx.guild_id().unwrap()).await;
+
+    guild.theme = Theme::default();
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(settings_embed(ctx, EmbedKind::Success, "settings-updated-theme", &guild).await),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 1] {
+    [config()]
+}
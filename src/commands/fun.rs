@@ -1,138 +1,68 @@
 use crate::{
-    structs::{Command, Context, Error, PetResponse},
-    utils::{get_reqwest_client, send_error_msg},
-};
-use poise::{
-    serenity_prelude::{Color, CreateEmbed, CreateEmbedAuthor},
-    CreateReply,
+    embeds::{embed, EmbedKind},
+    i18n::get_lang,
+    providers::ProviderConfig,
+    structs::{Command, Context, Error},
+    utils::{get_image_providers, get_reqwest_client, send_error_msg},
 };
+use fluent::fluent_args;
+use poise::{serenity_prelude::CreateEmbedAuthor, CreateReply};
 use tracing::error;
 
-/// Shows a cute cat! 😻
-#[poise::command(slash_command)]
-async fn cat(ctx: Context<'_>) -> Result<(), Error> {
-    let client = get_reqwest_client(ctx.serenity_context()).await;
+// Template for the image commands, which are generated from the image
+// provider config. The provider is looked up by the invoked command's name.
+/// Shows a random picture! 🖼️
+#[poise::command(slash_command, prefix_command, user_cooldown = 3)]
+async fn image(ctx: Context<'_>) -> Result<(), Error> {
+    let providers = get_image_providers(ctx.serenity_context()).await;
+    let lang = get_lang(ctx).await;
 
-    let request = client
-        .get("https://api.thecatapi.com/v1/images/search")
-        .send()
-        .await;
+    let Some(provider) = providers.get(&ctx.command().name) else {
+        send_error_msg(ctx, &lang.tr("image-not-configured")).await;
+        return Ok(());
+    };
 
-    match request {
-        Ok(res) => match res.json::<Vec<PetResponse>>().await {
-            Ok(res) => {
-                ctx.send(
-                    CreateReply::default().embed(
-                        CreateEmbed::new()
-                            .author(
-                                CreateEmbedAuthor::new("Here's a cat! 😻")
-                                    .icon_url(ctx.author().face()),
-                            )
-                            .image(res[0].url.to_string())
-                            .color(Color::BLUE),
-                    ),
-                )
-                .await?;
-            }
-            Err(e) => {
-                error!("Error: {}", e);
-                send_error_msg(ctx, "Error handing web request to cat API, try again later").await;
-            }
-        },
-        Err(e) => {
-            error!("Error: {}", e);
-            send_error_msg(ctx, "Error handing web request to cat API, try again later").await;
-        }
-    }
-
-    Ok(())
-}
-
-/// Shows a funny dog! 🐶
-#[poise::command(slash_command)]
-async fn dog(ctx: Context<'_>) -> Result<(), Error> {
     let client = get_reqwest_client(ctx.serenity_context()).await;
 
-    let request = client
-        .get("https://api.thedogapi.com/v1/images/search")
-        .send()
-        .await;
+    match provider.fetch(&client).await {
+        Ok(url) => {
+            let title = lang
+                .attribute(&format!("cmd-{}", provider.name()), "title")
+                .unwrap_or_else(|| provider.title().to_string());
 
-    match request {
-        Ok(res) => match res.json::<Vec<PetResponse>>().await {
-            Ok(res) => {
-                ctx.send(
-                    CreateReply::default().embed(
-                        CreateEmbed::new()
-                            .author(
-                                CreateEmbedAuthor::new("Here's a dog! 🐶")
-                                    .icon_url(ctx.author().face()),
-                            )
-                            .image(res[0].url.to_string())
-                            .color(Color::BLUE),
-                    ),
-                )
-                .await?;
-            }
-            Err(e) => {
-                error!("Error: {}", e);
-                send_error_msg(ctx, "Error handing web request to dog API, try again later").await;
-            }
-        },
-        Err(e) => {
-            error!("Error: {}", e);
-            send_error_msg(ctx, "Error handing web request to dog API, try again later").await;
+            ctx.send(
+                CreateReply::default().embed(
+                    embed(ctx, EmbedKind::Primary)
+                        .await
+                        .author(CreateEmbedAuthor::new(title).icon_url(ctx.author().face()))
+                        .image(url),
+                ),
+            )
+            .await?;
         }
-    }
-
-    Ok(())
-}
-
-/// Shows a smart duck! 🦆
-#[poise::command(slash_command)]
-async fn duck(ctx: Context<'_>) -> Result<(), Error> {
-    let client = get_reqwest_client(ctx.serenity_context()).await;
-
-    let request = client.get("https://random-d.uk/api/v2/quack").send().await;
-
-    match request {
-        Ok(res) => match res.json::<PetResponse>().await {
-            Ok(res) => {
-                ctx.send(
-                    CreateReply::default().embed(
-                        CreateEmbed::new()
-                            .author(
-                                CreateEmbedAuthor::new("Here's a duck! 🦆")
-                                    .icon_url(ctx.author().face()),
-                            )
-                            .image(res.url.to_string())
-                            .color(Color::BLUE),
-                    ),
-                )
-                .await?;
-            }
-            Err(e) => {
-                error!("Error: {}", e);
-                send_error_msg(
-                    ctx,
-                    "Error handing web request to duck API, try again later",
-                )
-                .await;
-            }
-        },
         Err(e) => {
             error!("Error: {}", e);
-            send_error_msg(
-                ctx,
-                "Error handing web request to duck API, try again later",
-            )
-            .await;
+            let msg = lang.tr_with(
+                "image-request-failed",
+                &fluent_args!["api" => provider.name()],
+            );
+            send_error_msg(ctx, &msg).await;
         }
     }
 
     Ok(())
 }
 
-pub fn commands() -> [Command; 3] {
-    [cat(), dog(), duck()]
+pub fn commands(providers: &[ProviderConfig]) -> Vec<Command> {
+    providers
+        .iter()
+        .map(|provider| {
+            let mut command = image();
+            command.name.clone_from(&provider.name);
+            command.qualified_name.clone_from(&provider.name);
+            command.identifying_name.clone_from(&provider.name);
+            command.description = Some(provider.description.clone());
+            command
+        })
+        .collect()
 }
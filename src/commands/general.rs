@@ -1,33 +1,772 @@
-use crate::structs::{Command, Context, Error};
+use super::autocomplete_command;
+use crate::{
+    cdn::{ImageFormat, UserImage},
+    database::get_database,
+    embeds::{embed, get_theme, hex, join_limited, EmbedKind, FIELD_LIMIT},
+    i18n::{autocomplete_locale, get_lang, Lang},
+    permissions::{default_permissions, MemberAccess, Verdict},
+    structs::{Command, Context, Error},
+    utils::{get_cooldowns, get_locales, send_error_msg},
+};
+use fluent::fluent_args;
 use poise::{
-    serenity_prelude::{Color, CreateEmbed, CreateEmbedAuthor, User},
-    CreateReply,
+    serenity_prelude::{
+        ChannelType, CreateEmbed, CreateEmbedAuthor, Guild, Member, Permissions, Role, RoleId,
+        Timestamp, User,
+    },
+    ChoiceParameter, CreateReply,
 };
 
+/// How many roles /serverinfo lists, highest first.
+const TOP_ROLES: usize = 10;
+
+/// Which of a user's pictures /avatar shows.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Eq)]
+enum AvatarKind {
+    Account,
+    Server,
+    Banner,
+}
+
 /// Shows yours or another user's avatar! 🖼️
-#[poise::command(slash_command)]
+#[poise::command(slash_command, prefix_command)]
 async fn avatar(
     ctx: Context<'_>,
     #[description = "The user to show"] user: Option<User>,
+    #[description = "Their account avatar, server avatar or banner"] show: Option<AvatarKind>,
+    #[description = "Image size in pixels, rounded up to a power of two"]
+    #[min = 16]
+    #[max = 4096]
+    size: Option<u16>,
+    #[description = "Image format, GIF only works for animated pictures"] format: Option<
+        ImageFormat,
+    >,
 ) -> Result<(), Error> {
     let user = user.as_ref().unwrap_or(ctx.author());
+    let show = show.unwrap_or(AvatarKind::Account);
+    let size = size.unwrap_or(1024);
+    let lang = get_lang(ctx).await;
+    let name = user.name.as_str();
+    let mut notes = Vec::new();
+
+    let image = match show {
+        AvatarKind::Account => user.avatar.map(|hash| UserImage::Avatar(user.id, hash)),
+        AvatarKind::Server => {
+            let member_avatar = match ctx.guild_id() {
+                Some(guild_id) => guild_id
+                    .member(ctx, user.id)
+                    .await
+                    .ok()
+                    .and_then(|member| member.avatar)
+                    .map(|hash| UserImage::MemberAvatar(guild_id, user.id, hash)),
+                None => None,
+            };
+
+            member_avatar.or_else(|| {
+                notes.push(lang.tr_with("avatar-no-server-avatar", &fluent_args!["user" => name]));
+                user.avatar.map(|hash| UserImage::Avatar(user.id, hash))
+            })
+        }
+        AvatarKind::Banner => {
+            // Banners are only sent when fetching the user
+            let Some(hash) = ctx.http().get_user(user.id).await?.banner else {
+                let msg = lang.tr_with("avatar-no-banner", &fluent_args!["user" => name]);
+                send_error_msg(ctx, &msg).await;
+                return Ok(());
+            };
+
+            Some(UserImage::Banner(user.id, hash))
+        }
+    };
+
+    let title = match show {
+        AvatarKind::Account => "avatar-title",
+        AvatarKind::Server => "avatar-server-title",
+        AvatarKind::Banner => "avatar-banner-title",
+    };
+
+    let mut embed = embed(ctx, EmbedKind::Primary).await.author(
+        CreateEmbedAuthor::new(lang.tr_with(title, &fluent_args!["user" => name]))
+            .icon_url(user.face()),
+    );
+
+    if let Some(image) = image {
+        let formats = image.formats();
+        let format = match format {
+            Some(format) if formats.contains(&format) => format,
+            Some(_) => {
+                notes.push(lang.tr("avatar-not-animated"));
+                image.default_format()
+            }
+            None => image.default_format(),
+        };
+
+        let downloads = formats
+            .iter()
+            .map(|f| format!("[{}]({})", f.name(), image.url(*f, size)))
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        embed = embed.image(image.url(format, size)).field(
+            lang.tr("avatar-downloads"),
+            downloads,
+            false,
+        );
+    } else {
+        // Users without an avatar have one of Discord's defaults, only available as PNG
+        let url = user.default_avatar_url();
+        notes.push(lang.tr_with("avatar-default", &fluent_args!["user" => name]));
+
+        embed =
+            embed
+                .image(&url)
+                .field(lang.tr("avatar-downloads"), format!("[PNG]({url})"), false);
+    }
+
+    if !notes.is_empty() {
+        embed = embed.description(notes.join("\n"));
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Choose the language I talk to you in! 🌍
+#[poise::command(slash_command, prefix_command)]
+async fn language(
+    ctx: Context<'_>,
+    #[description = "Your language, leave empty to use the server's"]
+    #[autocomplete = "autocomplete_locale"]
+    locale: Option<String>,
+) -> Result<(), Error> {
+    let locales = get_locales(ctx.serenity_context()).await;
+
+    if let Some(locale) = locale.as_deref().filter(|l| !locales.is_supported(l)) {
+        let lang = get_lang(ctx).await;
+        let msg = lang.tr_with("unknown-language", &fluent_args!["locale" => locale]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    db.set_user_locale(ctx.author().id, locale.as_deref()).await;
+
+    let lang = get_lang(ctx).await;
+    let msg = match locale {
+        Some(_) => lang.tr("language-updated"),
+        None => lang.tr("language-reset"),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                embed(ctx, EmbedKind::Success)
+                    .await
+                    .author(CreateEmbedAuthor::new(msg).icon_url(ctx.author().face())),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The command's description in the user's language.
+fn description<'a>(lang: &Lang, command: &'a Command) -> &'a str {
+    command
+        .description_localizations
+        .get(lang.locale())
+        .or(command.description.as_ref())
+        .map_or("", String::as_str)
+}
+
+/// Whether /help should list the command. Groups are listed when any of their
+/// subcommands are. Without `access` only guild-only commands are hidden.
+fn visible(
+    ctx: Context<'_>,
+    access: Option<&MemberAccess>,
+    command: &Command,
+    parents: &[&Command],
+) -> bool {
+    if command.hide_in_help || (command.guild_only && ctx.guild_id().is_none()) {
+        return false;
+    }
+
+    if !command.subcommands.is_empty() {
+        let mut chain = parents.to_vec();
+        chain.push(command);
+
+        return command
+            .subcommands
+            .iter()
+            .any(|subcommand| visible(ctx, access, subcommand, &chain));
+    }
+
+    access.is_none_or(|access| matches!(access.verdict(command, parents), Verdict::Allowed))
+}
+
+/// Finds a command by its qualified name, collecting its parents on the way.
+fn find<'a>(
+    commands: &'a [Command],
+    name: &str,
+    parents: &mut Vec<&'a Command>,
+) -> Option<&'a Command> {
+    for command in commands {
+        if command.qualified_name == name {
+            return Some(command);
+        }
+
+        if name.starts_with(&format!("{} ", command.qualified_name)) {
+            parents.push(command);
+            return find(&command.subcommands, name, parents);
+        }
+    }
+
+    None
+}
+
+fn usage(prefix: &str, command: &Command) -> String {
+    command.parameters.iter().fold(
+        format!("`{prefix}{}", command.qualified_name),
+        |usage, p| {
+            if p.required {
+                format!("{usage} <{}>", p.name)
+            } else {
+                format!("{usage} [{}]", p.name)
+            }
+        },
+    ) + "`"
+}
+
+async fn overview(
+    ctx: Context<'_>,
+    lang: &Lang,
+    access: Option<&MemberAccess>,
+    prefix: &str,
+) -> CreateEmbed {
+    let mut categories: Vec<(&str, Vec<String>)> = Vec::new();
+
+    for command in &ctx.framework().options().commands {
+        if !visible(ctx, access, command, &[]) {
+            continue;
+        }
+
+        let category = command.category.as_deref().unwrap_or("general");
+        let line = format!("`/{}` {}", command.name, description(lang, command));
+
+        match categories.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, lines)) => lines.push(line),
+            None => categories.push((category, vec![line])),
+        }
+    }
+
+    categories.into_iter().fold(
+        embed(ctx, EmbedKind::Primary)
+            .await
+            .author(CreateEmbedAuthor::new(lang.tr("help-title")).icon_url(ctx.author().face()))
+            .description(lang.tr_with("help-description", &fluent_args!["prefix" => prefix])),
+        |embed, (category, lines)| {
+            embed.field(
+                lang.tr(&format!("help-category-{category}")),
+                lines.join("\n"),
+                false,
+            )
+        },
+    )
+}
+
+async fn details(
+    ctx: Context<'_>,
+    lang: &Lang,
+    access: Option<&MemberAccess>,
+    prefix: &str,
+    command: &Command,
+    parents: &[&Command],
+) -> CreateEmbed {
+    let mut embed = embed(ctx, EmbedKind::Primary)
+        .await
+        .author(
+            CreateEmbedAuthor::new(format!("/{}", command.qualified_name))
+                .icon_url(ctx.author().face()),
+        )
+        .description(description(lang, command));
+
+    if command.subcommands.is_empty() {
+        let mut usages = vec![usage("/", command)];
+
+        if command.prefix_action.is_some() {
+            usages.push(usage(prefix, command));
+        }
+
+        embed = embed.field(lang.tr("help-usage"), usages.join("\n"), false);
+    }
+
+    if !command.parameters.is_empty() {
+        let parameters = command
+            .parameters
+            .iter()
+            .map(|p| {
+                let description = p
+                    .description_localizations
+                    .get(lang.locale())
+                    .or(p.description.as_ref())
+                    .map_or("", String::as_str);
+
+                if p.required {
+                    format!("`{}` {description}", p.name)
+                } else {
+                    format!("`{}` {description} {}", p.name, lang.tr("help-optional"))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        embed = embed.field(lang.tr("help-parameters"), parameters.join("\n"), false);
+    }
+
+    let required = parents
+        .iter()
+        .fold(default_permissions(command), |required, parent| {
+            required | default_permissions(parent)
+        });
+    let permissions = if required.is_empty() {
+        lang.tr("help-anyone")
+    } else {
+        required.to_string()
+    };
+
+    embed = embed.field(lang.tr("help-permissions"), permissions, true);
+
+    if command.subcommands.is_empty() {
+        let cooldowns = get_cooldowns(ctx.serenity_context()).await;
+        let defaults = command.cooldown_config.read().unwrap().clone();
+        let mut durations = cooldowns
+            .durations(&command.qualified_name, ctx.guild_id(), &defaults)
+            .await;
+        durations.sort_by_key(|(bucket, _)| bucket.name());
+
+        let value = if durations.is_empty() {
+            lang.tr("help-no-cooldown")
+        } else {
+            durations
+                .iter()
+                .map(|(bucket, duration)| {
+                    lang.tr_with(
+                        "help-cooldown",
+                        &fluent_args![
+                            "bucket" => bucket.name().to_lowercase(),
+                            "seconds" => duration.as_secs(),
+                        ],
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        embed = embed.field(lang.tr("help-cooldowns"), value, true);
+    }
+
+    let mut chain = parents.to_vec();
+    chain.push(command);
+
+    let subcommands = command
+        .subcommands
+        .iter()
+        .filter(|subcommand| visible(ctx, access, subcommand, &chain))
+        .map(|subcommand| {
+            format!(
+                "`/{}` {}",
+                subcommand.qualified_name,
+                description(lang, subcommand)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if !subcommands.is_empty() {
+        embed = embed.field(lang.tr("help-subcommands"), subcommands.join("\n"), false);
+    }
+
+    embed
+}
+
+/// Shows what I can do, or the details of a command! ❓
+#[poise::command(slash_command, prefix_command)]
+async fn help(
+    ctx: Context<'_>,
+    #[description = "The command to show details for"]
+    #[autocomplete = "autocomplete_command"]
+    #[rest]
+    command: Option<String>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let access = MemberAccess::resolve(ctx).await;
+
+    let prefix = match ctx.guild_id() {
+        Some(guild_id) => {
+            let db = get_database(ctx.serenity_context()).await;
+            db.get_settings(guild_id).await.prefix
+        }
+        None => None,
+    }
+    .unwrap_or_else(|| ctx.data().default_prefix.clone());
+
+    let Some(name) = command else {
+        let embed = overview(ctx, &lang, access.as_ref(), &prefix).await;
+        ctx.send(CreateReply::default().embed(embed)).await?;
+        return Ok(());
+    };
+
+    let name = name.trim().trim_start_matches('/');
+    let commands = &ctx.framework().options().commands;
+    let mut parents = Vec::new();
+
+    // Commands the user can't run are treated as if they didn't exist
+    let Some(command) = find(commands, name, &mut parents)
+        .filter(|command| visible(ctx, access.as_ref(), command, &parents))
+    else {
+        let msg = lang.tr_with("unknown-command", &fluent_args!["command" => name]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    };
+
+    let embed = details(ctx, &lang, access.as_ref(), &prefix, command, &parents).await;
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Permissions worth pointing out on /userinfo, most powerful first.
+const KEY_PERMISSIONS: [Permissions; 10] = [
+    Permissions::ADMINISTRATOR,
+    Permissions::MANAGE_GUILD,
+    Permissions::MANAGE_ROLES,
+    Permissions::MANAGE_CHANNELS,
+    Permissions::BAN_MEMBERS,
+    Permissions::KICK_MEMBERS,
+    Permissions::MODERATE_MEMBERS,
+    Permissions::MANAGE_MESSAGES,
+    Permissions::MANAGE_WEBHOOKS,
+    Permissions::MENTION_EVERYONE,
+];
+
+fn timestamp(time: Timestamp) -> String {
+    format!("<t:{}:F>", time.unix_timestamp())
+}
+
+fn yes_no(lang: &Lang, value: bool) -> String {
+    lang.tr_with("info-yes-no", &fluent_args!["value" => value.to_string()])
+}
+
+fn permission_list(lang: &Lang, permissions: Permissions) -> String {
+    if permissions.is_empty() {
+        lang.tr("info-none")
+    } else {
+        join_limited(
+            permissions
+                .get_permission_names()
+                .into_iter()
+                .map(String::from),
+            ", ",
+            FIELD_LIMIT,
+        )
+    }
+}
+
+/// The member's server-wide permissions, ignoring channel overwrites.
+fn guild_permissions(guild: &Guild, member: &Member) -> Permissions {
+    if guild.owner_id == member.user.id {
+        return Permissions::all();
+    }
+
+    // The @everyone role shares the guild's ID
+    member
+        .roles
+        .iter()
+        .chain([&RoleId::new(guild.id.get())])
+        .filter_map(|id| guild.roles.get(id))
+        .fold(Permissions::empty(), |permissions, role| {
+            permissions | role.permissions
+        })
+}
+
+/// Shows information about a member! 🪪
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn userinfo(
+    ctx: Context<'_>,
+    #[description = "The user to show"] user: Option<User>,
+) -> Result<(), Error> {
+    let user = user.as_ref().unwrap_or(ctx.author());
+    let guild_id = ctx.guild_id().unwrap();
+    let lang = get_lang(ctx).await;
+
+    let db = get_database(ctx.serenity_context()).await;
+    let infractions = db.get_infractions(guild_id, user.id).await.len();
+
+    let mut embed = embed(ctx, EmbedKind::Primary)
+        .await
+        .author(
+            CreateEmbedAuthor::new(lang.tr_with(
+                "userinfo-title",
+                &fluent_args!["user" => user.name.as_str()],
+            ))
+            .icon_url(user.face()),
+        )
+        .thumbnail(user.face())
+        .field(lang.tr("info-created"), timestamp(user.created_at()), true);
+
+    // Users who left the server only have their account details
+    if let Ok(member) = guild_id.member(ctx, user.id).await {
+        let (mut roles, permissions) = {
+            let guild = ctx.guild().unwrap();
+            let roles: Vec<Role> = member
+                .roles
+                .iter()
+                .filter_map(|id| guild.roles.get(id).cloned())
+                .collect();
+
+            (roles, guild_permissions(&guild, &member))
+        };
+        roles.sort_by_key(|role| std::cmp::Reverse(role.position));
+
+        let key_permissions = if permissions.administrator() {
+            Permissions::ADMINISTRATOR
+        } else {
+            KEY_PERMISSIONS
+                .iter()
+                .fold(Permissions::empty(), |key, permission| key | *permission)
+                & permissions
+        };
+
+        let timeout = member
+            .communication_disabled_until
+            .filter(|until| *until > Timestamp::now())
+            .map_or_else(
+                || lang.tr("info-not-timed-out"),
+                |until| {
+                    lang.tr_with(
+                        "info-timed-out-until",
+                        &fluent_args!["time" => format!("<t:{}:R>", until.unix_timestamp())],
+                    )
+                },
+            );
+
+        embed = embed
+            .field(
+                lang.tr("info-joined"),
+                member
+                    .joined_at
+                    .map_or_else(|| lang.tr("info-none"), timestamp),
+                true,
+            )
+            .field(
+                lang.tr("info-highest-role"),
+                roles
+                    .first()
+                    .map_or_else(|| lang.tr("info-none"), |role| format!("<@&{}>", role.id)),
+                true,
+            )
+            .field(
+                lang.tr_with("info-roles", &fluent_args!["count" => roles.len()]),
+                if roles.is_empty() {
+                    lang.tr("info-none")
+                } else {
+                    join_limited(
+                        roles.iter().map(|role| format!("<@&{}>", role.id)),
+                        " ",
+                        FIELD_LIMIT,
+                    )
+                },
+                false,
+            )
+            .field(
+                lang.tr("info-key-permissions"),
+                permission_list(&lang, key_permissions),
+                false,
+            )
+            .field(lang.tr("info-timeout"), timeout, true);
+    }
+
+    embed = embed.field(lang.tr("info-infractions"), infractions.to_string(), true);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Shows information about this server! 🏠
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn serverinfo(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let theme = get_theme(ctx).await;
+
+    let embed = {
+        let guild = ctx.guild().unwrap();
+        let count = |kind: ChannelType| guild.channels.values().filter(|c| c.kind == kind).count();
+
+        // The highest roles first, leaving out @everyone
+        let mut roles: Vec<&Role> = guild
+            .roles
+            .values()
+            .filter(|role| role.id.get() != guild.id.get())
+            .collect();
+        roles.sort_by_key(|role| std::cmp::Reverse(role.position));
+        let top_roles = if roles.is_empty() {
+            lang.tr("info-none")
+        } else {
+            join_limited(
+                roles
+                    .iter()
+                    .take(TOP_ROLES)
+                    .map(|role| format!("<@&{}>", role.id)),
+                " ",
+                FIELD_LIMIT,
+            )
+        };
+
+        let features = if guild.features.is_empty() {
+            lang.tr("info-none")
+        } else {
+            let mut features = guild.features.clone();
+            features.sort();
+            join_limited(
+                features.into_iter().map(|f| format!("`{f}`")),
+                ", ",
+                FIELD_LIMIT,
+            )
+        };
+
+        let mut embed = theme
+            .embed(EmbedKind::Primary)
+            .author(CreateEmbedAuthor::new(lang.tr_with(
+                "serverinfo-title",
+                &fluent_args!["server" => guild.name.as_str()],
+            )));
+
+        if let Some(icon) = guild.icon_url() {
+            embed = embed.thumbnail(icon);
+        }
+
+        embed
+            .field(
+                lang.tr("info-owner"),
+                format!("<@{}>", guild.owner_id),
+                true,
+            )
+            .field(
+                lang.tr("info-created"),
+                timestamp(guild.id.created_at()),
+                true,
+            )
+            .field(
+                lang.tr("info-members"),
+                guild.member_count.to_string(),
+                true,
+            )
+            .field(
+                lang.tr("info-channels"),
+                lang.tr_with(
+                    "info-channel-counts",
+                    &fluent_args![
+                        "text" => count(ChannelType::Text),
+                        "voice" => count(ChannelType::Voice),
+                        "categories" => count(ChannelType::Category),
+                    ],
+                ),
+                true,
+            )
+            .field(
+                lang.tr_with("info-roles", &fluent_args!["count" => roles.len()]),
+                top_roles,
+                false,
+            )
+            .field(lang.tr("info-emojis"), guild.emojis.len().to_string(), true)
+            .field(
+                lang.tr("info-boosts"),
+                lang.tr_with(
+                    "info-boost-summary",
+                    &fluent_args![
+                        "count" => guild.premium_subscription_count.unwrap_or(0),
+                        "tier" => u8::from(guild.premium_tier),
+                    ],
+                ),
+                true,
+            )
+            .field(lang.tr("info-features"), features, false)
+    };
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Shows information about a role! 🏷️
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn roleinfo(
+    ctx: Context<'_>,
+    #[description = "The role to show"] role: Role,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
 
-    let embed_author_builder = CreateEmbedAuthor::new(format!("{}'s avatar", user.name))
-        .icon_url(user.face())
-        .url(user.face());
+    // Members are never chunked, so this only counts those in the cache and is
+    // labelled as such
+    let members = {
+        let guild = ctx.guild().unwrap();
+        if role.id.get() == guild.id.get() {
+            guild.members.len()
+        } else {
+            guild
+                .members
+                .values()
+                .filter(|member| member.roles.contains(&role.id))
+                .count()
+        }
+    };
 
-    let embed_builder = CreateEmbed::new()
-        .author(embed_author_builder)
-        .image(user.face())
-        .color(Color::BLUE);
+    let mut embed = embed(ctx, EmbedKind::Primary)
+        .await
+        .author(
+            CreateEmbedAuthor::new(lang.tr_with(
+                "roleinfo-title",
+                &fluent_args!["role" => role.name.as_str()],
+            ))
+            .icon_url(ctx.author().face()),
+        )
+        .field(lang.tr("info-color"), hex(role.colour), true)
+        .field(lang.tr("info-position"), role.position.to_string(), true)
+        .field(lang.tr("info-cached-members"), members.to_string(), true)
+        .field(
+            lang.tr("info-mentionable"),
+            yes_no(&lang, role.mentionable),
+            true,
+        )
+        .field(lang.tr("info-hoisted"), yes_no(&lang, role.hoist), true)
+        .field(
+            lang.tr("info-created"),
+            timestamp(role.id.created_at()),
+            true,
+        )
+        .field(
+            lang.tr("info-permissions"),
+            permission_list(&lang, role.permissions),
+            false,
+        );
 
-    let reply_builder = CreateReply::default().embed(embed_builder);
+    if role.colour.0 != 0 {
+        embed = embed.color(role.colour);
+    }
 
-    ctx.send(reply_builder).await?;
+    ctx.send(CreateReply::default().embed(embed)).await?;
 
     Ok(())
 }
 
-pub fn commands() -> [Command; 1] {
-    [avatar()]
+pub fn commands() -> [Command; 6] {
+    [
+        avatar(),
+        help(),
+        language(),
+        userinfo(),
+        serverinfo(),
+        roleinfo(),
+    ]
 }
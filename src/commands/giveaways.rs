@@ -0,0 +1,327 @@
+use crate::{
+    database::get_database,
+    embeds::{embed, get_theme, EmbedKind},
+    giveaways::{
+        end as end_giveaway, giveaway_components, giveaway_embed, reroll as reroll_giveaway,
+        MAX_WINNERS,
+    },
+    i18n::{get_guild_lang, get_lang, Lang},
+    reminders::relative_time,
+    structs::{Command, Context, Error, Giveaway, Guild},
+    utils::{format_duration, get_giveaways, get_locales, parse_duration, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{
+        AutocompleteChoice, CreateEmbedAuthor, CreateMessage, GuildChannel, MessageId, Role,
+    },
+    CreateReply,
+};
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+use tracing::error;
+
+/// Giveaways can't run longer than this.
+const MAX_DURATION: Duration = Duration::from_hours(30 * 24);
+
+async fn autocomplete_giveaway(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+
+    get_database(ctx.serenity_context())
+        .await
+        .get_giveaways(guild_id)
+        .await
+        .into_iter()
+        .filter(|g| {
+            g.id.to_string().starts_with(&partial) || g.prize.to_lowercase().contains(&partial)
+        })
+        .take(25)
+        .map(|g| AutocompleteChoice::new(format!("#{} {}", g.id, g.prize), g.id))
+        .collect()
+}
+
+async fn find_giveaway(ctx: Context<'_>, lang: &Lang, id: i64) -> Option<Giveaway> {
+    let giveaway = get_database(ctx.serenity_context())
+        .await
+        .get_giveaway(ctx.guild_id().unwrap(), id)
+        .await;
+
+    if giveaway.is_none() {
+        let msg = lang.tr_with("giveaway-not-found", &fluent_args!["id" => id]);
+        send_error_msg(ctx, &msg).await;
+    }
+
+    giveaway
+}
+
+async fn send_success(ctx: Context<'_>, title: String) -> Result<(), Error> {
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Success)
+                .await
+                .author(CreateEmbedAuthor::new(title).icon_url(ctx.author().face())),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Gives things away to members who enter! 🎉
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("start", "end", "reroll", "list"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn giveaway(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Starts a giveaway members enter with a button! 🎉
+#[poise::command(slash_command, prefix_command)]
+async fn start(
+    ctx: Context<'_>,
+    #[description = "What's given away"]
+    #[max_length = 256]
+    prize: String,
+    #[description = "How long it runs, such as 1h or 3d"] duration: String,
+    #[description = "How many members win, 1 by default"]
+    #[min = 1]
+    #[max = 20]
+    winners: Option<i32>,
+    #[description = "The channel to post it in"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+    #[description = "A role members need to enter"] required_role: Option<Role>,
+    #[description = "How old accounts have to be to enter, such as 7d"] min_account_age: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let channel_id = channel.map_or(ctx.channel_id(), |channel| channel.id);
+
+    let Some(duration) = parse_duration(&duration) else {
+        let msg = lang.tr_with("invalid-duration", &fluent_args!["value" => duration]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    };
+
+    let min_account_age = match min_account_age {
+        Some(age) => {
+            let Some(parsed) = parse_duration(&age) else {
+                let msg = lang.tr_with("invalid-duration", &fluent_args!["value" => age]);
+                send_error_msg(ctx, &msg).await;
+                return Ok(());
+            };
+            Some(parsed)
+        }
+        None => None,
+    };
+
+    if duration > MAX_DURATION {
+        let msg = lang.tr_with(
+            "giveaway-too-long",
+            &fluent_args!["max" => format_duration(MAX_DURATION)],
+        );
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let mut giveaway = Giveaway {
+        id: 0,
+        guild_id,
+        channel_id,
+        message_id: MessageId::default(),
+        host_id: ctx.author().id,
+        prize,
+        winner_count: winners.unwrap_or(1).clamp(1, MAX_WINNERS),
+        required_role_id: required_role.map(|role| role.id),
+        min_account_age,
+        ends_at: OffsetDateTime::now_utc() + duration,
+        ended: false,
+        seed: None,
+        winner_ids: Vec::new(),
+    };
+
+    let theme = get_theme(ctx).await;
+    let giveaway_lang = get_guild_lang(ctx).await;
+
+    let message = match channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .embed(giveaway_embed(&theme, &giveaway_lang, &giveaway, 0))
+                .components(giveaway_components(&giveaway_lang, &giveaway)),
+        )
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Error posting giveaway: {}", e);
+            let msg = lang.tr_with(
+                "giveaway-send-failed",
+                &fluent_args!["channel" => format!("<#{channel_id}>")],
+            );
+            send_error_msg(ctx, &msg).await;
+            return Ok(());
+        }
+    };
+    giveaway.message_id = message.id;
+
+    get_database(ctx.serenity_context())
+        .await
+        .insert_guild(&Guild::new(guild_id))
+        .await;
+
+    let Some(id) = get_giveaways(ctx.serenity_context())
+        .await
+        .add(&giveaway)
+        .await
+    else {
+        if let Err(e) = message.delete(ctx).await {
+            error!("Error: {}", e);
+        }
+        send_error_msg(ctx, &lang.tr("giveaway-create-failed")).await;
+        return Ok(());
+    };
+
+    let title = lang.tr_with(
+        "giveaway-started",
+        &fluent_args!["id" => id, "channel" => format!("<#{channel_id}>")],
+    );
+    send_success(ctx, title).await
+}
+
+/// Ends a giveaway now and draws its winners! 🏁
+#[poise::command(slash_command, prefix_command)]
+async fn end(
+    ctx: Context<'_>,
+    #[description = "The giveaway to end"]
+    #[autocomplete = "autocomplete_giveaway"]
+    giveaway: i64,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(giveaway) = find_giveaway(ctx, &lang, giveaway).await else {
+        return Ok(());
+    };
+
+    let db = get_database(ctx.serenity_context()).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+
+    if giveaway.ended || !end_giveaway(ctx.http(), db.as_ref(), &locales, &giveaway).await {
+        let msg = lang.tr_with("giveaway-already-ended", &fluent_args!["id" => giveaway.id]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    send_success(
+        ctx,
+        lang.tr_with("giveaway-ended-now", &fluent_args!["id" => giveaway.id]),
+    )
+    .await
+}
+
+/// Draws new winners for an ended giveaway! 🎲
+#[poise::command(slash_command, prefix_command)]
+async fn reroll(
+    ctx: Context<'_>,
+    #[description = "The giveaway to reroll"]
+    #[autocomplete = "autocomplete_giveaway"]
+    giveaway: i64,
+    #[description = "How many new winners to draw, 1 by default"]
+    #[min = 1]
+    #[max = 20]
+    winners: Option<i32>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(giveaway) = find_giveaway(ctx, &lang, giveaway).await else {
+        return Ok(());
+    };
+
+    if !giveaway.ended {
+        let msg = lang.tr_with("giveaway-not-ended", &fluent_args!["id" => giveaway.id]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+    let count = usize::try_from(winners.unwrap_or(1).clamp(1, MAX_WINNERS)).unwrap_or(1);
+
+    let drawn = reroll_giveaway(ctx.http(), db.as_ref(), &locales, &giveaway, count).await;
+    if drawn.is_empty() {
+        let msg = lang.tr_with("giveaway-nobody-left", &fluent_args!["id" => giveaway.id]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    send_success(
+        ctx,
+        lang.tr_with(
+            "giveaway-rerolled",
+            &fluent_args!["id" => giveaway.id, "count" => drawn.len()],
+        ),
+    )
+    .await
+}
+
+/// Lists this server's giveaways! 📋
+#[poise::command(slash_command, prefix_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let giveaways = get_database(ctx.serenity_context())
+        .await
+        .get_giveaways(ctx.guild_id().unwrap())
+        .await;
+
+    let description = if giveaways.is_empty() {
+        lang.tr("giveaways-empty")
+    } else {
+        giveaways
+            .iter()
+            .take(25)
+            .map(|g| {
+                lang.tr_with(
+                    "giveaways-entry",
+                    &fluent_args![
+                        "id" => g.id,
+                        "prize" => g.prize.as_str(),
+                        "link" => g.message_id.link(g.channel_id, Some(g.guild_id)),
+                        "ended" => g.ended.to_string(),
+                        "time" => relative_time(g.ends_at),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Primary)
+                .await
+                .author(
+                    CreateEmbedAuthor::new(lang.tr("giveaways-title"))
+                        .icon_url(ctx.author().face()),
+                )
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 1] {
+    [giveaway()]
+}
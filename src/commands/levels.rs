@@ -0,0 +1,377 @@
+use super::config::save_settings;
+use crate::{
+    database::get_database,
+    embeds::{bar, embed, get_theme, join_limited, EmbedKind, FIELD_LIMIT},
+    i18n::{get_lang, Lang},
+    levels::{progress, XP_COOLDOWN},
+    structs::{Command, Context, Error, Guild, LevelReward, Theme},
+    utils::{check_assignable, get_levels, get_shutdown, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{
+        ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+        CreateEmbedAuthor, CreateInteractionResponse, CreateInteractionResponseMessage,
+        GuildChannel, GuildId, Role, User,
+    },
+    CreateReply,
+};
+use std::time::Duration;
+
+/// Members shown on each leaderboard page.
+const PAGE_SIZE: i64 = 10;
+/// How long the leaderboard's buttons keep working after the last press.
+const PAGE_TIMEOUT: Duration = Duration::from_mins(5);
+const BAR_WIDTH: usize = 20;
+
+/// Shows your level, or someone else's! 📈
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn rank(
+    ctx: Context<'_>,
+    #[description = "The member to show"] user: Option<User>,
+) -> Result<(), Error> {
+    let user = user.as_ref().unwrap_or(ctx.author());
+    let guild_id = ctx.guild_id().unwrap();
+    let lang = get_lang(ctx).await;
+    let db = get_database(ctx.serenity_context()).await;
+
+    // XP earned since the last write counts too, so members see it right away
+    let pending = get_levels(ctx.serenity_context())
+        .await
+        .pending(guild_id, user.id);
+    let total = db.get_xp(guild_id, user.id).await + pending;
+    let progress = progress(total);
+
+    let rank = if total > 0 {
+        lang.tr_with(
+            "rank-position",
+            &fluent_args!["rank" => db.count_above(guild_id, total).await + 1],
+        )
+    } else {
+        lang.tr("rank-unranked")
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Primary)
+                .await
+                .author(
+                    CreateEmbedAuthor::new(
+                        lang.tr_with("rank-title", &fluent_args!["user" => user.name.as_str()]),
+                    )
+                    .icon_url(user.face()),
+                )
+                .description(format!(
+                    "{}\n{}",
+                    bar(progress.xp, progress.needed, BAR_WIDTH),
+                    lang.tr_with(
+                        "rank-progress",
+                        &fluent_args![
+                            "xp" => progress.xp,
+                            "needed" => progress.needed,
+                            "next" => progress.level + 1,
+                        ],
+                    )
+                ))
+                .field(lang.tr("rank-level"), progress.level.to_string(), true)
+                .field(lang.tr("rank-rank"), rank, true)
+                .field(lang.tr("rank-xp"), total.to_string(), true),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn leaderboard_page(
+    ctx: Context<'_>,
+    lang: &Lang,
+    theme: &Theme,
+    page: i64,
+    pages: i64,
+) -> CreateEmbed {
+    let guild_id = ctx.guild_id().unwrap();
+    let offset = page * PAGE_SIZE;
+    let members = get_database(ctx.serenity_context())
+        .await
+        .get_leaderboard(guild_id, offset, PAGE_SIZE)
+        .await;
+
+    let description = if members.is_empty() {
+        lang.tr("leaderboard-empty")
+    } else {
+        members
+            .iter()
+            .zip(offset + 1..)
+            .map(|(member, rank)| {
+                lang.tr_with(
+                    "leaderboard-entry",
+                    &fluent_args![
+                        "rank" => rank,
+                        "user" => member.user_id.to_string(),
+                        "level" => progress(member.xp).level,
+                        "xp" => member.xp,
+                    ],
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    theme
+        .embed(EmbedKind::Primary)
+        .author(
+            CreateEmbedAuthor::new(lang.tr_with(
+                "leaderboard-title",
+                &fluent_args!["page" => page + 1, "pages" => pages],
+            ))
+            .icon_url(ctx.author().face()),
+        )
+        .description(description)
+}
+
+fn page_buttons(id: u64, page: i64, pages: i64) -> Vec<CreateActionRow> {
+    if pages <= 1 {
+        return Vec::new();
+    }
+
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{id}:prev"))
+            .label("◀")
+            .disabled(page == 0),
+        CreateButton::new(format!("{id}:next"))
+            .label("▶")
+            .disabled(page + 1 >= pages),
+    ])]
+}
+
+/// Shows who has the most XP in this server! 🏆
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let theme = get_theme(ctx).await;
+    let ranked = get_database(ctx.serenity_context())
+        .await
+        .count_ranked(ctx.guild_id().unwrap())
+        .await;
+    let pages = ((ranked + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+    let id = ctx.id();
+    let mut page = 0;
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .embed(leaderboard_page(ctx, &lang, &theme, page, pages).await)
+                .components(page_buttons(id, page, pages)),
+        )
+        .await?;
+
+    if pages <= 1 {
+        return Ok(());
+    }
+
+    // Stop listening for presses once shutdown stops waiting on in-flight commands
+    let token = get_shutdown(ctx.serenity_context()).await.token();
+    loop {
+        let collector = ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .filter(move |press| press.data.custom_id.starts_with(&format!("{id}:")))
+            .timeout(PAGE_TIMEOUT);
+        let press = tokio::select! {
+            press = collector => press,
+            () = token.cancelled() => None,
+        };
+        let Some(press) = press else {
+            break;
+        };
+
+        page = if press.data.custom_id.ends_with(":prev") {
+            (page - 1).max(0)
+        } else {
+            (page + 1).min(pages - 1)
+        };
+
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(leaderboard_page(ctx, &lang, &theme, page, pages).await)
+                        .components(page_buttons(id, page, pages)),
+                ),
+            )
+            .await?;
+    }
+
+    reply
+        .edit(ctx, CreateReply::default().components(Vec::new()))
+        .await?;
+
+    Ok(())
+}
+
+async fn levels_embed(
+    ctx: Context<'_>,
+    kind: EmbedKind,
+    title: &str,
+    guild_id: GuildId,
+) -> CreateEmbed {
+    let lang = get_lang(ctx).await;
+    let db = get_database(ctx.serenity_context()).await;
+    let guild = db.get_settings(guild_id).await;
+    let rewards = db.get_level_rewards(guild_id).await;
+
+    let rewards = if rewards.is_empty() {
+        lang.tr("settings-none")
+    } else {
+        join_limited(
+            rewards.iter().map(|r| {
+                lang.tr_with(
+                    "levels-reward",
+                    &fluent_args!["level" => r.level, "role" => format!("<@&{}>", r.role_id)],
+                )
+            }),
+            "\n",
+            FIELD_LIMIT,
+        )
+    };
+
+    let channels = if guild.no_xp_channels.is_empty() {
+        lang.tr("settings-none")
+    } else {
+        join_limited(
+            guild.no_xp_channels.iter().map(|id| format!("<#{id}>")),
+            ", ",
+            FIELD_LIMIT,
+        )
+    };
+
+    guild
+        .theme
+        .embed(kind)
+        .author(CreateEmbedAuthor::new(lang.tr(title)).icon_url(ctx.author().face()))
+        .description(lang.tr_with(
+            "levels-summary",
+            &fluent_args!["seconds" => XP_COOLDOWN.as_secs()],
+        ))
+        .field(lang.tr("levels-rewards"), rewards, false)
+        .field(lang.tr("levels-no-xp-channels"), channels, false)
+}
+
+/// Configure XP and level rewards in this server! 📈
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("show", "reward", "channel"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD",
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn levels(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Shows this server's level rewards and channels without XP! 📈
+#[poise::command(slash_command, prefix_command)]
+async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.send(
+        CreateReply::default().embed(
+            levels_embed(
+                ctx,
+                EmbedKind::Primary,
+                "levels-title",
+                ctx.guild_id().unwrap(),
+            )
+            .await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Gives members a role when they reach a level, or stops doing so! 🎁
+#[poise::command(slash_command, prefix_command)]
+async fn reward(
+    ctx: Context<'_>,
+    #[description = "The role to give"] role: Role,
+    #[description = "The level it's given at, leave empty to stop giving it"]
+    #[min = 1]
+    #[max = 1000]
+    level: Option<i32>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let db = get_database(ctx.serenity_context()).await;
+
+    match level {
+        Some(level) => {
+            if !check_assignable(ctx, &lang, &role).await? {
+                return Ok(());
+            }
+
+            db.insert_guild(&Guild::new(guild_id)).await;
+            db.set_level_reward(&LevelReward {
+                guild_id,
+                role_id: role.id,
+                level,
+            })
+            .await;
+        }
+        None => {
+            if !db.remove_level_reward(guild_id, role.id).await {
+                let msg = lang.tr_with(
+                    "levels-no-reward",
+                    &fluent_args!["role" => format!("<@&{}>", role.id)],
+                );
+                send_error_msg(ctx, &msg).await;
+                return Ok(());
+            }
+        }
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(levels_embed(ctx, EmbedKind::Success, "levels-updated-rewards", guild_id).await),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets whether messages in a channel give XP! 💬
+#[poise::command(slash_command, prefix_command)]
+async fn channel(
+    ctx: Context<'_>,
+    #[description = "The channel to change"]
+    #[channel_types("Text", "Voice", "News", "Forum")]
+    channel: GuildChannel,
+    #[description = "Whether messages there give XP"] xp: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap();
+    let db = get_database(ctx.serenity_context()).await;
+    let mut guild = db.get_settings(guild_id).await;
+
+    guild.no_xp_channels.retain(|id| *id != channel.id);
+    if !xp {
+        guild.no_xp_channels.push(channel.id);
+    }
+
+    if !save_settings(ctx, &guild).await {
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            levels_embed(ctx, EmbedKind::Success, "levels-updated-channels", guild_id).await,
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 3] {
+    [rank(), leaderboard(), levels()]
+}
@@ -1,13 +1,69 @@
+pub mod config;
 pub mod fun;
 pub mod general;
+pub mod giveaways;
+pub mod levels;
 pub mod moderation;
+pub mod permissions;
+pub mod polls;
+pub mod reminders;
+pub mod role_panels;
+pub mod tags;
 
-use crate::structs::Command;
+use crate::{
+    i18n::Locales,
+    permissions::take_required_permissions,
+    providers::ProviderConfig,
+    structs::{Command, Context},
+};
 
-pub fn commands() -> Vec<Command> {
-    fun::commands()
+pub fn commands(providers: &[ProviderConfig], locales: &Locales) -> Vec<Command> {
+    // Each module is a category in /help
+    let mut commands: Vec<Command> = [
+        ("fun", fun::commands(providers)),
+        ("general", Vec::from(general::commands())),
+        ("reminders", Vec::from(reminders::commands())),
+        ("polls", Vec::from(polls::commands())),
+        ("giveaways", Vec::from(giveaways::commands())),
+        ("tags", Vec::from(tags::commands())),
+        ("roles", Vec::from(role_panels::commands())),
+        ("levels", Vec::from(levels::commands())),
+        ("moderation", Vec::from(moderation::commands())),
+        ("config", Vec::from(config::commands())),
+        ("permissions", Vec::from(permissions::commands())),
+    ]
+    .into_iter()
+    .flat_map(|(category, commands)| {
+        commands.into_iter().map(move |mut command| {
+            command.category = Some(category.to_string());
+            command
+        })
+    })
+    .collect();
+
+    locales.localize_commands(&mut commands);
+    take_required_permissions(&mut commands);
+
+    commands
+}
+
+/// Qualified names of every command, including subcommands.
+pub fn command_names(commands: &[Command]) -> Vec<String> {
+    commands
+        .iter()
+        .flat_map(|c| {
+            let mut names = vec![c.qualified_name.clone()];
+            names.extend(command_names(&c.subcommands));
+            names
+        })
+        .collect()
+}
+
+#[allow(clippy::unused_async)]
+pub async fn autocomplete_command(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    command_names(&ctx.framework().options().commands)
         .into_iter()
-        .chain(moderation::commands())
-        .chain(general::commands())
+        .filter(|name| name.starts_with(partial))
+        .take(25)
         .collect()
 }
@@ -1,10 +1,18 @@
 use crate::{
+    i18n::get_lang,
     structs::{Command, Context, Error, InfractionType},
-    utils::{get_member, handle_moderation, manageable, send_error_msg},
+    utils::{
+        get_member, handle_moderation, infraction_key, manageable, parse_duration, send_error_msg,
+    },
 };
+use fluent::fluent_args;
 use poise::serenity_prelude::User;
+use std::time::Duration;
 use tracing::error;
 
+/// The longest timeout Discord allows.
+const MAX_MUTE: Duration = Duration::from_hours(28 * 24);
+
 async fn check_manageable(ctx: Context<'_>, user: &User, infraction_type: InfractionType) -> bool {
     let user_member = get_member(ctx, user.id).await;
     let author_member = ctx.author_member().await.unwrap().into_owned();
@@ -14,24 +22,25 @@ async fn check_manageable(ctx: Context<'_>, user: &User, infraction_type: Infrac
     let can_i_manage = manageable(ctx, &bot_member, &user_member).await;
 
     if !can_manage || !can_i_manage {
-        let string: &str;
-
-        if !can_manage && !can_i_manage {
-            string = "you and I";
+        let who = if !can_manage && !can_i_manage {
+            "both"
         } else if !can_manage {
-            string = "you";
+            "author"
         } else {
-            string = "me";
+            "bot"
         };
 
-        let infraction_type = match infraction_type {
-            InfractionType::Ban => "ban",
-            InfractionType::Kick => "kick",
-            InfractionType::Mute => "mute",
-            InfractionType::Warn => "warn",
-        };
-
-        send_error_msg(ctx, &format!("<@{}> has a higher role than {}, or is the owner of the server, so I cannot {} them.", user.id, string, infraction_type)).await;
+        let lang = get_lang(ctx).await;
+        let msg = lang.tr_with(
+            "mod-higher-role",
+            &fluent_args![
+                "user" => format!("<@{}>", user.id),
+                "who" => who,
+                "action" => infraction_key(infraction_type),
+            ],
+        );
+
+        send_error_msg(ctx, &msg).await;
         return false;
     }
 
@@ -41,99 +50,110 @@ async fn check_manageable(ctx: Context<'_>, user: &User, infraction_type: Infrac
 /// Ban a member! 🔨
 #[poise::command(
     slash_command,
-    default_member_permissions = "BAN_MEMBERS",
+    prefix_command,
     required_bot_permissions = "BAN_MEMBERS",
     required_permissions = "BAN_MEMBERS",
-    guild_only = true
+    guild_only = true,
+    user_cooldown = 2
 )]
 async fn ban(
     ctx: Context<'_>,
     #[description = "The user to ban"] user: User,
-    #[description = "The reason for banning this user"] reason: Option<String>,
+    #[description = "The reason for banning this user"]
+    #[rest]
+    reason: Option<String>,
 ) -> Result<(), Error> {
     if !check_manageable(ctx, &user, InfractionType::Ban).await {
         return Ok(());
     }
 
-    let reason = reason.unwrap_or(String::from("No reason provided"));
+    let lang = get_lang(ctx).await;
+    let reason = reason.unwrap_or_else(|| lang.tr("no-reason"));
 
-    if let Err(e) = handle_moderation(ctx, InfractionType::Ban, &user, &reason).await {
+    if let Err(e) = handle_moderation(ctx, InfractionType::Ban, &user, &reason, None).await {
         error!("Error: {}", e);
-        send_error_msg(ctx, "Error handling ban").await;
-        return Ok(());
+        let msg = lang.tr_with("mod-error", &fluent_args!["action" => "ban"]);
+        send_error_msg(ctx, &msg).await;
     }
 
-    ctx.guild_id()
-        .unwrap()
-        .ban_with_reason(ctx, &user.id, 7, reason.as_str())
-        .await?;
-
     Ok(())
 }
 
 /// Kick a member! 🔨
 #[poise::command(
     slash_command,
-    default_member_permissions = "KICK_MEMBERS",
+    prefix_command,
     required_bot_permissions = "KICK_MEMBERS",
     required_permissions = "KICK_MEMBERS",
-    guild_only = true
+    guild_only = true,
+    user_cooldown = 2
 )]
 async fn kick(
     ctx: Context<'_>,
     #[description = "The user to kick"] user: User,
-    #[description = "The reason for kicking this user"] reason: Option<String>,
+    #[description = "The reason for kicking this user"]
+    #[rest]
+    reason: Option<String>,
 ) -> Result<(), Error> {
     if !check_manageable(ctx, &user, InfractionType::Kick).await {
         return Ok(());
     }
 
-    let reason = reason.unwrap_or(String::from("No reason provided"));
+    let lang = get_lang(ctx).await;
+    let reason = reason.unwrap_or_else(|| lang.tr("no-reason"));
 
-    if let Err(e) = handle_moderation(ctx, InfractionType::Kick, &user, &reason).await {
+    if let Err(e) = handle_moderation(ctx, InfractionType::Kick, &user, &reason, None).await {
         error!("Error: {}", e);
-        send_error_msg(ctx, "Error handling kick").await;
-        return Ok(());
+        let msg = lang.tr_with("mod-error", &fluent_args!["action" => "kick"]);
+        send_error_msg(ctx, &msg).await;
     }
 
-    ctx.guild_id()
-        .unwrap()
-        .kick_with_reason(ctx, &user.id, reason.as_str())
-        .await?;
-
     Ok(())
 }
 
 /// Mute a member! 🔨
 #[poise::command(
     slash_command,
-    default_member_permissions = "MODERATE_MEMBERS",
+    prefix_command,
     required_bot_permissions = "MODERATE_MEMBERS",
     required_permissions = "MODERATE_MEMBERS",
-    guild_only = true
+    guild_only = true,
+    user_cooldown = 2
 )]
 async fn mute(
     ctx: Context<'_>,
     #[description = "The user to mute"] user: User,
-    #[description = "The reason for muting this user"] reason: Option<String>,
+    #[description = "How long to mute them for, such as 10m, 2h or 1d"] duration: String,
+    #[description = "The reason for muting this user"]
+    #[rest]
+    reason: Option<String>,
 ) -> Result<(), Error> {
-    if !check_manageable(ctx, &user, InfractionType::Mute).await {
+    let lang = get_lang(ctx).await;
+
+    let Some(duration) = parse_duration(&duration) else {
+        let msg = lang.tr_with("invalid-duration", &fluent_args!["value" => duration]);
+        send_error_msg(ctx, &msg).await;
         return Ok(());
-    }
+    };
 
-    let reason = reason.unwrap_or(String::from("No reason provided"));
+    if duration > MAX_MUTE {
+        send_error_msg(ctx, &lang.tr("mute-too-long")).await;
+        return Ok(());
+    }
 
-    if let Err(e) = handle_moderation(ctx, InfractionType::Mute, &user, &reason).await {
-        error!("Error: {}", e);
-        send_error_msg(ctx, "Error handling mute").await;
+    if !check_manageable(ctx, &user, InfractionType::Mute).await {
         return Ok(());
     }
 
-    // @TODO: Add mute timing
+    let reason = reason.unwrap_or_else(|| lang.tr("no-reason"));
 
-    // user_member
-    //     .disable_communication_until_datetime(ctx, Utc)
-    //     .await?;
+    if let Err(e) =
+        handle_moderation(ctx, InfractionType::Mute, &user, &reason, Some(duration)).await
+    {
+        error!("Error: {}", e);
+        let msg = lang.tr_with("mod-error", &fluent_args!["action" => "mute"]);
+        send_error_msg(ctx, &msg).await;
+    }
 
     Ok(())
 }
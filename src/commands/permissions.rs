@@ -0,0 +1,224 @@
+use super::{autocomplete_command, command_names};
+use crate::{
+    embeds::{embed, EmbedKind},
+    i18n::{get_lang, Lang},
+    structs::{Command, Context, Error, PermissionOverride, PermissionTarget},
+    utils::{get_permission_overrides, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{CreateEmbedAuthor, GuildChannel, Role, User},
+    CreateReply,
+};
+
+fn mention(target: PermissionTarget, id: u64) -> String {
+    match target {
+        PermissionTarget::Role => format!("<@&{id}>"),
+        PermissionTarget::User => format!("<@{id}>"),
+        PermissionTarget::Channel => format!("<#{id}>"),
+    }
+}
+
+fn rule_list(lang: &Lang, rules: &[PermissionOverride]) -> String {
+    if rules.is_empty() {
+        return lang.tr("permissions-empty");
+    }
+
+    rules
+        .iter()
+        .map(|r| {
+            lang.tr_with(
+                "permissions-rule",
+                &fluent_args![
+                    "allow" => r.allow.to_string(),
+                    "command" => r.command.as_str(),
+                    "target" => mention(r.target, r.target_id),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn target(
+    role: Option<Role>,
+    user: Option<User>,
+    channel: Option<GuildChannel>,
+) -> Option<(PermissionTarget, u64)> {
+    match (role, user, channel) {
+        (Some(role), None, None) => Some((PermissionTarget::Role, role.id.get())),
+        (None, Some(user), None) => Some((PermissionTarget::User, user.id.get())),
+        (None, None, Some(channel)) => Some((PermissionTarget::Channel, channel.id.get())),
+        _ => None,
+    }
+}
+
+/// Checks the command exists and may be overridden, telling the user if not.
+async fn validate_command(ctx: Context<'_>, lang: &Lang, command: &str) -> bool {
+    if !command_names(&ctx.framework().options().commands).contains(&command.to_string()) {
+        let msg = lang.tr_with("unknown-command", &fluent_args!["command" => command]);
+        send_error_msg(ctx, &msg).await;
+        return false;
+    }
+
+    // Overrides on these could lock the server out of managing overrides
+    if command.split(' ').next() == Some("permissions") {
+        let msg = lang.tr_with("permission-protected", &fluent_args!["command" => command]);
+        send_error_msg(ctx, &msg).await;
+        return false;
+    }
+
+    true
+}
+
+async fn send_rules(
+    ctx: Context<'_>,
+    lang: &Lang,
+    title: &str,
+    command: Option<&str>,
+) -> Result<(), Error> {
+    let overrides = get_permission_overrides(ctx.serenity_context()).await;
+    let rules: Vec<_> = overrides
+        .rules(ctx.guild_id().unwrap())
+        .await
+        .into_iter()
+        .filter(|r| command.is_none_or(|command| r.command == command))
+        .collect();
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Success)
+                .await
+                .author(CreateEmbedAuthor::new(lang.tr(title)).icon_url(ctx.author().face()))
+                .description(rule_list(lang, &rules)),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn set_rule(
+    ctx: Context<'_>,
+    command: String,
+    role: Option<Role>,
+    user: Option<User>,
+    channel: Option<GuildChannel>,
+    allow: bool,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    if !validate_command(ctx, &lang, &command).await {
+        return Ok(());
+    }
+
+    let Some((target, target_id)) = target(role, user, channel) else {
+        send_error_msg(ctx, &lang.tr("permission-one-target")).await;
+        return Ok(());
+    };
+
+    let overrides = get_permission_overrides(ctx.serenity_context()).await;
+    overrides
+        .set_rule(&PermissionOverride {
+            guild_id: ctx.guild_id().unwrap(),
+            command: command.clone(),
+            target,
+            target_id,
+            allow,
+        })
+        .await;
+
+    send_rules(ctx, &lang, "permissions-updated", Some(&command)).await
+}
+
+/// Manage who can use which commands in this server! 🔐
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("allow", "deny", "reset", "list"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD",
+    required_permissions = "MANAGE_GUILD",
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn permissions(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Lets a role, user or channel use a command! ✅
+#[poise::command(slash_command, prefix_command)]
+async fn allow(
+    ctx: Context<'_>,
+    #[description = "The command to allow"]
+    #[autocomplete = "autocomplete_command"]
+    command: String,
+    #[description = "The role to allow it for"] role: Option<Role>,
+    #[description = "The user to allow it for"] user: Option<User>,
+    #[description = "The channel to allow it in"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    set_rule(ctx, command, role, user, channel, true).await
+}
+
+/// Stops a role, user or channel from using a command! ⛔
+#[poise::command(slash_command, prefix_command)]
+async fn deny(
+    ctx: Context<'_>,
+    #[description = "The command to deny"]
+    #[autocomplete = "autocomplete_command"]
+    command: String,
+    #[description = "The role to deny it for"] role: Option<Role>,
+    #[description = "The user to deny it for"] user: Option<User>,
+    #[description = "The channel to deny it in"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    set_rule(ctx, command, role, user, channel, false).await
+}
+
+/// Removes an override, going back to the command's default permissions! ↩️
+#[poise::command(slash_command, prefix_command)]
+async fn reset(
+    ctx: Context<'_>,
+    #[description = "The command to reset"]
+    #[autocomplete = "autocomplete_command"]
+    command: String,
+    #[description = "The role to reset it for"] role: Option<Role>,
+    #[description = "The user to reset it for"] user: Option<User>,
+    #[description = "The channel to reset it in"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some((target, target_id)) = target(role, user, channel) else {
+        send_error_msg(ctx, &lang.tr("permission-one-target")).await;
+        return Ok(());
+    };
+
+    let overrides = get_permission_overrides(ctx.serenity_context()).await;
+    overrides
+        .remove_rule(ctx.guild_id().unwrap(), &command, target, target_id)
+        .await;
+
+    send_rules(ctx, &lang, "permissions-updated", Some(&command)).await
+}
+
+/// Lists this server's permission overrides! 🔐
+#[poise::command(slash_command, prefix_command)]
+async fn list(
+    ctx: Context<'_>,
+    #[description = "Only show overrides for this command"]
+    #[autocomplete = "autocomplete_command"]
+    command: Option<String>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    send_rules(ctx, &lang, "permissions-title", command.as_deref()).await
+}
+
+pub fn commands() -> [Command; 1] {
+    [permissions()]
+}
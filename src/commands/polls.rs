@@ -0,0 +1,107 @@
+use crate::{
+    database::get_database,
+    embeds::get_theme,
+    i18n::{get_guild_lang, get_lang},
+    polls::{parse_options, poll_components, poll_embed, MAX_OPTIONS, MAX_OPTION_LENGTH},
+    structs::{Command, Context, Error, Guild, Poll},
+    utils::{format_duration, get_polls, parse_duration, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{serenity_prelude::MessageId, CreateReply};
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+use tracing::error;
+
+/// How long polls run unless told otherwise.
+const DEFAULT_DURATION: Duration = Duration::from_hours(24);
+/// Polls can't run longer than this.
+const MAX_DURATION: Duration = Duration::from_hours(30 * 24);
+
+/// Asks the server a question! 📊
+#[poise::command(slash_command, prefix_command, guild_only = true)]
+async fn poll(
+    ctx: Context<'_>,
+    #[description = "What to ask"]
+    #[max_length = 256]
+    question: String,
+    #[description = "The answers to pick from, separated by |"] options: String,
+    #[description = "How long it runs, a day by default"] duration: Option<String>,
+    #[description = "Whether members can pick more than one answer"] multi_choice: Option<bool>,
+    #[description = "Whether to hide who voted for what"] anonymous: Option<bool>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let guild_id = ctx.guild_id().unwrap();
+
+    let Some(options) = parse_options(&options) else {
+        let msg = lang.tr_with(
+            "poll-invalid-options",
+            &fluent_args!["max" => MAX_OPTIONS, "length" => MAX_OPTION_LENGTH],
+        );
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    };
+
+    let duration = match duration {
+        Some(duration) => {
+            let Some(parsed) = parse_duration(&duration) else {
+                let msg = lang.tr_with("invalid-duration", &fluent_args!["value" => duration]);
+                send_error_msg(ctx, &msg).await;
+                return Ok(());
+            };
+            parsed
+        }
+        None => DEFAULT_DURATION,
+    };
+
+    if duration > MAX_DURATION {
+        let msg = lang.tr_with(
+            "poll-too-long",
+            &fluent_args!["max" => format_duration(MAX_DURATION)],
+        );
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let mut poll = Poll {
+        message_id: MessageId::default(),
+        guild_id,
+        channel_id: ctx.channel_id(),
+        user_id: ctx.author().id,
+        question,
+        options,
+        multi_choice: multi_choice.unwrap_or(false),
+        anonymous: anonymous.unwrap_or(false),
+        closes_at: OffsetDateTime::now_utc() + duration,
+    };
+
+    let poll_lang = get_guild_lang(ctx).await;
+    let theme = get_theme(ctx).await;
+
+    let reply = ctx
+        .send(
+            CreateReply::default()
+                .embed(poll_embed(&theme, &poll_lang, &poll, &[]))
+                .components(poll_components(&poll)),
+        )
+        .await?;
+    let message = reply.message().await?;
+    poll.message_id = message.id;
+
+    get_database(ctx.serenity_context())
+        .await
+        .insert_guild(&Guild::new(guild_id))
+        .await;
+
+    if !get_polls(ctx.serenity_context()).await.add(&poll).await {
+        if let Err(e) = reply.delete(ctx).await {
+            error!("Error: {}", e);
+        }
+        send_error_msg(ctx, &lang.tr("poll-create-failed")).await;
+    }
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 1] {
+    [poll()]
+}
@@ -0,0 +1,190 @@
+use crate::{
+    database::get_database,
+    embeds::{embed, EmbedKind},
+    i18n::get_lang,
+    reminders::relative_time,
+    structs::{Command, Context, Error, Reminder},
+    utils::{get_reminders, parse_duration, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{AutocompleteChoice, CreateEmbedAuthor},
+    CreateReply,
+};
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+
+/// Reminders can't be set further ahead than this.
+const MAX_DELAY: Duration = Duration::from_hours(365 * 24);
+/// Pending reminders one user can have.
+const MAX_REMINDERS: usize = 25;
+/// Characters of a reminder's text shown in lists.
+const PREVIEW_LENGTH: usize = 50;
+
+fn preview(text: &str) -> String {
+    let mut chars = text.chars();
+    let preview: String = chars.by_ref().take(PREVIEW_LENGTH).collect();
+
+    if chars.next().is_some() {
+        format!("{preview}…")
+    } else {
+        preview
+    }
+}
+
+async fn send_reply(ctx: Context<'_>, title: &str, description: String) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                embed(ctx, EmbedKind::Success)
+                    .await
+                    .author(CreateEmbedAuthor::new(lang.tr(title)).icon_url(ctx.author().face()))
+                    .description(description),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn autocomplete_reminder(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    get_database(ctx.serenity_context())
+        .await
+        .get_reminders(ctx.author().id)
+        .await
+        .into_iter()
+        .filter(|r| r.id.to_string().starts_with(partial) || r.text.contains(partial))
+        .take(25)
+        .map(|r| AutocompleteChoice::new(format!("#{} {}", r.id, preview(&r.text)), r.id))
+        .collect()
+}
+
+/// Reminds you of something later! ⏰
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("remind_in", "list", "cancel"),
+    subcommand_required
+)]
+#[allow(clippy::unused_async)]
+async fn remind(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Sets a reminder! ⏰
+#[poise::command(slash_command, prefix_command, rename = "in")]
+async fn remind_in(
+    ctx: Context<'_>,
+    #[description = "When to remind you, such as 10m or 1h30m"] duration: String,
+    #[description = "What to remind you of"]
+    #[max_length = 1000]
+    #[rest]
+    text: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(delay) = parse_duration(&duration) else {
+        let msg = lang.tr_with("invalid-duration", &fluent_args!["value" => duration]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    };
+
+    if delay > MAX_DELAY {
+        send_error_msg(ctx, &lang.tr("reminder-too-long")).await;
+        return Ok(());
+    }
+
+    let db = get_database(ctx.serenity_context()).await;
+    if db.get_reminders(ctx.author().id).await.len() >= MAX_REMINDERS {
+        let msg = lang.tr_with("reminder-limit", &fluent_args!["max" => MAX_REMINDERS]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let reminder = Reminder {
+        id: 0,
+        user_id: ctx.author().id,
+        guild_id: ctx.guild_id(),
+        // Reminders set in DMs are sent by DM
+        channel_id: ctx.guild_id().map(|_| ctx.channel_id()),
+        text,
+        created_at: now,
+        remind_at: now + delay,
+    };
+
+    let reminders = get_reminders(ctx.serenity_context()).await;
+    let Some(id) = reminders.add(&reminder).await else {
+        send_error_msg(ctx, &lang.tr("reminder-add-failed")).await;
+        return Ok(());
+    };
+
+    let description = lang.tr_with(
+        "reminder-added",
+        &fluent_args![
+            "time" => relative_time(reminder.remind_at),
+            "id" => id,
+        ],
+    );
+
+    send_reply(ctx, "reminder-title", description).await
+}
+
+/// Lists your reminders! 📋
+#[poise::command(slash_command, prefix_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let reminders = get_database(ctx.serenity_context())
+        .await
+        .get_reminders(ctx.author().id)
+        .await;
+
+    let description = if reminders.is_empty() {
+        lang.tr("reminders-empty")
+    } else {
+        reminders
+            .iter()
+            .map(|r| {
+                lang.tr_with(
+                    "reminders-entry",
+                    &fluent_args![
+                        "id" => r.id,
+                        "time" => relative_time(r.remind_at),
+                        "text" => preview(&r.text),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    send_reply(ctx, "reminders-title", description).await
+}
+
+/// Cancels one of your reminders! 🗑️
+#[poise::command(slash_command, prefix_command)]
+async fn cancel(
+    ctx: Context<'_>,
+    #[description = "The reminder to cancel"]
+    #[autocomplete = "autocomplete_reminder"]
+    id: i64,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let db = get_database(ctx.serenity_context()).await;
+
+    if !db.cancel_reminder(ctx.author().id, id).await {
+        let msg = lang.tr_with("reminder-not-found", &fluent_args!["id" => id]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let description = lang.tr_with("reminder-cancelled", &fluent_args!["id" => id]);
+    send_reply(ctx, "reminder-title", description).await
+}
+
+pub fn commands() -> [Command; 1] {
+    [remind()]
+}
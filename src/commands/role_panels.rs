@@ -0,0 +1,349 @@
+use crate::{
+    database::get_database,
+    embeds::{embed, get_theme, join_limited, EmbedKind, DESCRIPTION_LIMIT},
+    i18n::{get_guild_lang, get_lang, Lang},
+    role_panels::{panel_components, panel_embed, parse_emoji, MAX_ROLES},
+    structs::{Command, Context, Error, Guild, PanelRole, RolePanel, RolePanelKind},
+    utils::{check_assignable, send_error_msg},
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{
+        AutocompleteChoice, CreateEmbedAuthor, CreateMessage, EditMessage, GuildChannel, MessageId,
+        Role,
+    },
+    CreateReply,
+};
+use tracing::error;
+
+async fn autocomplete_panel(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+
+    get_database(ctx.serenity_context())
+        .await
+        .get_role_panels(guild_id)
+        .await
+        .into_iter()
+        .filter(|p| {
+            p.id.to_string().starts_with(&partial) || p.title.to_lowercase().contains(&partial)
+        })
+        .take(25)
+        .map(|p| AutocompleteChoice::new(format!("#{} {}", p.id, p.title), p.id))
+        .collect()
+}
+
+async fn find_panel(ctx: Context<'_>, lang: &Lang, id: i64) -> Option<RolePanel> {
+    let panel = get_database(ctx.serenity_context())
+        .await
+        .get_role_panel(ctx.guild_id().unwrap(), id)
+        .await;
+
+    if panel.is_none() {
+        let msg = lang.tr_with("rolepanel-not-found", &fluent_args!["id" => id]);
+        send_error_msg(ctx, &msg).await;
+    }
+
+    panel
+}
+
+/// Updates the panel's message to match what's stored, telling the user if
+/// it can't be edited anymore.
+async fn refresh(ctx: Context<'_>, lang: &Lang, panel_id: i64) -> bool {
+    let Some(panel) = get_database(ctx.serenity_context())
+        .await
+        .get_role_panel(ctx.guild_id().unwrap(), panel_id)
+        .await
+    else {
+        return false;
+    };
+
+    let theme = get_theme(ctx).await;
+    let panel_lang = get_guild_lang(ctx).await;
+    let icon = ctx.guild().unwrap().icon_url();
+
+    let edited = panel
+        .channel_id
+        .edit_message(
+            ctx,
+            panel.message_id,
+            EditMessage::new()
+                .embed(panel_embed(&theme, &panel_lang, &panel, icon))
+                .components(panel_components(&panel_lang, &panel)),
+        )
+        .await;
+
+    if let Err(e) = edited {
+        error!("Error updating role panel {}: {}", panel.id, e);
+        send_error_msg(ctx, &lang.tr("rolepanel-message-missing")).await;
+        return false;
+    }
+
+    true
+}
+
+async fn send_success(ctx: Context<'_>, lang: &Lang, id: &str, panel_id: i64) -> Result<(), Error> {
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Success).await.author(
+                CreateEmbedAuthor::new(lang.tr_with(id, &fluent_args!["id" => panel_id]))
+                    .icon_url(ctx.author().face()),
+            ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Self-service role panels! 🎭
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("create", "add", "remove", "delete", "list"),
+    subcommand_required,
+    required_permissions = "MANAGE_ROLES",
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn rolepanel(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Posts a new role panel, add roles to it with /rolepanel add! 🎭
+#[poise::command(slash_command, prefix_command)]
+async fn create(
+    ctx: Context<'_>,
+    #[description = "Buttons or a select menu"] kind: RolePanelKind,
+    #[description = "The panel's title"]
+    #[max_length = 256]
+    title: String,
+    #[description = "The channel to post it in"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+    #[description = "Text shown above the roles"]
+    #[max_length = 2000]
+    #[rest]
+    description: Option<String>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let channel_id = channel.map_or(ctx.channel_id(), |channel| channel.id);
+
+    let mut panel = RolePanel {
+        id: 0,
+        guild_id,
+        channel_id,
+        message_id: MessageId::default(),
+        kind,
+        title,
+        description,
+        roles: Vec::new(),
+    };
+
+    let theme = get_theme(ctx).await;
+    let panel_lang = get_guild_lang(ctx).await;
+    let icon = ctx.guild().unwrap().icon_url();
+
+    let message = match channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new().embed(panel_embed(&theme, &panel_lang, &panel, icon)),
+        )
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Error posting role panel: {}", e);
+            let msg = lang.tr_with(
+                "rolepanel-send-failed",
+                &fluent_args!["channel" => format!("<#{channel_id}>")],
+            );
+            send_error_msg(ctx, &msg).await;
+            return Ok(());
+        }
+    };
+    panel.message_id = message.id;
+
+    let db = get_database(ctx.serenity_context()).await;
+    db.insert_guild(&Guild::new(guild_id)).await;
+
+    let Some(id) = db.create_role_panel(&panel).await else {
+        if let Err(e) = message.delete(ctx).await {
+            error!("Error: {}", e);
+        }
+        send_error_msg(ctx, &lang.tr("rolepanel-create-failed")).await;
+        return Ok(());
+    };
+
+    send_success(ctx, &lang, "rolepanel-created", id).await
+}
+
+/// Adds a role to a panel, or changes how it's shown! ➕
+#[poise::command(slash_command, prefix_command)]
+async fn add(
+    ctx: Context<'_>,
+    #[description = "The panel to add it to"]
+    #[autocomplete = "autocomplete_panel"]
+    panel: i64,
+    #[description = "The role members can toggle"] role: Role,
+    #[description = "The button or option text, the role's name by default"]
+    #[max_length = 80]
+    label: Option<String>,
+    #[description = "An emoji shown next to it"] emoji: Option<String>,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(panel) = find_panel(ctx, &lang, panel).await else {
+        return Ok(());
+    };
+
+    let listed = panel.roles.iter().any(|r| r.role_id == role.id);
+    if !listed && panel.roles.len() >= MAX_ROLES {
+        let msg = lang.tr_with("rolepanel-full", &fluent_args!["max" => MAX_ROLES]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    if !check_assignable(ctx, &lang, &role).await? {
+        return Ok(());
+    }
+
+    if let Some(emoji) = emoji.as_deref().filter(|e| parse_emoji(e).is_none()) {
+        let msg = lang.tr_with("rolepanel-invalid-emoji", &fluent_args!["emoji" => emoji]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    get_database(ctx.serenity_context())
+        .await
+        .add_panel_role(
+            panel.id,
+            &PanelRole {
+                role_id: role.id,
+                label: label.unwrap_or_else(|| role.name.clone()),
+                emoji: emoji.map(|e| e.trim().to_string()),
+            },
+        )
+        .await;
+
+    if refresh(ctx, &lang, panel.id).await {
+        send_success(ctx, &lang, "rolepanel-updated", panel.id).await?;
+    }
+
+    Ok(())
+}
+
+/// Removes a role from a panel! ➖
+#[poise::command(slash_command, prefix_command)]
+async fn remove(
+    ctx: Context<'_>,
+    #[description = "The panel to remove it from"]
+    #[autocomplete = "autocomplete_panel"]
+    panel: i64,
+    #[description = "The role to remove"] role: Role,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(panel) = find_panel(ctx, &lang, panel).await else {
+        return Ok(());
+    };
+
+    let removed = get_database(ctx.serenity_context())
+        .await
+        .remove_panel_role(panel.id, role.id)
+        .await;
+
+    if !removed {
+        let msg = lang.tr_with(
+            "rolepanel-role-missing",
+            &fluent_args!["role" => format!("<@&{}>", role.id), "id" => panel.id],
+        );
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    if refresh(ctx, &lang, panel.id).await {
+        send_success(ctx, &lang, "rolepanel-updated", panel.id).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes a panel along with its message! 🗑️
+#[poise::command(slash_command, prefix_command)]
+async fn delete(
+    ctx: Context<'_>,
+    #[description = "The panel to delete"]
+    #[autocomplete = "autocomplete_panel"]
+    panel: i64,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(panel) = find_panel(ctx, &lang, panel).await else {
+        return Ok(());
+    };
+
+    get_database(ctx.serenity_context())
+        .await
+        .delete_role_panel(panel.guild_id, panel.id)
+        .await;
+
+    // The message may already be gone, which is fine
+    if let Err(e) = panel.channel_id.delete_message(ctx, panel.message_id).await {
+        error!("Error deleting role panel message: {}", e);
+    }
+
+    send_success(ctx, &lang, "rolepanel-deleted", panel.id).await
+}
+
+/// Lists this server's role panels! 📋
+#[poise::command(slash_command, prefix_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let panels = get_database(ctx.serenity_context())
+        .await
+        .get_role_panels(ctx.guild_id().unwrap())
+        .await;
+
+    let description = if panels.is_empty() {
+        lang.tr("rolepanels-empty")
+    } else {
+        join_limited(
+            panels.iter().map(|p| {
+                lang.tr_with(
+                    "rolepanels-entry",
+                    &fluent_args![
+                        "id" => p.id,
+                        "title" => p.title.as_str(),
+                        "link" => p.message_id.link(p.channel_id, Some(p.guild_id)),
+                        "count" => p.roles.len(),
+                    ],
+                )
+            }),
+            "\n",
+            DESCRIPTION_LIMIT,
+        )
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Primary)
+                .await
+                .author(
+                    CreateEmbedAuthor::new(lang.tr("rolepanels-title"))
+                        .icon_url(ctx.author().face()),
+                )
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 1] {
+    [rolepanel()]
+}
@@ -0,0 +1,328 @@
+use crate::{
+    database::get_database,
+    embeds::{embed, get_theme, join_limited, EmbedKind, DESCRIPTION_LIMIT},
+    i18n::{get_lang, Lang},
+    structs::{Command, Context, Error, Guild, Tag},
+    tags::{allowed_mentions, body, normalize_name, Placeholders, TagBody, NAME_LIMIT},
+    utils::send_error_msg,
+};
+use fluent::fluent_args;
+use poise::{serenity_prelude::CreateEmbedAuthor, CreateReply};
+use sqlx::types::time::OffsetDateTime;
+
+/// How a tag is posted.
+#[derive(poise::ChoiceParameter, Clone, Copy, PartialEq, Eq)]
+enum TagFormat {
+    Text,
+    Embed,
+}
+
+async fn autocomplete_tag(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+
+    get_database(ctx.serenity_context())
+        .await
+        .get_tags(guild_id)
+        .await
+        .into_iter()
+        .map(|tag| tag.name)
+        .filter(|name| name.contains(&partial))
+        .take(25)
+        .collect()
+}
+
+/// Finds the tag, telling the user if there is none by that name.
+async fn find_tag(ctx: Context<'_>, lang: &Lang, name: &str) -> Option<Tag> {
+    let name = name.trim().to_lowercase();
+    let tag = get_database(ctx.serenity_context())
+        .await
+        .get_tag(ctx.guild_id().unwrap(), &name)
+        .await;
+
+    if tag.is_none() {
+        let msg = lang.tr_with("tag-not-found", &fluent_args!["name" => name]);
+        send_error_msg(ctx, &msg).await;
+    }
+
+    tag
+}
+
+async fn send_success(ctx: Context<'_>, lang: &Lang, id: &str, name: &str) -> Result<(), Error> {
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Success).await.author(
+                CreateEmbedAuthor::new(lang.tr_with(id, &fluent_args!["name" => name]))
+                    .icon_url(ctx.author().face()),
+            ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Snippets anyone in this server can post! 🏷️
+#[poise::command(
+    slash_command,
+    prefix_command,
+    subcommands("create", "edit", "delete", "list", "info", "show"),
+    subcommand_required,
+    guild_only = true
+)]
+#[allow(clippy::unused_async)]
+async fn tag(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Creates a tag! 🏷️
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MANAGE_MESSAGES"
+)]
+async fn create(
+    ctx: Context<'_>,
+    #[description = "The tag's name"]
+    #[max_length = 32]
+    name: String,
+    #[description = "Post it as plain text or in an embed"] format: TagFormat,
+    #[description = "What to post, {user}, {user.name}, {server} and {channel} are filled in"]
+    #[max_length = 2000]
+    #[rest]
+    content: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(name) = normalize_name(&name) else {
+        let msg = lang.tr_with(
+            "tag-invalid-name",
+            &fluent_args!["name" => name, "max" => NAME_LIMIT],
+        );
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    };
+
+    // Tags are also text commands, which would never run under a command's name
+    let commands = &ctx.framework().options().commands;
+    if commands
+        .iter()
+        .any(|c| c.name == name || c.aliases.contains(&name))
+    {
+        let msg = lang.tr_with("tag-command-name", &fluent_args!["name" => name]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id().unwrap();
+    let db = get_database(ctx.serenity_context()).await;
+    db.insert_guild(&Guild::new(guild_id)).await;
+
+    let created = db
+        .create_tag(&Tag {
+            guild_id,
+            name: name.clone(),
+            content,
+            embed: format == TagFormat::Embed,
+            owner_id: ctx.author().id,
+            uses: 0,
+            created_at: OffsetDateTime::now_utc(),
+        })
+        .await;
+
+    if !created {
+        let msg = lang.tr_with("tag-exists", &fluent_args!["name" => name]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(());
+    }
+
+    send_success(ctx, &lang, "tag-created", &name).await
+}
+
+/// Changes what a tag posts! ✏️
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MANAGE_MESSAGES"
+)]
+async fn edit(
+    ctx: Context<'_>,
+    #[description = "The tag to edit"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+    #[description = "Post it as plain text or in an embed"] format: TagFormat,
+    #[description = "What to post, {user}, {user.name}, {server} and {channel} are filled in"]
+    #[max_length = 2000]
+    #[rest]
+    content: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(tag) = find_tag(ctx, &lang, &name).await else {
+        return Ok(());
+    };
+
+    get_database(ctx.serenity_context())
+        .await
+        .update_tag(&Tag {
+            content,
+            embed: format == TagFormat::Embed,
+            ..tag.clone()
+        })
+        .await;
+
+    send_success(ctx, &lang, "tag-updated", &tag.name).await
+}
+
+/// Deletes a tag! 🗑️
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "MANAGE_MESSAGES"
+)]
+async fn delete(
+    ctx: Context<'_>,
+    #[description = "The tag to delete"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(tag) = find_tag(ctx, &lang, &name).await else {
+        return Ok(());
+    };
+
+    get_database(ctx.serenity_context())
+        .await
+        .delete_tag(tag.guild_id, &tag.name)
+        .await;
+
+    send_success(ctx, &lang, "tag-deleted", &tag.name).await
+}
+
+/// Lists this server's tags! 📋
+#[poise::command(slash_command, prefix_command)]
+async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+    let tags = get_database(ctx.serenity_context())
+        .await
+        .get_tags(ctx.guild_id().unwrap())
+        .await;
+
+    let description = if tags.is_empty() {
+        lang.tr("tags-empty")
+    } else {
+        join_limited(
+            tags.iter().map(|tag| format!("`{}`", tag.name)),
+            ", ",
+            DESCRIPTION_LIMIT,
+        )
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Primary)
+                .await
+                .author(
+                    CreateEmbedAuthor::new(
+                        lang.tr_with("tags-title", &fluent_args!["count" => tags.len()]),
+                    )
+                    .icon_url(ctx.author().face()),
+                )
+                .description(description),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Shows who made a tag and how often it's used! ℹ️
+#[poise::command(slash_command, prefix_command)]
+async fn info(
+    ctx: Context<'_>,
+    #[description = "The tag to show info about"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(tag) = find_tag(ctx, &lang, &name).await else {
+        return Ok(());
+    };
+
+    ctx.send(
+        CreateReply::default().embed(
+            embed(ctx, EmbedKind::Primary)
+                .await
+                .author(
+                    CreateEmbedAuthor::new(
+                        lang.tr_with("tag-info-title", &fluent_args!["name" => tag.name.as_str()]),
+                    )
+                    .icon_url(ctx.author().face()),
+                )
+                .field(lang.tr("tag-owner"), format!("<@{}>", tag.owner_id), true)
+                .field(lang.tr("tag-uses"), tag.uses.to_string(), true)
+                .field(
+                    lang.tr("tag-format"),
+                    lang.tr_with(
+                        "tag-format-value",
+                        &fluent_args!["embed" => tag.embed.to_string()],
+                    ),
+                    true,
+                )
+                .field(
+                    lang.tr("tag-created-at"),
+                    format!("<t:{}:F>", tag.created_at.unix_timestamp()),
+                    true,
+                ),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Posts a tag! 🏷️
+#[poise::command(slash_command, prefix_command)]
+async fn show(
+    ctx: Context<'_>,
+    #[description = "The tag to post"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+) -> Result<(), Error> {
+    let lang = get_lang(ctx).await;
+
+    let Some(tag) = find_tag(ctx, &lang, &name).await else {
+        return Ok(());
+    };
+
+    let server = ctx.guild().unwrap().name.clone();
+    let theme = get_theme(ctx).await;
+    let placeholders = Placeholders {
+        user: ctx.author(),
+        server: &server,
+        channel_id: ctx.channel_id(),
+    };
+
+    let reply = match body(&theme, &tag, &placeholders) {
+        TagBody::Text(text) => CreateReply::default().content(text),
+        TagBody::Embed(embed) => CreateReply::default().embed(*embed),
+    };
+
+    ctx.send(reply.allowed_mentions(allowed_mentions(ctx.author())))
+        .await?;
+
+    get_database(ctx.serenity_context())
+        .await
+        .use_tag(tag.guild_id, &tag.name)
+        .await;
+
+    Ok(())
+}
+
+pub fn commands() -> [Command; 1] {
+    [tag()]
+}
@@ -0,0 +1,150 @@
+use crate::{
+    providers::ProviderConfig,
+    structs::{DbConfig, HealthConfig, ShardConfig},
+};
+use std::{env::var, fs, str::FromStr, time::Duration};
+use tracing::info;
+
+fn parse_var<T: FromStr>(key: &str, default: &str) -> T {
+    var(key)
+        .unwrap_or_else(|_| String::from(default))
+        .parse::<T>()
+        .unwrap_or_else(|_| panic!("{key} env is not of type {}", std::any::type_name::<T>()))
+}
+
+pub fn db_config() -> DbConfig {
+    let host = var("DATABASE_HOST").expect("missing DATABASE_HOST env");
+    let port = var("DATABASE_PORT")
+        .expect("missing DATABASE_PORT env")
+        .parse::<u16>()
+        .expect("DATABASE_PORT env is not of type u16");
+    let username = var("DATABASE_USER").expect("missing DATABASE_USER env");
+    let password = var("DATABASE_PASS").expect("missing DATABASE_PASS env");
+
+    DbConfig {
+        host,
+        port,
+        username,
+        password,
+    }
+}
+
+/// Returns `None` when the health endpoints are disabled.
+pub fn health_config() -> Option<HealthConfig> {
+    if !parse_var::<bool>("HEALTH_ENABLED", "true") {
+        return None;
+    }
+
+    Some(HealthConfig {
+        port: parse_var("HEALTH_PORT", "8080"),
+        max_disconnect: Duration::from_secs(parse_var("HEALTH_MAX_DISCONNECT_SECS", "60")),
+    })
+}
+
+/// How long shutdown waits for in-flight commands, and then for background jobs.
+pub fn shutdown_grace() -> Duration {
+    Duration::from_secs(parse_var("SHUTDOWN_TIMEOUT_SECS", "30"))
+}
+
+/// `SHARD_COUNT` unset or `auto` uses autosharding. Otherwise `SHARD_RANGE`, in the
+/// form `first-last`, selects which of the `SHARD_COUNT` shards this process runs.
+/// A range with autosharding is rejected, since every process would run every shard.
+pub fn shard_config() -> ShardConfig {
+    let range = var("SHARD_RANGE").ok().filter(|range| !range.is_empty());
+
+    let total = match var("SHARD_COUNT").as_deref() {
+        Err(_) | Ok("auto") => {
+            assert!(
+                range.is_none(),
+                "SHARD_RANGE env needs SHARD_COUNT env to be set to a number"
+            );
+            return ShardConfig::Auto;
+        }
+        Ok(count) => count
+            .parse::<u32>()
+            .expect("SHARD_COUNT env is not of type u32"),
+    };
+
+    assert!(total > 0, "SHARD_COUNT env must be at least 1");
+
+    let (first, last) = match range {
+        None => (0, total - 1),
+        Some(range) => {
+            let (first, last) = range
+                .split_once('-')
+                .expect("SHARD_RANGE env must be in the form first-last");
+            (
+                first
+                    .trim()
+                    .parse::<u32>()
+                    .expect("SHARD_RANGE start is not of type u32"),
+                last.trim()
+                    .parse::<u32>()
+                    .expect("SHARD_RANGE end is not of type u32"),
+            )
+        }
+    };
+
+    assert!(
+        first <= last && last < total,
+        "SHARD_RANGE env must be within 0-{}",
+        total - 1
+    );
+
+    ShardConfig::Range { first, last, total }
+}
+
+/// Cooldowns at least this long are stored in the database so they survive restarts.
+pub fn cooldown_persist_after() -> Duration {
+    Duration::from_secs(parse_var("COOLDOWN_PERSIST_SECS", "60"))
+}
+
+/// How often cooldowns that ran out are deleted from the database.
+pub fn cooldown_cleanup() -> Duration {
+    Duration::from_secs(parse_var("COOLDOWN_CLEANUP_SECS", "3600"))
+}
+
+/// How far ahead the reminder scheduler looks, and so how often it checks for
+/// reminders added by other processes.
+pub fn reminder_poll() -> Duration {
+    Duration::from_secs(parse_var("REMINDER_POLL_SECS", "60"))
+}
+
+/// How far ahead the poll scheduler looks, and so how often it checks for
+/// polls started by other processes.
+pub fn poll_check() -> Duration {
+    Duration::from_secs(parse_var("POLL_CHECK_SECS", "60"))
+}
+
+/// How far ahead the giveaway scheduler looks, and so how often it checks for
+/// giveaways started by other processes.
+pub fn giveaway_check() -> Duration {
+    Duration::from_secs(parse_var("GIVEAWAY_CHECK_SECS", "60"))
+}
+
+/// How often XP earned from messages is written to the database.
+pub fn xp_flush() -> Duration {
+    Duration::from_secs(parse_var("XP_FLUSH_SECS", "30"))
+}
+
+/// Prefix for text commands in guilds that haven't set their own.
+pub fn default_prefix() -> String {
+    var("PREFIX").unwrap_or_else(|_| String::from("!"))
+}
+
+/// Reads the image providers from `IMAGE_PROVIDERS_PATH` (default `providers.json`),
+/// falling back to the providers bundled with the binary when the file is missing.
+pub fn image_providers() -> Vec<ProviderConfig> {
+    let path = var("IMAGE_PROVIDERS_PATH").unwrap_or_else(|_| String::from("providers.json"));
+
+    let contents = fs::read_to_string(&path).unwrap_or_else(|_| {
+        info!(
+            "No image provider config at {}, using the bundled one",
+            path
+        );
+        String::from(include_str!("../providers.json"))
+    });
+
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|e| panic!("Image provider config {path} is invalid: {e}"))
+}
@@ -0,0 +1,269 @@
+use crate::{
+    database::Database,
+    embeds::{embed, EmbedKind},
+    i18n::get_lang,
+    shutdown::Shutdown,
+    structs::{Context, CooldownBucket, CooldownKey, CooldownPolicy, Guild},
+    utils::get_cooldowns,
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{ChannelId, CreateEmbedAuthor, GuildId, UserId},
+    CooldownConfig, CreateReply,
+};
+use sqlx::types::time::OffsetDateTime;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::time::sleep;
+use tracing::error;
+
+/// Who used a command and where, as far as cooldowns are concerned.
+pub struct Invocation<'a> {
+    pub command: &'a str,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+}
+
+impl Invocation<'_> {
+    fn key(&self, bucket: CooldownBucket) -> Option<CooldownKey> {
+        let target_id = match bucket {
+            CooldownBucket::User => self.user_id.get(),
+            CooldownBucket::Channel => self.channel_id.get(),
+            CooldownBucket::Guild => self.guild_id?.get(),
+        };
+
+        Some(CooldownKey {
+            scope_id: self.guild_id,
+            command: self.command.to_string(),
+            bucket,
+            target_id,
+        })
+    }
+}
+
+/// Tracks command cooldowns. Each command's defaults come from its `*_cooldown`
+/// attributes and can be overridden per guild. Running cooldowns live in memory,
+/// and those at least `persist_after` long are also stored so they survive restarts.
+pub struct Cooldowns {
+    db: Arc<dyn Database>,
+    persist_after: Duration,
+    active: Mutex<HashMap<CooldownKey, OffsetDateTime>>,
+    policies: RwLock<HashMap<GuildId, Vec<CooldownPolicy>>>,
+}
+
+impl Cooldowns {
+    pub fn new(db: Arc<dyn Database>, persist_after: Duration) -> Self {
+        Self {
+            db,
+            persist_after,
+            active: Mutex::new(HashMap::new()),
+            policies: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn policies(&self, guild_id: GuildId) -> Vec<CooldownPolicy> {
+        if let Some(policies) = self.policies.read().unwrap().get(&guild_id) {
+            return policies.clone();
+        }
+
+        let policies = self.db.get_cooldown_policies(guild_id).await;
+        self.policies
+            .write()
+            .unwrap()
+            .insert(guild_id, policies.clone());
+
+        policies
+    }
+
+    pub async fn set_policy(&self, policy: &CooldownPolicy) {
+        self.db.insert_guild(&Guild::new(policy.guild_id)).await;
+        self.db.set_cooldown_policy(policy).await;
+        self.policies.write().unwrap().remove(&policy.guild_id);
+    }
+
+    pub async fn remove_policy(&self, guild_id: GuildId, command: &str, bucket: CooldownBucket) {
+        self.db
+            .remove_cooldown_policy(guild_id, command, bucket)
+            .await;
+        self.policies.write().unwrap().remove(&guild_id);
+    }
+
+    /// The command's default cooldowns with the guild's overrides applied.
+    pub async fn durations(
+        &self,
+        command: &str,
+        guild_id: Option<GuildId>,
+        defaults: &CooldownConfig,
+    ) -> Vec<(CooldownBucket, Duration)> {
+        let mut durations = HashMap::new();
+
+        for (bucket, duration) in [
+            (CooldownBucket::User, defaults.user),
+            (CooldownBucket::Channel, defaults.channel),
+            (CooldownBucket::Guild, defaults.guild),
+        ] {
+            if let Some(duration) = duration {
+                durations.insert(bucket, duration);
+            }
+        }
+
+        if let Some(guild_id) = guild_id {
+            for policy in self.policies(guild_id).await {
+                if policy.command == command {
+                    durations.insert(policy.bucket, policy.duration);
+                }
+            }
+        }
+
+        durations
+            .into_iter()
+            .filter(|(_, duration)| !duration.is_zero())
+            .collect()
+    }
+
+    /// Loads a stored cooldown that isn't running in memory yet, such as one
+    /// started before a restart.
+    async fn load(&self, key: &CooldownKey, duration: Duration) {
+        if duration < self.persist_after || self.active.lock().unwrap().contains_key(key) {
+            return;
+        }
+
+        if let Some(expires_at) = self.db.get_cooldown(key).await {
+            self.active
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_insert(expires_at);
+        }
+    }
+
+    /// Returns how long is left if any of the invocation's cooldowns is still
+    /// running. Otherwise starts them all and returns `None`.
+    pub async fn hit(
+        &self,
+        invocation: &Invocation<'_>,
+        defaults: &CooldownConfig,
+    ) -> Option<Duration> {
+        let durations: Vec<_> = self
+            .durations(invocation.command, invocation.guild_id, defaults)
+            .await
+            .into_iter()
+            .filter_map(|(bucket, duration)| Some((invocation.key(bucket)?, duration)))
+            .collect();
+
+        for (key, duration) in &durations {
+            self.load(key, *duration).await;
+        }
+
+        // Checking and starting happen under one lock, so invocations arriving
+        // together can't both get through
+        let now = OffsetDateTime::now_utc();
+        {
+            let mut active = self.active.lock().unwrap();
+            let remaining = durations
+                .iter()
+                .filter_map(|(key, _)| active.get(key))
+                .map(|expires_at| Duration::try_from(*expires_at - now).unwrap_or_default())
+                .max()
+                .unwrap_or_default();
+
+            if !remaining.is_zero() {
+                return Some(remaining);
+            }
+
+            for (key, duration) in &durations {
+                active.insert(key.clone(), now + *duration);
+            }
+        }
+
+        for (key, duration) in durations {
+            if duration >= self.persist_after {
+                self.db.set_cooldown(&key, now + duration).await;
+            }
+        }
+
+        None
+    }
+
+    /// Forgets cooldowns that ran out, in memory and in the database.
+    pub async fn prune(&self) {
+        let now = OffsetDateTime::now_utc();
+        self.active
+            .lock()
+            .unwrap()
+            .retain(|_, expires_at| *expires_at > now);
+
+        self.db.delete_expired_cooldowns().await;
+    }
+}
+
+/// Prunes cooldowns that ran out every `interval`, so starting one doesn't
+/// have to.
+pub fn spawn(cooldowns: Arc<Cooldowns>, interval: Duration, shutdown: &Shutdown) {
+    let token = shutdown.token();
+
+    shutdown.jobs().spawn(async move {
+        loop {
+            tokio::select! {
+                () = sleep(interval) => {}
+                () = token.cancelled() => break,
+            }
+
+            cooldowns.prune().await;
+        }
+    });
+}
+
+/// Command check that refuses commands on cooldown with a message saying
+/// when they can be used again.
+pub async fn check(ctx: Context<'_>) -> bool {
+    let command = ctx.command();
+    let invocation = Invocation {
+        command: &command.qualified_name,
+        guild_id: ctx.guild_id(),
+        channel_id: ctx.channel_id(),
+        user_id: ctx.author().id,
+    };
+
+    let cooldowns = get_cooldowns(ctx.serenity_context()).await;
+    let defaults = command.cooldown_config.read().unwrap().clone();
+
+    let Some(remaining) = cooldowns.hit(&invocation, &defaults).await else {
+        return true;
+    };
+
+    let ready_at = OffsetDateTime::now_utc() + remaining;
+    let lang = get_lang(ctx).await;
+    let description = lang.tr_with(
+        "cooldown-description",
+        &fluent_args![
+            "command" => command.qualified_name.as_str(),
+            "time" => format!("<t:{}:R>", ready_at.unix_timestamp() + 1),
+        ],
+    );
+
+    if let Err(e) = ctx
+        .send(
+            CreateReply::default()
+                .embed(
+                    embed(ctx, EmbedKind::Error)
+                        .await
+                        .author(
+                            CreateEmbedAuthor::new(lang.tr("cooldown-title"))
+                                .icon_url(ctx.author().face()),
+                        )
+                        .description(description),
+                )
+                .ephemeral(true),
+        )
+        .await
+    {
+        error!("Error: {}", e);
+    }
+
+    false
+}
@@ -0,0 +1,460 @@
+use super::{
+    CooldownRepository, Database, GiveawayRepository, GuildRepository, InfractionRepository,
+    LevelRepository, PermissionRepository, PollRepository, ReminderRepository, RolePanelRepository,
+    SettingsRepository, StarboardRepository, TagRepository, UserSettingsRepository,
+};
+use crate::structs::{
+    CooldownBucket, CooldownKey, CooldownPolicy, Giveaway, Guild, Infraction, LevelReward,
+    MemberXp, PanelRole, PermissionOverride, PermissionTarget, Poll, PollVote, Reminder, RolePanel,
+    StarboardPost, Tag,
+};
+use poise::serenity_prelude::{async_trait, GuildId, MessageId, RoleId, UserId};
+use sqlx::types::time::OffsetDateTime;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+/// How long a user's language is cached before it's read again.
+const USER_LOCALE_TTL: Duration = Duration::from_mins(15);
+/// At most this many users' languages are cached at once.
+pub const MAX_USER_LOCALES: usize = 10_000;
+
+struct CachedLocale {
+    locale: Option<String>,
+    cached_at: Instant,
+}
+
+/// What the cache knows about a guild.
+#[derive(Clone)]
+enum CachedGuild {
+    Stored(Guild),
+    /// The guild has no row, so it uses the default settings until it's written.
+    Missing,
+}
+
+/// Keeps guild settings in memory in front of another [`Database`]. Settings are
+/// loaded on first use, written through on update and evicted when the bot
+/// leaves a guild. Guilds without a row are cached too, so unconfigured guilds
+/// don't cost a query per message, but lookups that failed are not.
+/// User language overrides are cached for a while, including users without one,
+/// but lookups that failed are not.
+pub struct SettingsCache {
+    inner: Arc<dyn Database>,
+    guilds: RwLock<HashMap<GuildId, CachedGuild>>,
+    user_locales: RwLock<HashMap<UserId, CachedLocale>>,
+}
+
+impl SettingsCache {
+    pub fn new(inner: Arc<dyn Database>) -> Self {
+        Self {
+            inner,
+            guilds: RwLock::new(HashMap::new()),
+            user_locales: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn evict(&self, guild_id: GuildId) {
+        self.guilds.write().unwrap().remove(&guild_id);
+    }
+
+    fn cached(&self, guild_id: GuildId) -> Option<CachedGuild> {
+        self.guilds.read().unwrap().get(&guild_id).cloned()
+    }
+
+    fn store(&self, guild: Guild) {
+        self.guilds
+            .write()
+            .unwrap()
+            .insert(guild.id, CachedGuild::Stored(guild));
+    }
+
+    fn store_user_locale(&self, user_id: UserId, locale: Option<String>) {
+        let mut user_locales = self.user_locales.write().unwrap();
+
+        if user_locales.len() >= MAX_USER_LOCALES && !user_locales.contains_key(&user_id) {
+            user_locales.retain(|_, cached| cached.cached_at.elapsed() < USER_LOCALE_TTL);
+        }
+
+        // Still full of fresh entries, so make room by dropping the older half
+        if user_locales.len() >= MAX_USER_LOCALES {
+            let mut ages: Vec<_> = user_locales.values().map(|c| c.cached_at).collect();
+            ages.sort_unstable();
+            let cutoff = ages[ages.len() / 2];
+            user_locales.retain(|_, cached| cached.cached_at > cutoff);
+        }
+
+        user_locales.insert(
+            user_id,
+            CachedLocale {
+                locale,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl GuildRepository for SettingsCache {
+    async fn insert_guild(&self, guild: &Guild) -> bool {
+        if let Ok(Some(_)) = self.get_guild(guild.id).await {
+            return true;
+        }
+
+        if !self.inner.insert_guild(guild).await {
+            return false;
+        }
+
+        // The guild may have existed after all if reading it failed, so read
+        // back whatever is stored instead of assuming it's this one
+        self.evict(guild.id);
+        true
+    }
+
+    async fn get_guild(&self, guild_id: GuildId) -> Result<Option<Guild>, sqlx::Error> {
+        match self.cached(guild_id) {
+            Some(CachedGuild::Stored(guild)) => return Ok(Some(guild)),
+            Some(CachedGuild::Missing) => return Ok(None),
+            None => {}
+        }
+
+        let guild = self.inner.get_guild(guild_id).await?;
+        let cached = guild
+            .clone()
+            .map_or(CachedGuild::Missing, CachedGuild::Stored);
+        self.guilds.write().unwrap().insert(guild_id, cached);
+
+        Ok(guild)
+    }
+}
+
+#[async_trait]
+impl InfractionRepository for SettingsCache {
+    async fn insert_infraction(&self, infraction: &Infraction) {
+        self.inner.insert_infraction(infraction).await;
+    }
+
+    async fn get_infractions(&self, guild_id: GuildId, member_id: UserId) -> Vec<Infraction> {
+        self.inner.get_infractions(guild_id, member_id).await
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for SettingsCache {
+    async fn get_settings(&self, guild_id: GuildId) -> Guild {
+        self.get_guild(guild_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| Guild::new(guild_id))
+    }
+
+    async fn update_settings(&self, guild: &Guild) -> bool {
+        // Caching settings that weren't saved would serve them until the next restart
+        if !self.inner.update_settings(guild).await {
+            return false;
+        }
+
+        self.store(guild.clone());
+        true
+    }
+}
+
+#[async_trait]
+impl CooldownRepository for SettingsCache {
+    async fn get_cooldown_policies(&self, guild_id: GuildId) -> Vec<CooldownPolicy> {
+        self.inner.get_cooldown_policies(guild_id).await
+    }
+
+    async fn set_cooldown_policy(&self, policy: &CooldownPolicy) {
+        self.inner.set_cooldown_policy(policy).await;
+    }
+
+    async fn remove_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        bucket: CooldownBucket,
+    ) {
+        self.inner
+            .remove_cooldown_policy(guild_id, command, bucket)
+            .await;
+    }
+
+    async fn get_cooldown(&self, key: &CooldownKey) -> Option<OffsetDateTime> {
+        self.inner.get_cooldown(key).await
+    }
+
+    async fn set_cooldown(&self, key: &CooldownKey, expires_at: OffsetDateTime) {
+        self.inner.set_cooldown(key, expires_at).await;
+    }
+
+    async fn delete_expired_cooldowns(&self) {
+        self.inner.delete_expired_cooldowns().await;
+    }
+}
+
+#[async_trait]
+impl UserSettingsRepository for SettingsCache {
+    async fn get_user_locale(&self, user_id: UserId) -> Result<Option<String>, sqlx::Error> {
+        if let Some(cached) = self
+            .user_locales
+            .read()
+            .unwrap()
+            .get(&user_id)
+            .filter(|cached| cached.cached_at.elapsed() < USER_LOCALE_TTL)
+        {
+            return Ok(cached.locale.clone());
+        }
+
+        let locale = self.inner.get_user_locale(user_id).await?;
+        self.store_user_locale(user_id, locale.clone());
+
+        Ok(locale)
+    }
+
+    async fn set_user_locale(&self, user_id: UserId, locale: Option<&str>) {
+        self.inner.set_user_locale(user_id, locale).await;
+        self.store_user_locale(user_id, locale.map(str::to_string));
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for SettingsCache {
+    async fn get_permission_overrides(&self, guild_id: GuildId) -> Vec<PermissionOverride> {
+        self.inner.get_permission_overrides(guild_id).await
+    }
+
+    async fn set_permission_override(&self, rule: &PermissionOverride) {
+        self.inner.set_permission_override(rule).await;
+    }
+
+    async fn remove_permission_override(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        target: PermissionTarget,
+        target_id: u64,
+    ) {
+        self.inner
+            .remove_permission_override(guild_id, command, target, target_id)
+            .await;
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for SettingsCache {
+    async fn add_reminder(&self, reminder: &Reminder) -> Option<i64> {
+        self.inner.add_reminder(reminder).await
+    }
+
+    async fn get_reminders(&self, user_id: UserId) -> Vec<Reminder> {
+        self.inner.get_reminders(user_id).await
+    }
+
+    async fn cancel_reminder(&self, user_id: UserId, id: i64) -> bool {
+        self.inner.cancel_reminder(user_id, id).await
+    }
+
+    async fn get_reminders_before(&self, until: OffsetDateTime) -> Vec<Reminder> {
+        self.inner.get_reminders_before(until).await
+    }
+
+    async fn delete_reminder(&self, id: i64) {
+        self.inner.delete_reminder(id).await;
+    }
+}
+
+#[async_trait]
+impl TagRepository for SettingsCache {
+    async fn get_tags(&self, guild_id: GuildId) -> Vec<Tag> {
+        self.inner.get_tags(guild_id).await
+    }
+
+    async fn get_tag(&self, guild_id: GuildId, name: &str) -> Option<Tag> {
+        self.inner.get_tag(guild_id, name).await
+    }
+
+    async fn create_tag(&self, tag: &Tag) -> bool {
+        self.inner.create_tag(tag).await
+    }
+
+    async fn update_tag(&self, tag: &Tag) {
+        self.inner.update_tag(tag).await;
+    }
+
+    async fn delete_tag(&self, guild_id: GuildId, name: &str) -> bool {
+        self.inner.delete_tag(guild_id, name).await
+    }
+
+    async fn use_tag(&self, guild_id: GuildId, name: &str) {
+        self.inner.use_tag(guild_id, name).await;
+    }
+}
+
+#[async_trait]
+impl RolePanelRepository for SettingsCache {
+    async fn create_role_panel(&self, panel: &RolePanel) -> Option<i64> {
+        self.inner.create_role_panel(panel).await
+    }
+
+    async fn get_role_panels(&self, guild_id: GuildId) -> Vec<RolePanel> {
+        self.inner.get_role_panels(guild_id).await
+    }
+
+    async fn get_role_panel(&self, guild_id: GuildId, id: i64) -> Option<RolePanel> {
+        self.inner.get_role_panel(guild_id, id).await
+    }
+
+    async fn add_panel_role(&self, panel_id: i64, role: &PanelRole) {
+        self.inner.add_panel_role(panel_id, role).await;
+    }
+
+    async fn remove_panel_role(&self, panel_id: i64, role_id: RoleId) -> bool {
+        self.inner.remove_panel_role(panel_id, role_id).await
+    }
+
+    async fn delete_role_panel(&self, guild_id: GuildId, id: i64) -> bool {
+        self.inner.delete_role_panel(guild_id, id).await
+    }
+}
+
+#[async_trait]
+impl LevelRepository for SettingsCache {
+    async fn add_xp(&self, gains: &[MemberXp]) -> Option<Vec<MemberXp>> {
+        self.inner.add_xp(gains).await
+    }
+
+    async fn get_xp(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+        self.inner.get_xp(guild_id, user_id).await
+    }
+
+    async fn count_above(&self, guild_id: GuildId, xp: i64) -> i64 {
+        self.inner.count_above(guild_id, xp).await
+    }
+
+    async fn count_ranked(&self, guild_id: GuildId) -> i64 {
+        self.inner.count_ranked(guild_id).await
+    }
+
+    async fn get_leaderboard(&self, guild_id: GuildId, offset: i64, limit: i64) -> Vec<MemberXp> {
+        self.inner.get_leaderboard(guild_id, offset, limit).await
+    }
+
+    async fn get_level_rewards(&self, guild_id: GuildId) -> Vec<LevelReward> {
+        self.inner.get_level_rewards(guild_id).await
+    }
+
+    async fn set_level_reward(&self, reward: &LevelReward) {
+        self.inner.set_level_reward(reward).await;
+    }
+
+    async fn remove_level_reward(&self, guild_id: GuildId, role_id: RoleId) -> bool {
+        self.inner.remove_level_reward(guild_id, role_id).await
+    }
+}
+
+#[async_trait]
+impl StarboardRepository for SettingsCache {
+    async fn get_starboard_post(&self, message_id: MessageId) -> Option<StarboardPost> {
+        self.inner.get_starboard_post(message_id).await
+    }
+
+    async fn set_starboard_post(&self, post: &StarboardPost) {
+        self.inner.set_starboard_post(post).await;
+    }
+
+    async fn delete_starboard_post(&self, message_id: MessageId) {
+        self.inner.delete_starboard_post(message_id).await;
+    }
+}
+
+#[async_trait]
+impl PollRepository for SettingsCache {
+    async fn create_poll(&self, poll: &Poll) -> bool {
+        self.inner.create_poll(poll).await
+    }
+
+    async fn get_poll(&self, message_id: MessageId) -> Option<Poll> {
+        self.inner.get_poll(message_id).await
+    }
+
+    async fn get_polls_closing_before(&self, until: OffsetDateTime) -> Vec<Poll> {
+        self.inner.get_polls_closing_before(until).await
+    }
+
+    async fn delete_poll(&self, message_id: MessageId) {
+        self.inner.delete_poll(message_id).await;
+    }
+
+    async fn get_poll_votes(&self, message_id: MessageId) -> Vec<PollVote> {
+        self.inner.get_poll_votes(message_id).await
+    }
+
+    async fn toggle_poll_vote(
+        &self,
+        message_id: MessageId,
+        user_id: UserId,
+        option: i16,
+    ) -> Option<Vec<i16>> {
+        self.inner
+            .toggle_poll_vote(message_id, user_id, option)
+            .await
+    }
+}
+
+#[async_trait]
+impl GiveawayRepository for SettingsCache {
+    async fn create_giveaway(&self, giveaway: &Giveaway) -> Option<i64> {
+        self.inner.create_giveaway(giveaway).await
+    }
+
+    async fn get_giveaway(&self, guild_id: GuildId, id: i64) -> Option<Giveaway> {
+        self.inner.get_giveaway(guild_id, id).await
+    }
+
+    async fn get_giveaway_by_message(&self, message_id: MessageId) -> Option<Giveaway> {
+        self.inner.get_giveaway_by_message(message_id).await
+    }
+
+    async fn get_giveaways(&self, guild_id: GuildId) -> Vec<Giveaway> {
+        self.inner.get_giveaways(guild_id).await
+    }
+
+    async fn get_giveaways_ending_before(&self, until: OffsetDateTime) -> Vec<Giveaway> {
+        self.inner.get_giveaways_ending_before(until).await
+    }
+
+    async fn add_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        self.inner.add_giveaway_entry(id, user_id).await
+    }
+
+    async fn remove_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        self.inner.remove_giveaway_entry(id, user_id).await
+    }
+
+    async fn get_giveaway_entries(&self, id: i64) -> Vec<UserId> {
+        self.inner.get_giveaway_entries(id).await
+    }
+
+    async fn count_giveaway_entries(&self, id: i64) -> i64 {
+        self.inner.count_giveaway_entries(id).await
+    }
+
+    async fn end_giveaway(&self, id: i64, seed: u64) -> Option<(Vec<UserId>, Vec<UserId>)> {
+        self.inner.end_giveaway(id, seed).await
+    }
+
+    async fn add_giveaway_draw(
+        &self,
+        id: i64,
+        seed: u64,
+        excluded_ids: &[UserId],
+        winner_ids: &[UserId],
+    ) {
+        self.inner
+            .add_giveaway_draw(id, seed, excluded_ids, winner_ids)
+            .await;
+    }
+}
@@ -0,0 +1,750 @@
+use super::{
+    CooldownRepository, GiveawayRepository, GuildRepository, InfractionRepository, LevelRepository,
+    PermissionRepository, PollRepository, ReminderRepository, RolePanelRepository,
+    SettingsRepository, StarboardRepository, TagRepository, UserSettingsRepository,
+};
+use crate::{
+    giveaways::draw,
+    polls::toggle,
+    structs::{
+        CooldownBucket, CooldownKey, CooldownPolicy, Giveaway, Guild, Infraction, LevelReward,
+        MemberXp, PanelRole, PermissionOverride, PermissionTarget, Poll, PollVote, Reminder,
+        RolePanel, StarboardPost, Tag,
+    },
+};
+use poise::serenity_prelude::{async_trait, GuildId, MessageId, RoleId, UserId};
+use sqlx::types::time::{OffsetDateTime, PrimitiveDateTime};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+/// A stored draw, which Postgres keeps in its own table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GiveawayDraw {
+    pub giveaway_id: i64,
+    pub seed: u64,
+    pub excluded_ids: Vec<UserId>,
+    pub winner_ids: Vec<UserId>,
+}
+
+/// Storage backed by plain collections, for exercising logic without Postgres.
+#[derive(Default)]
+pub struct MemoryDatabase {
+    pub guilds: Mutex<HashMap<GuildId, Guild>>,
+    pub infractions: Mutex<Vec<Infraction>>,
+    pub cooldown_policies: Mutex<Vec<CooldownPolicy>>,
+    pub cooldowns: Mutex<HashMap<CooldownKey, OffsetDateTime>>,
+    pub user_locales: Mutex<HashMap<UserId, String>>,
+    pub permission_overrides: Mutex<Vec<PermissionOverride>>,
+    pub reminders: Mutex<Vec<Reminder>>,
+    pub tags: Mutex<Vec<Tag>>,
+    pub role_panels: Mutex<Vec<RolePanel>>,
+    pub levels: Mutex<HashMap<(GuildId, UserId), i64>>,
+    pub level_rewards: Mutex<Vec<LevelReward>>,
+    pub starboard_posts: Mutex<HashMap<MessageId, StarboardPost>>,
+    pub polls: Mutex<Vec<Poll>>,
+    pub poll_votes: Mutex<HashMap<MessageId, Vec<PollVote>>>,
+    pub giveaways: Mutex<Vec<Giveaway>>,
+    pub giveaway_entries: Mutex<HashMap<i64, Vec<UserId>>>,
+    pub giveaway_draws: Mutex<Vec<GiveawayDraw>>,
+    /// Makes guild and settings writes fail, as if the database were down.
+    pub fail_writes: AtomicBool,
+    /// Makes guild and user language lookups fail, as if the database were down.
+    pub fail_reads: AtomicBool,
+}
+
+#[async_trait]
+impl GuildRepository for MemoryDatabase {
+    async fn insert_guild(&self, guild: &Guild) -> bool {
+        if self.fail_writes.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        self.guilds
+            .lock()
+            .unwrap()
+            .entry(guild.id)
+            .or_insert_with(|| guild.clone());
+        true
+    }
+
+    async fn get_guild(&self, guild_id: GuildId) -> Result<Option<Guild>, sqlx::Error> {
+        if self.fail_reads.load(Ordering::Relaxed) {
+            return Err(sqlx::Error::PoolTimedOut);
+        }
+
+        Ok(self.guilds.lock().unwrap().get(&guild_id).cloned())
+    }
+}
+
+#[async_trait]
+impl InfractionRepository for MemoryDatabase {
+    async fn insert_infraction(&self, infraction: &Infraction) {
+        let now = OffsetDateTime::now_utc();
+
+        self.infractions.lock().unwrap().push(Infraction {
+            created_at: Some(PrimitiveDateTime::new(now.date(), now.time())),
+            ..infraction.clone()
+        });
+    }
+
+    async fn get_infractions(&self, guild_id: GuildId, member_id: UserId) -> Vec<Infraction> {
+        self.infractions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|i| i.guild_id == guild_id && i.member_id == member_id)
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for MemoryDatabase {
+    async fn get_settings(&self, guild_id: GuildId) -> Guild {
+        self.get_guild(guild_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| Guild::new(guild_id))
+    }
+
+    async fn update_settings(&self, guild: &Guild) -> bool {
+        if self.fail_writes.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        self.guilds.lock().unwrap().insert(guild.id, guild.clone());
+        true
+    }
+}
+
+#[async_trait]
+impl CooldownRepository for MemoryDatabase {
+    async fn get_cooldown_policies(&self, guild_id: GuildId) -> Vec<CooldownPolicy> {
+        self.cooldown_policies
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.guild_id == guild_id)
+            .cloned()
+            .collect()
+    }
+
+    async fn set_cooldown_policy(&self, policy: &CooldownPolicy) {
+        self.remove_cooldown_policy(policy.guild_id, &policy.command, policy.bucket)
+            .await;
+        self.cooldown_policies.lock().unwrap().push(policy.clone());
+    }
+
+    async fn remove_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        bucket: CooldownBucket,
+    ) {
+        self.cooldown_policies
+            .lock()
+            .unwrap()
+            .retain(|p| !(p.guild_id == guild_id && p.command == command && p.bucket == bucket));
+    }
+
+    async fn get_cooldown(&self, key: &CooldownKey) -> Option<OffsetDateTime> {
+        self.cooldowns.lock().unwrap().get(key).copied()
+    }
+
+    async fn set_cooldown(&self, key: &CooldownKey, expires_at: OffsetDateTime) {
+        self.cooldowns
+            .lock()
+            .unwrap()
+            .insert(key.clone(), expires_at);
+    }
+
+    async fn delete_expired_cooldowns(&self) {
+        let now = OffsetDateTime::now_utc();
+        self.cooldowns
+            .lock()
+            .unwrap()
+            .retain(|_, expires_at| *expires_at > now);
+    }
+}
+
+#[async_trait]
+impl UserSettingsRepository for MemoryDatabase {
+    async fn get_user_locale(&self, user_id: UserId) -> Result<Option<String>, sqlx::Error> {
+        if self.fail_reads.load(Ordering::Relaxed) {
+            return Err(sqlx::Error::PoolTimedOut);
+        }
+
+        Ok(self.user_locales.lock().unwrap().get(&user_id).cloned())
+    }
+
+    async fn set_user_locale(&self, user_id: UserId, locale: Option<&str>) {
+        let mut user_locales = self.user_locales.lock().unwrap();
+
+        match locale {
+            Some(locale) => user_locales.insert(user_id, locale.to_string()),
+            None => user_locales.remove(&user_id),
+        };
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for MemoryDatabase {
+    async fn get_permission_overrides(&self, guild_id: GuildId) -> Vec<PermissionOverride> {
+        self.permission_overrides
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.guild_id == guild_id)
+            .cloned()
+            .collect()
+    }
+
+    async fn set_permission_override(&self, rule: &PermissionOverride) {
+        self.remove_permission_override(rule.guild_id, &rule.command, rule.target, rule.target_id)
+            .await;
+        self.permission_overrides.lock().unwrap().push(rule.clone());
+    }
+
+    async fn remove_permission_override(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        target: PermissionTarget,
+        target_id: u64,
+    ) {
+        self.permission_overrides.lock().unwrap().retain(|r| {
+            !(r.guild_id == guild_id
+                && r.command == command
+                && r.target == target
+                && r.target_id == target_id)
+        });
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for MemoryDatabase {
+    async fn add_reminder(&self, reminder: &Reminder) -> Option<i64> {
+        let mut reminders = self.reminders.lock().unwrap();
+        let id = reminders.iter().map(|r| r.id).max().unwrap_or_default() + 1;
+        reminders.push(Reminder {
+            id,
+            ..reminder.clone()
+        });
+        Some(id)
+    }
+
+    async fn get_reminders(&self, user_id: UserId) -> Vec<Reminder> {
+        let mut reminders: Vec<_> = self
+            .reminders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.user_id == user_id)
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|r| r.remind_at);
+        reminders
+    }
+
+    async fn cancel_reminder(&self, user_id: UserId, id: i64) -> bool {
+        let mut reminders = self.reminders.lock().unwrap();
+        let before = reminders.len();
+        reminders.retain(|r| !(r.id == id && r.user_id == user_id));
+        reminders.len() < before
+    }
+
+    async fn get_reminders_before(&self, until: OffsetDateTime) -> Vec<Reminder> {
+        let mut reminders: Vec<_> = self
+            .reminders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.remind_at < until)
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|r| r.remind_at);
+        reminders
+    }
+
+    async fn delete_reminder(&self, id: i64) {
+        self.reminders.lock().unwrap().retain(|r| r.id != id);
+    }
+}
+
+#[async_trait]
+impl TagRepository for MemoryDatabase {
+    async fn get_tags(&self, guild_id: GuildId) -> Vec<Tag> {
+        let mut tags: Vec<_> = self
+            .tags
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|t| t.guild_id == guild_id)
+            .cloned()
+            .collect();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        tags
+    }
+
+    async fn get_tag(&self, guild_id: GuildId, name: &str) -> Option<Tag> {
+        self.tags
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.guild_id == guild_id && t.name == name)
+            .cloned()
+    }
+
+    async fn create_tag(&self, tag: &Tag) -> bool {
+        let mut tags = self.tags.lock().unwrap();
+        if tags
+            .iter()
+            .any(|t| t.guild_id == tag.guild_id && t.name == tag.name)
+        {
+            return false;
+        }
+
+        tags.push(tag.clone());
+        true
+    }
+
+    async fn update_tag(&self, tag: &Tag) {
+        for existing in self.tags.lock().unwrap().iter_mut() {
+            if existing.guild_id == tag.guild_id && existing.name == tag.name {
+                existing.content.clone_from(&tag.content);
+                existing.embed = tag.embed;
+            }
+        }
+    }
+
+    async fn delete_tag(&self, guild_id: GuildId, name: &str) -> bool {
+        let mut tags = self.tags.lock().unwrap();
+        let before = tags.len();
+        tags.retain(|t| !(t.guild_id == guild_id && t.name == name));
+        tags.len() < before
+    }
+
+    async fn use_tag(&self, guild_id: GuildId, name: &str) {
+        for tag in self.tags.lock().unwrap().iter_mut() {
+            if tag.guild_id == guild_id && tag.name == name {
+                tag.uses += 1;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl RolePanelRepository for MemoryDatabase {
+    async fn create_role_panel(&self, panel: &RolePanel) -> Option<i64> {
+        let mut panels = self.role_panels.lock().unwrap();
+        let id = panels.iter().map(|p| p.id).max().unwrap_or_default() + 1;
+        panels.push(RolePanel {
+            id,
+            ..panel.clone()
+        });
+        Some(id)
+    }
+
+    async fn get_role_panels(&self, guild_id: GuildId) -> Vec<RolePanel> {
+        self.role_panels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.guild_id == guild_id)
+            .cloned()
+            .collect()
+    }
+
+    async fn get_role_panel(&self, guild_id: GuildId, id: i64) -> Option<RolePanel> {
+        self.role_panels
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.guild_id == guild_id && p.id == id)
+            .cloned()
+    }
+
+    async fn add_panel_role(&self, panel_id: i64, role: &PanelRole) {
+        for panel in self.role_panels.lock().unwrap().iter_mut() {
+            if panel.id != panel_id {
+                continue;
+            }
+
+            match panel.roles.iter_mut().find(|r| r.role_id == role.role_id) {
+                Some(existing) => *existing = role.clone(),
+                None => panel.roles.push(role.clone()),
+            }
+        }
+    }
+
+    async fn remove_panel_role(&self, panel_id: i64, role_id: RoleId) -> bool {
+        let mut panels = self.role_panels.lock().unwrap();
+        let Some(panel) = panels.iter_mut().find(|p| p.id == panel_id) else {
+            return false;
+        };
+
+        let before = panel.roles.len();
+        panel.roles.retain(|r| r.role_id != role_id);
+        panel.roles.len() < before
+    }
+
+    async fn delete_role_panel(&self, guild_id: GuildId, id: i64) -> bool {
+        let mut panels = self.role_panels.lock().unwrap();
+        let before = panels.len();
+        panels.retain(|p| !(p.guild_id == guild_id && p.id == id));
+        panels.len() < before
+    }
+}
+
+#[async_trait]
+impl LevelRepository for MemoryDatabase {
+    async fn add_xp(&self, gains: &[MemberXp]) -> Option<Vec<MemberXp>> {
+        let mut levels = self.levels.lock().unwrap();
+
+        Some(
+            gains
+                .iter()
+                .map(|gain| {
+                    let xp = levels.entry((gain.guild_id, gain.user_id)).or_default();
+                    *xp += gain.xp;
+
+                    MemberXp {
+                        xp: *xp,
+                        ..gain.clone()
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    async fn get_xp(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+        self.levels
+            .lock()
+            .unwrap()
+            .get(&(guild_id, user_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    async fn count_above(&self, guild_id: GuildId, xp: i64) -> i64 {
+        let count = self
+            .levels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((guild, _), total)| *guild == guild_id && **total > xp)
+            .count();
+
+        i64::try_from(count).unwrap_or_default()
+    }
+
+    async fn count_ranked(&self, guild_id: GuildId) -> i64 {
+        self.count_above(guild_id, 0).await
+    }
+
+    async fn get_leaderboard(&self, guild_id: GuildId, offset: i64, limit: i64) -> Vec<MemberXp> {
+        let mut members: Vec<_> = self
+            .levels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((guild, _), xp)| *guild == guild_id && **xp > 0)
+            .map(|((guild_id, user_id), xp)| MemberXp {
+                guild_id: *guild_id,
+                user_id: *user_id,
+                xp: *xp,
+            })
+            .collect();
+        members.sort_by(|a, b| b.xp.cmp(&a.xp).then(a.user_id.cmp(&b.user_id)));
+
+        members
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or_default())
+            .take(usize::try_from(limit).unwrap_or_default())
+            .collect()
+    }
+
+    async fn get_level_rewards(&self, guild_id: GuildId) -> Vec<LevelReward> {
+        let mut rewards: Vec<_> = self
+            .level_rewards
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.guild_id == guild_id)
+            .cloned()
+            .collect();
+        rewards.sort_by_key(|r| r.level);
+        rewards
+    }
+
+    async fn set_level_reward(&self, reward: &LevelReward) {
+        let mut rewards = self.level_rewards.lock().unwrap();
+        rewards.retain(|r| !(r.guild_id == reward.guild_id && r.role_id == reward.role_id));
+        rewards.push(reward.clone());
+    }
+
+    async fn remove_level_reward(&self, guild_id: GuildId, role_id: RoleId) -> bool {
+        let mut rewards = self.level_rewards.lock().unwrap();
+        let before = rewards.len();
+        rewards.retain(|r| !(r.guild_id == guild_id && r.role_id == role_id));
+        rewards.len() < before
+    }
+}
+
+#[async_trait]
+impl StarboardRepository for MemoryDatabase {
+    async fn get_starboard_post(&self, message_id: MessageId) -> Option<StarboardPost> {
+        self.starboard_posts
+            .lock()
+            .unwrap()
+            .get(&message_id)
+            .cloned()
+    }
+
+    async fn set_starboard_post(&self, post: &StarboardPost) {
+        self.starboard_posts
+            .lock()
+            .unwrap()
+            .insert(post.message_id, post.clone());
+    }
+
+    async fn delete_starboard_post(&self, message_id: MessageId) {
+        self.starboard_posts.lock().unwrap().remove(&message_id);
+    }
+}
+
+#[async_trait]
+impl PollRepository for MemoryDatabase {
+    async fn create_poll(&self, poll: &Poll) -> bool {
+        self.polls.lock().unwrap().push(poll.clone());
+        true
+    }
+
+    async fn get_poll(&self, message_id: MessageId) -> Option<Poll> {
+        self.polls
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.message_id == message_id)
+            .cloned()
+    }
+
+    async fn get_polls_closing_before(&self, until: OffsetDateTime) -> Vec<Poll> {
+        let mut polls: Vec<_> = self
+            .polls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.closes_at < until)
+            .cloned()
+            .collect();
+        polls.sort_by_key(|p| p.closes_at);
+        polls
+    }
+
+    async fn delete_poll(&self, message_id: MessageId) {
+        self.polls
+            .lock()
+            .unwrap()
+            .retain(|p| p.message_id != message_id);
+        self.poll_votes.lock().unwrap().remove(&message_id);
+    }
+
+    async fn get_poll_votes(&self, message_id: MessageId) -> Vec<PollVote> {
+        self.poll_votes
+            .lock()
+            .unwrap()
+            .get(&message_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn toggle_poll_vote(
+        &self,
+        message_id: MessageId,
+        user_id: UserId,
+        option: i16,
+    ) -> Option<Vec<i16>> {
+        let polls = self.polls.lock().unwrap();
+        let poll = polls.iter().find(|p| p.message_id == message_id)?;
+        let mut votes = self.poll_votes.lock().unwrap();
+        let votes = votes.entry(message_id).or_default();
+
+        let current: Vec<_> = votes
+            .iter()
+            .filter(|v| v.user_id == user_id)
+            .map(|v| v.option)
+            .collect();
+        let chosen = toggle(&current, option, poll.multi_choice);
+
+        votes.retain(|v| v.user_id != user_id);
+        votes.extend(chosen.iter().map(|option| PollVote {
+            user_id,
+            option: *option,
+        }));
+
+        Some(chosen)
+    }
+}
+
+impl MemoryDatabase {
+    fn update_giveaway(&self, id: i64, update: impl FnOnce(&mut Giveaway) -> bool) -> bool {
+        self.giveaways
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|g| g.id == id)
+            .is_some_and(update)
+    }
+
+    fn giveaway_ended(&self, id: i64) -> bool {
+        self.giveaways
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|g| g.id == id && g.ended)
+    }
+}
+
+#[async_trait]
+impl GiveawayRepository for MemoryDatabase {
+    async fn create_giveaway(&self, giveaway: &Giveaway) -> Option<i64> {
+        let mut giveaways = self.giveaways.lock().unwrap();
+        let id = giveaways.iter().map(|g| g.id).max().unwrap_or_default() + 1;
+
+        giveaways.push(Giveaway {
+            id,
+            ..giveaway.clone()
+        });
+        Some(id)
+    }
+
+    async fn get_giveaway(&self, guild_id: GuildId, id: i64) -> Option<Giveaway> {
+        self.giveaways
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|g| g.guild_id == guild_id && g.id == id)
+            .cloned()
+    }
+
+    async fn get_giveaway_by_message(&self, message_id: MessageId) -> Option<Giveaway> {
+        self.giveaways
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|g| g.message_id == message_id)
+            .cloned()
+    }
+
+    async fn get_giveaways(&self, guild_id: GuildId) -> Vec<Giveaway> {
+        self.giveaways
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|g| g.guild_id == guild_id)
+            .cloned()
+            .collect()
+    }
+
+    async fn get_giveaways_ending_before(&self, until: OffsetDateTime) -> Vec<Giveaway> {
+        let mut giveaways: Vec<_> = self
+            .giveaways
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|g| !g.ended && g.ends_at < until)
+            .cloned()
+            .collect();
+        giveaways.sort_by_key(|g| g.ends_at);
+        giveaways
+    }
+
+    async fn add_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        if self.giveaway_ended(id) {
+            return false;
+        }
+
+        let mut entries = self.giveaway_entries.lock().unwrap();
+        let entries = entries.entry(id).or_default();
+
+        if entries.contains(&user_id) {
+            return false;
+        }
+
+        entries.push(user_id);
+        true
+    }
+
+    async fn remove_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        if self.giveaway_ended(id) {
+            return false;
+        }
+
+        let mut entries = self.giveaway_entries.lock().unwrap();
+        let Some(entries) = entries.get_mut(&id) else {
+            return false;
+        };
+
+        let before = entries.len();
+        entries.retain(|entry| *entry != user_id);
+        entries.len() < before
+    }
+
+    async fn get_giveaway_entries(&self, id: i64) -> Vec<UserId> {
+        self.giveaway_entries
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn count_giveaway_entries(&self, id: i64) -> i64 {
+        i64::try_from(self.get_giveaway_entries(id).await.len()).unwrap_or(i64::MAX)
+    }
+
+    async fn end_giveaway(&self, id: i64, seed: u64) -> Option<(Vec<UserId>, Vec<UserId>)> {
+        let mut winner_count = 0;
+        if !self.update_giveaway(id, |giveaway| {
+            winner_count = giveaway.winner_count;
+            !std::mem::replace(&mut giveaway.ended, true)
+        }) {
+            return None;
+        }
+
+        let entries = self.get_giveaway_entries(id).await;
+        let count = usize::try_from(winner_count).unwrap_or_default();
+        let winners = draw(&entries, &[], count, seed);
+        self.add_giveaway_draw(id, seed, &[], &winners).await;
+
+        Some((entries, winners))
+    }
+
+    async fn add_giveaway_draw(
+        &self,
+        id: i64,
+        seed: u64,
+        excluded_ids: &[UserId],
+        winner_ids: &[UserId],
+    ) {
+        self.giveaway_draws.lock().unwrap().push(GiveawayDraw {
+            giveaway_id: id,
+            seed,
+            excluded_ids: excluded_ids.to_vec(),
+            winner_ids: winner_ids.to_vec(),
+        });
+        self.update_giveaway(id, |giveaway| {
+            giveaway.seed = Some(seed);
+            giveaway.winner_ids.extend(winner_ids);
+            true
+        });
+    }
+}
@@ -0,0 +1,255 @@
+pub mod cache;
+#[cfg(test)]
+pub mod memory;
+pub mod postgres;
+
+pub use cache::SettingsCache;
+pub use postgres::{connect, PostgresDatabase};
+
+use crate::structs::{
+    CooldownBucket, CooldownKey, CooldownPolicy, DatabaseContainer, Giveaway, Guild, Infraction,
+    LevelReward, MemberXp, PanelRole, PermissionOverride, PermissionTarget, Poll, PollVote,
+    Reminder, RolePanel, SettingsCacheContainer, StarboardPost, Tag,
+};
+use poise::serenity_prelude::{async_trait, Context, GuildId, MessageId, RoleId, UserId};
+use sqlx::types::time::OffsetDateTime;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait GuildRepository: Send + Sync {
+    /// Inserts the guild unless it already exists, returning whether it's stored.
+    async fn insert_guild(&self, guild: &Guild) -> bool;
+    /// The stored guild, `None` if it has no row, or an error if it couldn't be read.
+    async fn get_guild(&self, guild_id: GuildId) -> Result<Option<Guild>, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait InfractionRepository: Send + Sync {
+    async fn insert_infraction(&self, infraction: &Infraction);
+    async fn get_infractions(&self, guild_id: GuildId, member_id: UserId) -> Vec<Infraction>;
+}
+
+#[async_trait]
+pub trait SettingsRepository: Send + Sync {
+    /// The guild's settings, or the defaults if it has none stored.
+    async fn get_settings(&self, guild_id: GuildId) -> Guild;
+    /// Stores the guild's settings, creating the guild if needed, and returns
+    /// whether they were saved.
+    async fn update_settings(&self, guild: &Guild) -> bool;
+}
+
+#[async_trait]
+pub trait CooldownRepository: Send + Sync {
+    async fn get_cooldown_policies(&self, guild_id: GuildId) -> Vec<CooldownPolicy>;
+    /// Stores the policy, replacing the guild's existing one for that command and bucket.
+    async fn set_cooldown_policy(&self, policy: &CooldownPolicy);
+    async fn remove_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        bucket: CooldownBucket,
+    );
+    async fn get_cooldown(&self, key: &CooldownKey) -> Option<OffsetDateTime>;
+    async fn set_cooldown(&self, key: &CooldownKey, expires_at: OffsetDateTime);
+    /// Deletes cooldowns that ran out, which are never read again.
+    async fn delete_expired_cooldowns(&self);
+}
+
+#[async_trait]
+pub trait PermissionRepository: Send + Sync {
+    async fn get_permission_overrides(&self, guild_id: GuildId) -> Vec<PermissionOverride>;
+    /// Stores the override, replacing the existing one for that command and target.
+    async fn set_permission_override(&self, rule: &PermissionOverride);
+    async fn remove_permission_override(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        target: PermissionTarget,
+        target_id: u64,
+    );
+}
+
+#[async_trait]
+pub trait ReminderRepository: Send + Sync {
+    /// Stores the reminder and returns its id.
+    async fn add_reminder(&self, reminder: &Reminder) -> Option<i64>;
+    /// The user's pending reminders, soonest first.
+    async fn get_reminders(&self, user_id: UserId) -> Vec<Reminder>;
+    /// Deletes one of the user's reminders, returning whether it existed.
+    async fn cancel_reminder(&self, user_id: UserId, id: i64) -> bool;
+    /// Reminders due before `until`, soonest first.
+    async fn get_reminders_before(&self, until: OffsetDateTime) -> Vec<Reminder>;
+    async fn delete_reminder(&self, id: i64);
+}
+
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    /// The guild's tags, sorted by name.
+    async fn get_tags(&self, guild_id: GuildId) -> Vec<Tag>;
+    async fn get_tag(&self, guild_id: GuildId, name: &str) -> Option<Tag>;
+    /// Stores a new tag, returning `false` if the guild already has one by that name.
+    async fn create_tag(&self, tag: &Tag) -> bool;
+    /// Replaces an existing tag's content.
+    async fn update_tag(&self, tag: &Tag);
+    /// Deletes the tag, returning whether it existed.
+    async fn delete_tag(&self, guild_id: GuildId, name: &str) -> bool;
+    /// Counts one use of the tag.
+    async fn use_tag(&self, guild_id: GuildId, name: &str);
+}
+
+#[async_trait]
+pub trait RolePanelRepository: Send + Sync {
+    /// Stores the panel along with its roles and returns its id.
+    async fn create_role_panel(&self, panel: &RolePanel) -> Option<i64>;
+    async fn get_role_panels(&self, guild_id: GuildId) -> Vec<RolePanel>;
+    async fn get_role_panel(&self, guild_id: GuildId, id: i64) -> Option<RolePanel>;
+    /// Adds the role to the end of the panel, or updates it if it's already there.
+    async fn add_panel_role(&self, panel_id: i64, role: &PanelRole);
+    /// Removes the role from the panel, returning whether it was there.
+    async fn remove_panel_role(&self, panel_id: i64, role_id: RoleId) -> bool;
+    /// Deletes the panel, returning whether it existed.
+    async fn delete_role_panel(&self, guild_id: GuildId, id: i64) -> bool;
+}
+
+#[async_trait]
+pub trait LevelRepository: Send + Sync {
+    /// Adds every gain in one write and returns the members' new totals, or
+    /// `None` if nothing was written.
+    async fn add_xp(&self, gains: &[MemberXp]) -> Option<Vec<MemberXp>>;
+    async fn get_xp(&self, guild_id: GuildId, user_id: UserId) -> i64;
+    /// How many of the guild's members have more than `xp`.
+    async fn count_above(&self, guild_id: GuildId, xp: i64) -> i64;
+    /// How many of the guild's members have any XP.
+    async fn count_ranked(&self, guild_id: GuildId) -> i64;
+    /// The guild's members with the most XP, skipping the first `offset`.
+    async fn get_leaderboard(&self, guild_id: GuildId, offset: i64, limit: i64) -> Vec<MemberXp>;
+    /// The guild's rewards, lowest level first.
+    async fn get_level_rewards(&self, guild_id: GuildId) -> Vec<LevelReward>;
+    /// Stores the reward, replacing the level of an existing one for the role.
+    async fn set_level_reward(&self, reward: &LevelReward);
+    /// Removes the role's reward, returning whether it had one.
+    async fn remove_level_reward(&self, guild_id: GuildId, role_id: RoleId) -> bool;
+}
+
+#[async_trait]
+pub trait StarboardRepository: Send + Sync {
+    async fn get_starboard_post(&self, message_id: MessageId) -> Option<StarboardPost>;
+    /// Stores the post, replacing the existing one for the same message.
+    async fn set_starboard_post(&self, post: &StarboardPost);
+    async fn delete_starboard_post(&self, message_id: MessageId);
+}
+
+#[async_trait]
+pub trait PollRepository: Send + Sync {
+    /// Stores the poll, returning whether it was saved.
+    async fn create_poll(&self, poll: &Poll) -> bool;
+    async fn get_poll(&self, message_id: MessageId) -> Option<Poll>;
+    /// Polls closing before `until`, soonest first.
+    async fn get_polls_closing_before(&self, until: OffsetDateTime) -> Vec<Poll>;
+    /// Deletes the poll along with its votes.
+    async fn delete_poll(&self, message_id: MessageId);
+    async fn get_poll_votes(&self, message_id: MessageId) -> Vec<PollVote>;
+    /// Applies the user's click on `option` as [`toggle`](crate::polls::toggle)
+    /// does, reading and replacing their votes in one go so quick clicks can't
+    /// interleave. Returns their votes afterwards, `None` if the poll is gone or
+    /// they couldn't be stored.
+    async fn toggle_poll_vote(
+        &self,
+        message_id: MessageId,
+        user_id: UserId,
+        option: i16,
+    ) -> Option<Vec<i16>>;
+}
+
+#[async_trait]
+pub trait GiveawayRepository: Send + Sync {
+    /// Stores the giveaway and returns its id.
+    async fn create_giveaway(&self, giveaway: &Giveaway) -> Option<i64>;
+    async fn get_giveaway(&self, guild_id: GuildId, id: i64) -> Option<Giveaway>;
+    async fn get_giveaway_by_message(&self, message_id: MessageId) -> Option<Giveaway>;
+    /// The guild's giveaways, newest first.
+    async fn get_giveaways(&self, guild_id: GuildId) -> Vec<Giveaway>;
+    /// Giveaways that haven't ended and end before `until`, soonest first.
+    async fn get_giveaways_ending_before(&self, until: OffsetDateTime) -> Vec<Giveaway>;
+    /// Enters the user, returning `false` if they already were or the
+    /// giveaway ended.
+    async fn add_giveaway_entry(&self, id: i64, user_id: UserId) -> bool;
+    /// Withdraws the user's entry, returning whether they had one. Entries
+    /// can't be withdrawn once the giveaway ended.
+    async fn remove_giveaway_entry(&self, id: i64, user_id: UserId) -> bool;
+    async fn get_giveaway_entries(&self, id: i64) -> Vec<UserId>;
+    async fn count_giveaway_entries(&self, id: i64) -> i64;
+    /// Marks the giveaway ended, which closes its entries, and stores the
+    /// winners drawn from them with `seed` along with it. Returns the entries
+    /// and winners, or `None` if it already had ended or couldn't be, so only
+    /// one caller announces them.
+    async fn end_giveaway(&self, id: i64, seed: u64) -> Option<(Vec<UserId>, Vec<UserId>)>;
+    /// Stores a reroll's draw.
+    async fn add_giveaway_draw(
+        &self,
+        id: i64,
+        seed: u64,
+        excluded_ids: &[UserId],
+        winner_ids: &[UserId],
+    );
+}
+
+#[async_trait]
+pub trait UserSettingsRepository: Send + Sync {
+    /// The user's language override, or an error if it couldn't be read.
+    async fn get_user_locale(&self, user_id: UserId) -> Result<Option<String>, sqlx::Error>;
+    /// Sets the user's language override, `None` clears it.
+    async fn set_user_locale(&self, user_id: UserId, locale: Option<&str>);
+}
+
+pub trait Database:
+    GuildRepository
+    + InfractionRepository
+    + SettingsRepository
+    + CooldownRepository
+    + UserSettingsRepository
+    + PermissionRepository
+    + ReminderRepository
+    + TagRepository
+    + RolePanelRepository
+    + LevelRepository
+    + StarboardRepository
+    + PollRepository
+    + GiveawayRepository
+{
+}
+
+impl<T> Database for T where
+    T: GuildRepository
+        + InfractionRepository
+        + SettingsRepository
+        + CooldownRepository
+        + UserSettingsRepository
+        + PermissionRepository
+        + ReminderRepository
+        + TagRepository
+        + RolePanelRepository
+        + LevelRepository
+        + StarboardRepository
+        + PollRepository
+        + GiveawayRepository
+{
+}
+
+pub async fn get_database(ctx: &Context) -> Arc<dyn Database> {
+    ctx.data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_settings_cache(ctx: &Context) -> Arc<SettingsCache> {
+    ctx.data
+        .read()
+        .await
+        .get::<SettingsCacheContainer>()
+        .unwrap()
+        .clone()
+}
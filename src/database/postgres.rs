@@ -0,0 +1,1747 @@
+use super::{
+    CooldownRepository, GiveawayRepository, GuildRepository, InfractionRepository, LevelRepository,
+    PermissionRepository, PollRepository, ReminderRepository, RolePanelRepository,
+    SettingsRepository, StarboardRepository, TagRepository, UserSettingsRepository,
+};
+use crate::{
+    giveaways::draw,
+    polls::toggle,
+    structs::{
+        CooldownBucket, CooldownKey, CooldownPolicy, DbConfig, Giveaway, Guild, Infraction,
+        InfractionType, LevelReward, MemberXp, PanelRole, PermissionOverride, PermissionTarget,
+        Poll, PollVote, Reminder, RolePanel, RolePanelKind, StarboardPost, Tag, Theme,
+    },
+};
+use poise::serenity_prelude::{async_trait, ChannelId, Color, GuildId, MessageId, RoleId, UserId};
+use sqlx::{
+    migrate,
+    types::time::{OffsetDateTime, PrimitiveDateTime},
+    PgPool, Pool, Postgres,
+};
+use std::time::Duration;
+use tracing::{error, info};
+
+pub async fn connect(db_config: &DbConfig) -> Pool<Postgres> {
+    let db = sqlx::postgres::PgPoolOptions::new()
+        .min_connections(5)
+        .max_connections(100)
+        .connect_with(
+            sqlx::postgres::PgConnectOptions::new()
+                .host(db_config.host.as_str())
+                .port(db_config.port)
+                .username(db_config.username.as_str())
+                .password(db_config.password.as_str())
+                .database("rusty"),
+        )
+        .await
+        .expect("Failed to connect to database");
+
+    migrate!().run(&db).await.expect("Failed to run migrations");
+
+    db
+}
+
+struct GuildRow {
+    id: i64,
+    mod_id: Option<i64>,
+    audit_id: Option<i64>,
+    welcome_id: Option<i64>,
+    locale: Option<String>,
+    prefix: Option<String>,
+    mention_prefix: bool,
+    primary_color: Option<i32>,
+    success_color: Option<i32>,
+    error_color: Option<i32>,
+    footer_text: Option<String>,
+    footer_icon: Option<String>,
+    timestamps: bool,
+    no_xp_channels: Vec<i64>,
+    starboard_id: Option<i64>,
+    starboard_threshold: i32,
+}
+
+impl From<GuildRow> for Guild {
+    fn from(row: GuildRow) -> Self {
+        let color = |value: Option<i32>| value.map(|value| Color::new(value.cast_unsigned()));
+
+        Self {
+            id: GuildId::new(row.id.cast_unsigned()),
+            mod_id: row.mod_id.map(|id| ChannelId::new(id.cast_unsigned())),
+            audit_id: row.audit_id.map(|id| ChannelId::new(id.cast_unsigned())),
+            welcome_id: row.welcome_id.map(|id| ChannelId::new(id.cast_unsigned())),
+            locale: row.locale,
+            prefix: row.prefix,
+            mention_prefix: row.mention_prefix,
+            theme: Theme {
+                primary: color(row.primary_color),
+                success: color(row.success_color),
+                error: color(row.error_color),
+                footer_text: row.footer_text,
+                footer_icon: row.footer_icon,
+                timestamps: row.timestamps,
+            },
+            no_xp_channels: row
+                .no_xp_channels
+                .into_iter()
+                .map(|id| ChannelId::new(id.cast_unsigned()))
+                .collect(),
+            starboard_id: row
+                .starboard_id
+                .map(|id| ChannelId::new(id.cast_unsigned())),
+            starboard_threshold: row.starboard_threshold,
+        }
+    }
+}
+
+#[allow(clippy::struct_field_names)]
+struct InfractionRow {
+    guild_id: i64,
+    member_id: i64,
+    moderator_id: i64,
+    reason: String,
+    infraction_type: InfractionType,
+    created_at: PrimitiveDateTime,
+}
+
+impl From<InfractionRow> for Infraction {
+    fn from(row: InfractionRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            member_id: UserId::new(row.member_id.cast_unsigned()),
+            moderator_id: UserId::new(row.moderator_id.cast_unsigned()),
+            reason: row.reason,
+            infraction_type: row.infraction_type,
+            created_at: Some(row.created_at),
+        }
+    }
+}
+
+struct CooldownPolicyRow {
+    guild_id: i64,
+    command: String,
+    bucket: CooldownBucket,
+    seconds: i32,
+}
+
+impl From<CooldownPolicyRow> for CooldownPolicy {
+    fn from(row: CooldownPolicyRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            command: row.command,
+            bucket: row.bucket,
+            duration: Duration::from_secs(u64::try_from(row.seconds).unwrap_or_default()),
+        }
+    }
+}
+
+struct PermissionOverrideRow {
+    guild_id: i64,
+    command: String,
+    target: PermissionTarget,
+    target_id: i64,
+    allow: bool,
+}
+
+impl From<PermissionOverrideRow> for PermissionOverride {
+    fn from(row: PermissionOverrideRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            command: row.command,
+            target: row.target,
+            target_id: row.target_id.cast_unsigned(),
+            allow: row.allow,
+        }
+    }
+}
+
+struct ReminderRow {
+    id: i64,
+    user_id: i64,
+    guild_id: Option<i64>,
+    channel_id: Option<i64>,
+    text: String,
+    created_at: OffsetDateTime,
+    remind_at: OffsetDateTime,
+}
+
+impl From<ReminderRow> for Reminder {
+    fn from(row: ReminderRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: UserId::new(row.user_id.cast_unsigned()),
+            guild_id: row.guild_id.map(|id| GuildId::new(id.cast_unsigned())),
+            channel_id: row.channel_id.map(|id| ChannelId::new(id.cast_unsigned())),
+            text: row.text,
+            created_at: row.created_at,
+            remind_at: row.remind_at,
+        }
+    }
+}
+
+struct TagRow {
+    guild_id: i64,
+    name: String,
+    content: String,
+    embed: bool,
+    owner_id: i64,
+    uses: i64,
+    created_at: OffsetDateTime,
+}
+
+impl From<TagRow> for Tag {
+    fn from(row: TagRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            name: row.name,
+            content: row.content,
+            embed: row.embed,
+            owner_id: UserId::new(row.owner_id.cast_unsigned()),
+            uses: row.uses,
+            created_at: row.created_at,
+        }
+    }
+}
+
+struct RolePanelRow {
+    id: i64,
+    guild_id: i64,
+    channel_id: i64,
+    message_id: i64,
+    kind: RolePanelKind,
+    title: String,
+    description: Option<String>,
+}
+
+struct PanelRoleRow {
+    panel_id: i64,
+    role_id: i64,
+    label: String,
+    emoji: Option<String>,
+}
+
+impl RolePanelRow {
+    fn into_panel(self, roles: &[PanelRoleRow]) -> RolePanel {
+        RolePanel {
+            id: self.id,
+            guild_id: GuildId::new(self.guild_id.cast_unsigned()),
+            channel_id: ChannelId::new(self.channel_id.cast_unsigned()),
+            message_id: MessageId::new(self.message_id.cast_unsigned()),
+            kind: self.kind,
+            title: self.title,
+            description: self.description,
+            roles: roles
+                .iter()
+                .filter(|role| role.panel_id == self.id)
+                .map(|role| PanelRole {
+                    role_id: RoleId::new(role.role_id.cast_unsigned()),
+                    label: role.label.clone(),
+                    emoji: role.emoji.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+struct MemberXpRow {
+    guild_id: i64,
+    user_id: i64,
+    xp: i64,
+}
+
+impl From<MemberXpRow> for MemberXp {
+    fn from(row: MemberXpRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            user_id: UserId::new(row.user_id.cast_unsigned()),
+            xp: row.xp,
+        }
+    }
+}
+
+struct LevelRewardRow {
+    guild_id: i64,
+    role_id: i64,
+    level: i32,
+}
+
+impl From<LevelRewardRow> for LevelReward {
+    fn from(row: LevelRewardRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            role_id: RoleId::new(row.role_id.cast_unsigned()),
+            level: row.level,
+        }
+    }
+}
+
+struct StarboardPostRow {
+    guild_id: i64,
+    channel_id: i64,
+    message_id: i64,
+    starboard_channel_id: i64,
+    starboard_message_id: i64,
+    stars: i32,
+}
+
+impl From<StarboardPostRow> for StarboardPost {
+    fn from(row: StarboardPostRow) -> Self {
+        Self {
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            channel_id: ChannelId::new(row.channel_id.cast_unsigned()),
+            message_id: MessageId::new(row.message_id.cast_unsigned()),
+            starboard_channel_id: ChannelId::new(row.starboard_channel_id.cast_unsigned()),
+            starboard_message_id: MessageId::new(row.starboard_message_id.cast_unsigned()),
+            stars: row.stars,
+        }
+    }
+}
+
+struct PollRow {
+    message_id: i64,
+    guild_id: i64,
+    channel_id: i64,
+    user_id: i64,
+    question: String,
+    options: Vec<String>,
+    multi_choice: bool,
+    anonymous: bool,
+    closes_at: OffsetDateTime,
+}
+
+impl From<PollRow> for Poll {
+    fn from(row: PollRow) -> Self {
+        Self {
+            message_id: MessageId::new(row.message_id.cast_unsigned()),
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            channel_id: ChannelId::new(row.channel_id.cast_unsigned()),
+            user_id: UserId::new(row.user_id.cast_unsigned()),
+            question: row.question,
+            options: row.options,
+            multi_choice: row.multi_choice,
+            anonymous: row.anonymous,
+            closes_at: row.closes_at,
+        }
+    }
+}
+
+struct PollVoteRow {
+    user_id: i64,
+    option: i16,
+}
+
+impl From<PollVoteRow> for PollVote {
+    fn from(row: PollVoteRow) -> Self {
+        Self {
+            user_id: UserId::new(row.user_id.cast_unsigned()),
+            option: row.option,
+        }
+    }
+}
+
+struct GiveawayRow {
+    id: i64,
+    guild_id: i64,
+    channel_id: i64,
+    message_id: i64,
+    host_id: i64,
+    prize: String,
+    winner_count: i32,
+    required_role_id: Option<i64>,
+    min_account_age_secs: Option<i64>,
+    ends_at: OffsetDateTime,
+    ended: bool,
+    seed: Option<i64>,
+    winner_ids: Vec<i64>,
+}
+
+impl From<GiveawayRow> for Giveaway {
+    fn from(row: GiveawayRow) -> Self {
+        Self {
+            id: row.id,
+            guild_id: GuildId::new(row.guild_id.cast_unsigned()),
+            channel_id: ChannelId::new(row.channel_id.cast_unsigned()),
+            message_id: MessageId::new(row.message_id.cast_unsigned()),
+            host_id: UserId::new(row.host_id.cast_unsigned()),
+            prize: row.prize,
+            winner_count: row.winner_count,
+            required_role_id: row
+                .required_role_id
+                .map(|id| RoleId::new(id.cast_unsigned())),
+            min_account_age: row
+                .min_account_age_secs
+                .map(|secs| Duration::from_secs(secs.cast_unsigned())),
+            ends_at: row.ends_at,
+            ended: row.ended,
+            seed: row.seed.map(i64::cast_unsigned),
+            winner_ids: row
+                .winner_ids
+                .into_iter()
+                .map(|id| UserId::new(id.cast_unsigned()))
+                .collect(),
+        }
+    }
+}
+
+/// Cooldowns used outside a guild are stored under scope 0.
+fn scope_id(key: &CooldownKey) -> i64 {
+    key.scope_id.map(i64::from).unwrap_or_default()
+}
+
+pub struct PostgresDatabase {
+    pool: PgPool,
+}
+
+impl PostgresDatabase {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl GuildRepository for PostgresDatabase {
+    async fn insert_guild(&self, guild: &Guild) -> bool {
+        let result = sqlx::query!(
+            "INSERT INTO guilds (id, mod_id, audit_id, welcome_id, locale, primary_color, success_color, error_color, footer_text, footer_icon, timestamps, prefix, mention_prefix, no_xp_channels, starboard_id, starboard_threshold)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (id) DO NOTHING",
+            i64::from(guild.id),
+            guild.mod_id.map(i64::from),
+            guild.audit_id.map(i64::from),
+            guild.welcome_id.map(i64::from),
+            guild.locale,
+            guild.theme.primary.map(|c| c.0.cast_signed()),
+            guild.theme.success.map(|c| c.0.cast_signed()),
+            guild.theme.error.map(|c| c.0.cast_signed()),
+            guild.theme.footer_text,
+            guild.theme.footer_icon,
+            guild.theme.timestamps,
+            guild.prefix,
+            guild.mention_prefix,
+            &guild.no_xp_channels.iter().copied().map(i64::from).collect::<Vec<_>>(),
+            guild.starboard_id.map(i64::from),
+            guild.starboard_threshold
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(done) => {
+                if done.rows_affected() > 0 {
+                    info!("Inserted guild {}", guild.id);
+                }
+                true
+            }
+            Err(e) => {
+                error!("Error inserting guild {}: {}", guild.id, e);
+                false
+            }
+        }
+    }
+
+    async fn get_guild(&self, guild_id: GuildId) -> Result<Option<Guild>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            GuildRow,
+            "SELECT id, mod_id, audit_id, welcome_id, locale, prefix, mention_prefix, primary_color, success_color, error_color, footer_text, footer_icon, timestamps, no_xp_channels, starboard_id, starboard_threshold FROM guilds WHERE id = $1",
+            i64::from(guild_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(row.map(Guild::from)),
+            Err(e) => {
+                error!("Error reading guild {}: {}", guild_id, e);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl InfractionRepository for PostgresDatabase {
+    async fn insert_infraction(&self, infraction: &Infraction) {
+        if let Err(e) = sqlx::query!(
+            r#"INSERT INTO infractions (guild_id, member_id, moderator_id, reason, infraction_type) VALUES ($1, $2, $3, $4, $5)"#,
+            i64::from(infraction.guild_id),
+            i64::from(infraction.member_id),
+            i64::from(infraction.moderator_id),
+            infraction.reason,
+            infraction.infraction_type as InfractionType
+        )
+        .execute(&self.pool)
+        .await {
+            error!("Error inserting infraction: {}", e);
+            return;
+        }
+
+        info!("Inserted infraction for {}", infraction.member_id);
+    }
+
+    async fn get_infractions(&self, guild_id: GuildId, member_id: UserId) -> Vec<Infraction> {
+        let result = sqlx::query_as!(
+            InfractionRow,
+            r#"SELECT guild_id, member_id, moderator_id, reason, infraction_type as "infraction_type: InfractionType", created_at FROM infractions WHERE guild_id = $1 AND member_id = $2"#,
+            i64::from(guild_id),
+            i64::from(member_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(Infraction::from).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for PostgresDatabase {
+    async fn get_settings(&self, guild_id: GuildId) -> Guild {
+        self.get_guild(guild_id)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| Guild::new(guild_id))
+    }
+
+    async fn update_settings(&self, guild: &Guild) -> bool {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO guilds (id, mod_id, audit_id, welcome_id, locale, primary_color, success_color, error_color, footer_text, footer_icon, timestamps, prefix, mention_prefix, no_xp_channels, starboard_id, starboard_threshold)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (id) DO UPDATE SET mod_id = $2, audit_id = $3, welcome_id = $4, locale = $5,
+            primary_color = $6, success_color = $7, error_color = $8, footer_text = $9, footer_icon = $10, timestamps = $11,
+            prefix = $12, mention_prefix = $13, no_xp_channels = $14,
+            starboard_id = $15, starboard_threshold = $16",
+            i64::from(guild.id),
+            guild.mod_id.map(i64::from),
+            guild.audit_id.map(i64::from),
+            guild.welcome_id.map(i64::from),
+            guild.locale,
+            guild.theme.primary.map(|c| c.0.cast_signed()),
+            guild.theme.success.map(|c| c.0.cast_signed()),
+            guild.theme.error.map(|c| c.0.cast_signed()),
+            guild.theme.footer_text,
+            guild.theme.footer_icon,
+            guild.theme.timestamps,
+            guild.prefix,
+            guild.mention_prefix,
+            &guild.no_xp_channels.iter().copied().map(i64::from).collect::<Vec<_>>(),
+            guild.starboard_id.map(i64::from),
+            guild.starboard_threshold
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error updating settings for guild {}: {}", guild.id, e);
+            return false;
+        }
+
+        info!("Updated settings for guild {}", guild.id);
+        true
+    }
+}
+
+#[async_trait]
+impl CooldownRepository for PostgresDatabase {
+    async fn get_cooldown_policies(&self, guild_id: GuildId) -> Vec<CooldownPolicy> {
+        let result = sqlx::query_as!(
+            CooldownPolicyRow,
+            r#"SELECT guild_id, command, bucket as "bucket: CooldownBucket", seconds FROM cooldown_policies WHERE guild_id = $1"#,
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(CooldownPolicy::from).collect())
+            .unwrap_or_default()
+    }
+
+    async fn set_cooldown_policy(&self, policy: &CooldownPolicy) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO cooldown_policies (guild_id, command, bucket, seconds) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (guild_id, command, bucket) DO UPDATE SET seconds = $4",
+            i64::from(policy.guild_id),
+            policy.command,
+            policy.bucket as CooldownBucket,
+            i32::try_from(policy.duration.as_secs()).unwrap_or(i32::MAX)
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error setting cooldown for guild {}: {}", policy.guild_id, e);
+            return;
+        }
+
+        info!(
+            "Set {} cooldown for guild {}",
+            policy.command, policy.guild_id
+        );
+    }
+
+    async fn remove_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        bucket: CooldownBucket,
+    ) {
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM cooldown_policies WHERE guild_id = $1 AND command = $2 AND bucket = $3",
+            i64::from(guild_id),
+            command,
+            bucket as CooldownBucket
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error removing cooldown for guild {}: {}", guild_id, e);
+        }
+    }
+
+    async fn get_cooldown(&self, key: &CooldownKey) -> Option<OffsetDateTime> {
+        let result = sqlx::query!(
+            "SELECT expires_at FROM cooldowns WHERE scope_id = $1 AND command = $2 AND bucket = $3 AND target_id = $4 AND expires_at > NOW()",
+            scope_id(key),
+            key.command,
+            key.bucket as CooldownBucket,
+            key.target_id.cast_signed()
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(|row| row.expires_at),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn set_cooldown(&self, key: &CooldownKey, expires_at: OffsetDateTime) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO cooldowns (scope_id, command, bucket, target_id, expires_at) VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (scope_id, command, bucket, target_id) DO UPDATE SET expires_at = $5",
+            scope_id(key),
+            key.command,
+            key.bucket as CooldownBucket,
+            key.target_id.cast_signed(),
+            expires_at
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error: {}", e);
+        }
+    }
+
+    async fn delete_expired_cooldowns(&self) {
+        match sqlx::query!("DELETE FROM cooldowns WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await
+        {
+            Ok(done) => info!("Deleted {} expired cooldowns", done.rows_affected()),
+            Err(e) => error!("Error deleting expired cooldowns: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl UserSettingsRepository for PostgresDatabase {
+    async fn get_user_locale(&self, user_id: UserId) -> Result<Option<String>, sqlx::Error> {
+        let result = sqlx::query!(
+            "SELECT locale FROM user_settings WHERE id = $1",
+            i64::from(user_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(row.and_then(|row| row.locale)),
+            Err(e) => {
+                error!("Error reading language of user {}: {}", user_id, e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn set_user_locale(&self, user_id: UserId, locale: Option<&str>) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO user_settings (id, locale) VALUES ($1, $2)
+            ON CONFLICT (id) DO UPDATE SET locale = $2",
+            i64::from(user_id),
+            locale
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error updating settings for user {}: {}", user_id, e);
+            return;
+        }
+
+        info!("Updated settings for user {}", user_id);
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for PostgresDatabase {
+    async fn get_permission_overrides(&self, guild_id: GuildId) -> Vec<PermissionOverride> {
+        let result = sqlx::query_as!(
+            PermissionOverrideRow,
+            r#"SELECT guild_id, command, target as "target: PermissionTarget", target_id, allow FROM permission_overrides WHERE guild_id = $1"#,
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(PermissionOverride::from).collect())
+            .unwrap_or_default()
+    }
+
+    async fn set_permission_override(&self, rule: &PermissionOverride) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO permission_overrides (guild_id, command, target, target_id, allow) VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (guild_id, command, target, target_id) DO UPDATE SET allow = $5",
+            i64::from(rule.guild_id),
+            rule.command,
+            rule.target as PermissionTarget,
+            rule.target_id.cast_signed(),
+            rule.allow
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error setting permission override for guild {}: {}", rule.guild_id, e);
+            return;
+        }
+
+        info!(
+            "Set {} permission override for guild {}",
+            rule.command, rule.guild_id
+        );
+    }
+
+    async fn remove_permission_override(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        target: PermissionTarget,
+        target_id: u64,
+    ) {
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM permission_overrides WHERE guild_id = $1 AND command = $2 AND target = $3 AND target_id = $4",
+            i64::from(guild_id),
+            command,
+            target as PermissionTarget,
+            target_id.cast_signed()
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error removing permission override for guild {}: {}", guild_id, e);
+        }
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for PostgresDatabase {
+    async fn add_reminder(&self, reminder: &Reminder) -> Option<i64> {
+        let result = sqlx::query_scalar!(
+            "INSERT INTO reminders (user_id, guild_id, channel_id, text, created_at, remind_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+            i64::from(reminder.user_id),
+            reminder.guild_id.map(i64::from),
+            reminder.channel_id.map(i64::from),
+            reminder.text,
+            reminder.created_at,
+            reminder.remind_at
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(id) => {
+                info!("Added reminder {} for user {}", id, reminder.user_id);
+                Some(id)
+            }
+            Err(e) => {
+                error!("Error adding reminder for user {}: {}", reminder.user_id, e);
+                None
+            }
+        }
+    }
+
+    async fn get_reminders(&self, user_id: UserId) -> Vec<Reminder> {
+        let result = sqlx::query_as!(
+            ReminderRow,
+            "SELECT id, user_id, guild_id, channel_id, text, created_at, remind_at FROM reminders WHERE user_id = $1 ORDER BY remind_at",
+            i64::from(user_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(Reminder::from).collect())
+            .unwrap_or_default()
+    }
+
+    async fn cancel_reminder(&self, user_id: UserId, id: i64) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM reminders WHERE id = $1 AND user_id = $2",
+            id,
+            i64::from(user_id)
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error cancelling reminder {}: {}", id, e);
+                false
+            }
+        }
+    }
+
+    async fn get_reminders_before(&self, until: OffsetDateTime) -> Vec<Reminder> {
+        let result = sqlx::query_as!(
+            ReminderRow,
+            "SELECT id, user_id, guild_id, channel_id, text, created_at, remind_at FROM reminders WHERE remind_at < $1 ORDER BY remind_at",
+            until
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(Reminder::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn delete_reminder(&self, id: i64) {
+        if let Err(e) = sqlx::query!("DELETE FROM reminders WHERE id = $1", id)
+            .execute(&self.pool)
+            .await
+        {
+            error!("Error deleting reminder {}: {}", id, e);
+        }
+    }
+}
+
+#[async_trait]
+impl TagRepository for PostgresDatabase {
+    async fn get_tags(&self, guild_id: GuildId) -> Vec<Tag> {
+        let result = sqlx::query_as!(
+            TagRow,
+            "SELECT guild_id, name, content, embed, owner_id, uses, created_at FROM tags WHERE guild_id = $1 ORDER BY name",
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(Tag::from).collect())
+            .unwrap_or_default()
+    }
+
+    async fn get_tag(&self, guild_id: GuildId, name: &str) -> Option<Tag> {
+        let result = sqlx::query_as!(
+            TagRow,
+            "SELECT guild_id, name, content, embed, owner_id, uses, created_at FROM tags WHERE guild_id = $1 AND name = $2",
+            i64::from(guild_id),
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(Tag::from),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn create_tag(&self, tag: &Tag) -> bool {
+        let result = sqlx::query!(
+            "INSERT INTO tags (guild_id, name, content, embed, owner_id, uses, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (guild_id, name) DO NOTHING",
+            i64::from(tag.guild_id),
+            tag.name,
+            tag.content,
+            tag.embed,
+            i64::from(tag.owner_id),
+            tag.uses,
+            tag.created_at
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) if result.rows_affected() > 0 => {
+                info!("Created tag {} for guild {}", tag.name, tag.guild_id);
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                error!("Error creating tag for guild {}: {}", tag.guild_id, e);
+                false
+            }
+        }
+    }
+
+    async fn update_tag(&self, tag: &Tag) {
+        if let Err(e) = sqlx::query!(
+            "UPDATE tags SET content = $3, embed = $4 WHERE guild_id = $1 AND name = $2",
+            i64::from(tag.guild_id),
+            tag.name,
+            tag.content,
+            tag.embed
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error updating tag for guild {}: {}", tag.guild_id, e);
+            return;
+        }
+
+        info!("Updated tag {} for guild {}", tag.name, tag.guild_id);
+    }
+
+    async fn delete_tag(&self, guild_id: GuildId, name: &str) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM tags WHERE guild_id = $1 AND name = $2",
+            i64::from(guild_id),
+            name
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error deleting tag for guild {}: {}", guild_id, e);
+                false
+            }
+        }
+    }
+
+    async fn use_tag(&self, guild_id: GuildId, name: &str) {
+        if let Err(e) = sqlx::query!(
+            "UPDATE tags SET uses = uses + 1 WHERE guild_id = $1 AND name = $2",
+            i64::from(guild_id),
+            name
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error: {}", e);
+        }
+    }
+}
+
+impl PostgresDatabase {
+    async fn get_panel_roles(&self, panel_ids: &[i64]) -> Vec<PanelRoleRow> {
+        let result = sqlx::query_as!(
+            PanelRoleRow,
+            "SELECT panel_id, role_id, label, emoji FROM role_panel_roles WHERE panel_id = ANY($1) ORDER BY position",
+            panel_ids
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl RolePanelRepository for PostgresDatabase {
+    async fn create_role_panel(&self, panel: &RolePanel) -> Option<i64> {
+        let result = sqlx::query_scalar!(
+            "INSERT INTO role_panels (guild_id, channel_id, message_id, kind, title, description) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+            i64::from(panel.guild_id),
+            i64::from(panel.channel_id),
+            i64::from(panel.message_id),
+            panel.kind as RolePanelKind,
+            panel.title,
+            panel.description
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        let id = match result {
+            Ok(id) => id,
+            Err(e) => {
+                error!(
+                    "Error creating role panel for guild {}: {}",
+                    panel.guild_id, e
+                );
+                return None;
+            }
+        };
+
+        for role in &panel.roles {
+            self.add_panel_role(id, role).await;
+        }
+
+        info!("Created role panel {} for guild {}", id, panel.guild_id);
+        Some(id)
+    }
+
+    async fn get_role_panels(&self, guild_id: GuildId) -> Vec<RolePanel> {
+        let result = sqlx::query_as!(
+            RolePanelRow,
+            r#"SELECT id, guild_id, channel_id, message_id, kind as "kind: RolePanelKind", title, description FROM role_panels WHERE guild_id = $1 ORDER BY id"#,
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        let rows = match result {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("Error: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let ids: Vec<_> = rows.iter().map(|row| row.id).collect();
+        let roles = self.get_panel_roles(&ids).await;
+
+        rows.into_iter().map(|row| row.into_panel(&roles)).collect()
+    }
+
+    async fn get_role_panel(&self, guild_id: GuildId, id: i64) -> Option<RolePanel> {
+        let result = sqlx::query_as!(
+            RolePanelRow,
+            r#"SELECT id, guild_id, channel_id, message_id, kind as "kind: RolePanelKind", title, description FROM role_panels WHERE guild_id = $1 AND id = $2"#,
+            i64::from(guild_id),
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => {
+                let roles = self.get_panel_roles(&[id]).await;
+                row.map(|row| row.into_panel(&roles))
+            }
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn add_panel_role(&self, panel_id: i64, role: &PanelRole) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO role_panel_roles (panel_id, role_id, label, emoji, position)
+            VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(position) + 1, 0) FROM role_panel_roles WHERE panel_id = $1))
+            ON CONFLICT (panel_id, role_id) DO UPDATE SET label = $3, emoji = $4",
+            panel_id,
+            i64::from(role.role_id),
+            role.label,
+            role.emoji
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error adding role to role panel {}: {}", panel_id, e);
+        }
+    }
+
+    async fn remove_panel_role(&self, panel_id: i64, role_id: RoleId) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM role_panel_roles WHERE panel_id = $1 AND role_id = $2",
+            panel_id,
+            i64::from(role_id)
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error removing role from role panel {}: {}", panel_id, e);
+                false
+            }
+        }
+    }
+
+    async fn delete_role_panel(&self, guild_id: GuildId, id: i64) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM role_panels WHERE guild_id = $1 AND id = $2",
+            i64::from(guild_id),
+            id
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error deleting role panel {}: {}", id, e);
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LevelRepository for PostgresDatabase {
+    async fn add_xp(&self, gains: &[MemberXp]) -> Option<Vec<MemberXp>> {
+        let guild_ids: Vec<_> = gains.iter().map(|g| i64::from(g.guild_id)).collect();
+        let user_ids: Vec<_> = gains.iter().map(|g| i64::from(g.user_id)).collect();
+        let xp: Vec<_> = gains.iter().map(|g| g.xp).collect();
+
+        let result = async {
+            let mut tx = self.pool.begin().await?;
+
+            sqlx::query!(
+                "INSERT INTO guilds (id) SELECT DISTINCT UNNEST($1::BIGINT[]) ON CONFLICT DO NOTHING",
+                &guild_ids
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            let rows = sqlx::query_as!(
+                MemberXpRow,
+                r#"INSERT INTO levels (guild_id, user_id, xp)
+                SELECT guild_id, user_id, SUM(xp)::BIGINT FROM UNNEST($1::BIGINT[], $2::BIGINT[], $3::BIGINT[]) AS gains (guild_id, user_id, xp)
+                GROUP BY guild_id, user_id
+                ON CONFLICT (guild_id, user_id) DO UPDATE SET xp = levels.xp + EXCLUDED.xp
+                RETURNING guild_id, user_id, xp"#,
+                &guild_ids,
+                &user_ids,
+                &xp
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            Ok::<_, sqlx::Error>(rows)
+        }
+        .await;
+
+        match result {
+            Ok(rows) => Some(rows.into_iter().map(MemberXp::from).collect()),
+            Err(e) => {
+                error!("Error adding XP for {} members: {}", gains.len(), e);
+                None
+            }
+        }
+    }
+
+    async fn get_xp(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+        let result = sqlx::query_scalar!(
+            "SELECT xp FROM levels WHERE guild_id = $1 AND user_id = $2",
+            i64::from(guild_id),
+            i64::from(user_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(xp) => xp.unwrap_or_default(),
+            Err(e) => {
+                error!("Error: {}", e);
+                0
+            }
+        }
+    }
+
+    async fn count_above(&self, guild_id: GuildId, xp: i64) -> i64 {
+        let result = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM levels WHERE guild_id = $1 AND xp > $2",
+            i64::from(guild_id),
+            xp
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(count) => count.unwrap_or_default(),
+            Err(e) => {
+                error!("Error: {}", e);
+                0
+            }
+        }
+    }
+
+    async fn count_ranked(&self, guild_id: GuildId) -> i64 {
+        self.count_above(guild_id, 0).await
+    }
+
+    async fn get_leaderboard(&self, guild_id: GuildId, offset: i64, limit: i64) -> Vec<MemberXp> {
+        let result = sqlx::query_as!(
+            MemberXpRow,
+            "SELECT guild_id, user_id, xp FROM levels WHERE guild_id = $1 AND xp > 0 ORDER BY xp DESC, user_id OFFSET $2 LIMIT $3",
+            i64::from(guild_id),
+            offset,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(MemberXp::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn get_level_rewards(&self, guild_id: GuildId) -> Vec<LevelReward> {
+        let result = sqlx::query_as!(
+            LevelRewardRow,
+            "SELECT guild_id, role_id, level FROM level_rewards WHERE guild_id = $1 ORDER BY level, role_id",
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(LevelReward::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn set_level_reward(&self, reward: &LevelReward) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO level_rewards (guild_id, role_id, level) VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, role_id) DO UPDATE SET level = $3",
+            i64::from(reward.guild_id),
+            i64::from(reward.role_id),
+            reward.level
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!(
+                "Error setting level reward for guild {}: {}",
+                reward.guild_id, e
+            );
+        }
+    }
+
+    async fn remove_level_reward(&self, guild_id: GuildId, role_id: RoleId) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM level_rewards WHERE guild_id = $1 AND role_id = $2",
+            i64::from(guild_id),
+            i64::from(role_id)
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error removing level reward for guild {}: {}", guild_id, e);
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StarboardRepository for PostgresDatabase {
+    async fn get_starboard_post(&self, message_id: MessageId) -> Option<StarboardPost> {
+        let result = sqlx::query_as!(
+            StarboardPostRow,
+            "SELECT guild_id, channel_id, message_id, starboard_channel_id, starboard_message_id, stars FROM starboard_posts WHERE message_id = $1",
+            i64::from(message_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(StarboardPost::from),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn set_starboard_post(&self, post: &StarboardPost) {
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO starboard_posts (message_id, guild_id, channel_id, starboard_channel_id, starboard_message_id, stars)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (message_id) DO UPDATE SET starboard_channel_id = $4, starboard_message_id = $5, stars = $6",
+            i64::from(post.message_id),
+            i64::from(post.guild_id),
+            i64::from(post.channel_id),
+            i64::from(post.starboard_channel_id),
+            i64::from(post.starboard_message_id),
+            post.stars
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!(
+                "Error storing starboard post for message {}: {}",
+                post.message_id, e
+            );
+        }
+    }
+
+    async fn delete_starboard_post(&self, message_id: MessageId) {
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM starboard_posts WHERE message_id = $1",
+            i64::from(message_id)
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!(
+                "Error deleting starboard post for message {}: {}",
+                message_id, e
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl PollRepository for PostgresDatabase {
+    async fn create_poll(&self, poll: &Poll) -> bool {
+        let result = sqlx::query!(
+            "INSERT INTO polls (message_id, guild_id, channel_id, user_id, question, options, multi_choice, anonymous, closes_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            i64::from(poll.message_id),
+            i64::from(poll.guild_id),
+            i64::from(poll.channel_id),
+            i64::from(poll.user_id),
+            poll.question,
+            &poll.options,
+            poll.multi_choice,
+            poll.anonymous,
+            poll.closes_at
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(_) => {
+                info!(
+                    "Created poll {} for guild {}",
+                    poll.message_id, poll.guild_id
+                );
+                true
+            }
+            Err(e) => {
+                error!("Error creating poll for guild {}: {}", poll.guild_id, e);
+                false
+            }
+        }
+    }
+
+    async fn get_poll(&self, message_id: MessageId) -> Option<Poll> {
+        let result = sqlx::query_as!(
+            PollRow,
+            "SELECT message_id, guild_id, channel_id, user_id, question, options, multi_choice, anonymous, closes_at FROM polls WHERE message_id = $1",
+            i64::from(message_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(Poll::from),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn get_polls_closing_before(&self, until: OffsetDateTime) -> Vec<Poll> {
+        let result = sqlx::query_as!(
+            PollRow,
+            "SELECT message_id, guild_id, channel_id, user_id, question, options, multi_choice, anonymous, closes_at FROM polls WHERE closes_at < $1 ORDER BY closes_at",
+            until
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(Poll::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn delete_poll(&self, message_id: MessageId) {
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM polls WHERE message_id = $1",
+            i64::from(message_id)
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error deleting poll {}: {}", message_id, e);
+        }
+    }
+
+    async fn get_poll_votes(&self, message_id: MessageId) -> Vec<PollVote> {
+        let result = sqlx::query_as!(
+            PollVoteRow,
+            "SELECT user_id, option FROM poll_votes WHERE message_id = $1",
+            i64::from(message_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(PollVote::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn toggle_poll_vote(
+        &self,
+        message_id: MessageId,
+        user_id: UserId,
+        option: i16,
+    ) -> Option<Vec<i16>> {
+        let result = async {
+            let mut tx = self.pool.begin().await?;
+
+            // Locking the poll queues up clicks on it until this one is stored
+            let Some(multi_choice) = sqlx::query_scalar!(
+                "SELECT multi_choice FROM polls WHERE message_id = $1 FOR UPDATE",
+                i64::from(message_id)
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                return Ok(None);
+            };
+
+            let current = sqlx::query_scalar!(
+                "DELETE FROM poll_votes WHERE message_id = $1 AND user_id = $2 RETURNING option",
+                i64::from(message_id),
+                i64::from(user_id)
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            let chosen = toggle(&current, option, multi_choice);
+
+            sqlx::query!(
+                "INSERT INTO poll_votes (message_id, user_id, option, multi_choice) SELECT $1, $2, UNNEST($3::SMALLINT[]), $4",
+                i64::from(message_id),
+                i64::from(user_id),
+                &chosen,
+                multi_choice
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            Ok::<_, sqlx::Error>(Some(chosen))
+        }
+        .await;
+
+        match result {
+            Ok(chosen) => chosen,
+            Err(e) => {
+                error!(
+                    "Error storing votes of {} on poll {}: {}",
+                    user_id, message_id, e
+                );
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl GiveawayRepository for PostgresDatabase {
+    async fn create_giveaway(&self, giveaway: &Giveaway) -> Option<i64> {
+        let result = sqlx::query_scalar!(
+            "INSERT INTO giveaways (guild_id, channel_id, message_id, host_id, prize, winner_count, required_role_id, min_account_age_secs, ends_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
+            i64::from(giveaway.guild_id),
+            i64::from(giveaway.channel_id),
+            i64::from(giveaway.message_id),
+            i64::from(giveaway.host_id),
+            giveaway.prize,
+            giveaway.winner_count,
+            giveaway.required_role_id.map(i64::from),
+            giveaway
+                .min_account_age
+                .map(|age| i64::try_from(age.as_secs()).unwrap_or(i64::MAX)),
+            giveaway.ends_at
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(id) => {
+                info!("Created giveaway {} for guild {}", id, giveaway.guild_id);
+                Some(id)
+            }
+            Err(e) => {
+                error!(
+                    "Error creating giveaway for guild {}: {}",
+                    giveaway.guild_id, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn get_giveaway(&self, guild_id: GuildId, id: i64) -> Option<Giveaway> {
+        let result = sqlx::query_as!(
+            GiveawayRow,
+            r#"SELECT id, guild_id, channel_id, message_id, host_id, prize, winner_count, required_role_id, min_account_age_secs, ends_at, ended,
+                (SELECT seed FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id DESC LIMIT 1) AS seed,
+                ARRAY(SELECT UNNEST(winner_ids) FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id) AS "winner_ids!"
+            FROM giveaways WHERE guild_id = $1 AND id = $2"#,
+            i64::from(guild_id),
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(Giveaway::from),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn get_giveaway_by_message(&self, message_id: MessageId) -> Option<Giveaway> {
+        let result = sqlx::query_as!(
+            GiveawayRow,
+            r#"SELECT id, guild_id, channel_id, message_id, host_id, prize, winner_count, required_role_id, min_account_age_secs, ends_at, ended,
+                (SELECT seed FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id DESC LIMIT 1) AS seed,
+                ARRAY(SELECT UNNEST(winner_ids) FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id) AS "winner_ids!"
+            FROM giveaways WHERE message_id = $1"#,
+            i64::from(message_id)
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match result {
+            Ok(row) => row.map(Giveaway::from),
+            Err(e) => {
+                error!("Error: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn get_giveaways(&self, guild_id: GuildId) -> Vec<Giveaway> {
+        let result = sqlx::query_as!(
+            GiveawayRow,
+            r#"SELECT id, guild_id, channel_id, message_id, host_id, prize, winner_count, required_role_id, min_account_age_secs, ends_at, ended,
+                (SELECT seed FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id DESC LIMIT 1) AS seed,
+                ARRAY(SELECT UNNEST(winner_ids) FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id) AS "winner_ids!"
+            FROM giveaways WHERE guild_id = $1 ORDER BY id DESC"#,
+            i64::from(guild_id)
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        result
+            .map(|rows| rows.into_iter().map(Giveaway::from).collect())
+            .unwrap_or_default()
+    }
+
+    async fn get_giveaways_ending_before(&self, until: OffsetDateTime) -> Vec<Giveaway> {
+        let result = sqlx::query_as!(
+            GiveawayRow,
+            r#"SELECT id, guild_id, channel_id, message_id, host_id, prize, winner_count, required_role_id, min_account_age_secs, ends_at, ended,
+                (SELECT seed FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id DESC LIMIT 1) AS seed,
+                ARRAY(SELECT UNNEST(winner_ids) FROM giveaway_draws WHERE giveaway_id = giveaways.id ORDER BY id) AS "winner_ids!"
+            FROM giveaways WHERE NOT ended AND ends_at < $1 ORDER BY ends_at"#,
+            until
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(rows) => rows.into_iter().map(Giveaway::from).collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn add_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        // Locking the giveaway makes ending it wait for the entry, or the entry
+        // see that it ended
+        let result = sqlx::query!(
+            "INSERT INTO giveaway_entries (giveaway_id, user_id)
+            SELECT id, $2 FROM giveaways WHERE id = $1 AND NOT ended FOR SHARE
+            ON CONFLICT DO NOTHING",
+            id,
+            i64::from(user_id)
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error entering {} into giveaway {}: {}", user_id, id, e);
+                false
+            }
+        }
+    }
+
+    async fn remove_giveaway_entry(&self, id: i64, user_id: UserId) -> bool {
+        let result = sqlx::query!(
+            "DELETE FROM giveaway_entries USING giveaways
+            WHERE giveaways.id = giveaway_id AND giveaway_id = $1 AND user_id = $2 AND NOT giveaways.ended",
+            id,
+            i64::from(user_id)
+        )
+        .execute(&self.pool)
+        .await;
+
+        match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("Error withdrawing {} from giveaway {}: {}", user_id, id, e);
+                false
+            }
+        }
+    }
+
+    async fn get_giveaway_entries(&self, id: i64) -> Vec<UserId> {
+        let result = sqlx::query_scalar!(
+            "SELECT user_id FROM giveaway_entries WHERE giveaway_id = $1",
+            id
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match result {
+            Ok(ids) => ids
+                .into_iter()
+                .map(|id| UserId::new(id.cast_unsigned()))
+                .collect(),
+            Err(e) => {
+                error!("Error: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn count_giveaway_entries(&self, id: i64) -> i64 {
+        let result = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM giveaway_entries WHERE giveaway_id = $1",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(count) => count.unwrap_or_default(),
+            Err(e) => {
+                error!("Error: {}", e);
+                0
+            }
+        }
+    }
+
+    async fn end_giveaway(&self, id: i64, seed: u64) -> Option<(Vec<UserId>, Vec<UserId>)> {
+        let result = async {
+            let mut tx = self.pool.begin().await?;
+
+            // Entering takes a share lock on the giveaway, so no entry slips in
+            // after this
+            let Some(winner_count) = sqlx::query_scalar!(
+                "UPDATE giveaways SET ended = TRUE WHERE id = $1 AND NOT ended RETURNING winner_count",
+                id
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                return Ok(None);
+            };
+
+            let entries: Vec<_> = sqlx::query_scalar!(
+                "SELECT user_id FROM giveaway_entries WHERE giveaway_id = $1",
+                id
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|id| UserId::new(id.cast_unsigned()))
+            .collect();
+            let count = usize::try_from(winner_count).unwrap_or_default();
+            let winners = draw(&entries, &[], count, seed);
+            let winner_ids: Vec<_> = winners.iter().map(|id| i64::from(*id)).collect();
+
+            sqlx::query!(
+                "INSERT INTO giveaway_draws (giveaway_id, seed, excluded_ids, winner_ids) VALUES ($1, $2, '{}', $3)",
+                id,
+                seed.cast_signed(),
+                &winner_ids
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            Ok::<_, sqlx::Error>(Some((entries, winners)))
+        }
+        .await;
+
+        match result {
+            Ok(ended) => ended,
+            Err(e) => {
+                error!("Error ending giveaway {}: {}", id, e);
+                None
+            }
+        }
+    }
+
+    async fn add_giveaway_draw(
+        &self,
+        id: i64,
+        seed: u64,
+        excluded_ids: &[UserId],
+        winner_ids: &[UserId],
+    ) {
+        let excluded_ids: Vec<_> = excluded_ids.iter().map(|id| i64::from(*id)).collect();
+        let winner_ids: Vec<_> = winner_ids.iter().map(|id| i64::from(*id)).collect();
+
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO giveaway_draws (giveaway_id, seed, excluded_ids, winner_ids) VALUES ($1, $2, $3, $4)",
+            id,
+            seed.cast_signed(),
+            &excluded_ids,
+            &winner_ids
+        )
+        .execute(&self.pool)
+        .await
+        {
+            error!("Error storing draw of giveaway {}: {}", id, e);
+        }
+    }
+}
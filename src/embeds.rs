@@ -0,0 +1,111 @@
+use crate::{
+    database::get_database,
+    structs::{Context, Theme},
+};
+use poise::serenity_prelude::{Color, CreateEmbed, CreateEmbedFooter, Timestamp};
+
+pub const DEFAULT_PRIMARY: Color = Color::BLUE;
+pub const DEFAULT_SUCCESS: Color = Color::BLUE;
+pub const DEFAULT_ERROR: Color = Color::RED;
+
+/// Embed field values are limited to 1024 characters.
+pub const FIELD_LIMIT: usize = 1024;
+/// Embed descriptions are limited to 4096 characters.
+pub const DESCRIPTION_LIMIT: usize = 4096;
+/// Everything in an embed together is limited to 6000 characters.
+pub const EMBED_LIMIT: usize = 6000;
+
+/// Joins items with `separator`, leaving out whatever doesn't fit in `limit`.
+pub fn join_limited(
+    items: impl IntoIterator<Item = String>,
+    separator: &str,
+    limit: usize,
+) -> String {
+    let mut joined = String::new();
+
+    for item in items {
+        if joined.len() + separator.len() + item.len() > limit - 1 {
+            joined.push('…');
+            break;
+        }
+
+        if !joined.is_empty() {
+            joined.push_str(separator);
+        }
+
+        joined.push_str(&item);
+    }
+
+    joined
+}
+
+/// Draws `value` out of `max` as a bar `width` characters wide.
+pub fn bar(value: i64, max: i64, width: usize) -> String {
+    let filled = if max > 0 {
+        let scale = i64::try_from(width).unwrap_or(i64::MAX);
+        usize::try_from(value.clamp(0, max) * scale / max).unwrap_or_default()
+    } else {
+        0
+    };
+
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Formats a color as a hex code such as `#5865F2`.
+pub fn hex(color: Color) -> String {
+    format!("#{:06X}", color.0)
+}
+
+#[derive(Clone, Copy)]
+pub enum EmbedKind {
+    /// Information and results, such as images or settings.
+    Primary,
+    /// Confirms that something was changed or carried out.
+    Success,
+    Error,
+}
+
+impl Theme {
+    pub fn color(&self, kind: EmbedKind) -> Color {
+        match kind {
+            EmbedKind::Primary => self.primary.unwrap_or(DEFAULT_PRIMARY),
+            EmbedKind::Success => self.success.unwrap_or(DEFAULT_SUCCESS),
+            EmbedKind::Error => self.error.unwrap_or(DEFAULT_ERROR),
+        }
+    }
+
+    /// Starts an embed with the theme's color, footer and timestamp applied.
+    pub fn embed(&self, kind: EmbedKind) -> CreateEmbed {
+        let mut embed = CreateEmbed::new().color(self.color(kind));
+
+        if let Some(text) = &self.footer_text {
+            let mut footer = CreateEmbedFooter::new(text);
+
+            if let Some(icon) = &self.footer_icon {
+                footer = footer.icon_url(icon);
+            }
+
+            embed = embed.footer(footer);
+        }
+
+        if self.timestamps {
+            embed = embed.timestamp(Timestamp::now());
+        }
+
+        embed
+    }
+}
+
+/// The theme of the guild the command was used in, or the default one in DMs.
+pub async fn get_theme(ctx: Context<'_>) -> Theme {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Theme::default();
+    };
+
+    let db = get_database(ctx.serenity_context()).await;
+    db.get_settings(guild_id).await.theme
+}
+
+pub async fn embed(ctx: Context<'_>, kind: EmbedKind) -> CreateEmbed {
+    get_theme(ctx).await.embed(kind)
+}
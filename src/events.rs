@@ -1,5 +1,15 @@
-use poise::serenity_prelude::{async_trait, Context, EventHandler, Guild, Ready};
-use tracing::info;
+use crate::{
+    database::{get_database, get_settings_cache},
+    framework::restarting,
+    giveaways, levels, polls, role_panels, starboard,
+    structs::GatewayStatusContainer,
+    utils::get_shutdown,
+};
+use poise::serenity_prelude::{
+    async_trait, ChannelId, Context, EventHandler, Guild, Interaction, Message, MessageId,
+    Reaction, Ready, ShardStageUpdateEvent, UnavailableGuild,
+};
+use tracing::{error, info};
 
 pub struct Handler;
 
@@ -12,4 +22,89 @@ impl EventHandler for Handler {
     async fn guild_create(&self, _: Context, guild: Guild, _: Option<bool>) {
         info!("Joined guild {}", guild.name);
     }
+
+    async fn guild_delete(&self, ctx: Context, incomplete: UnavailableGuild, _: Option<Guild>) {
+        if !incomplete.unavailable {
+            info!("Left guild {}", incomplete.id);
+        }
+
+        get_settings_cache(&ctx).await.evict(incomplete.id);
+    }
+
+    async fn message(&self, ctx: Context, new_message: Message) {
+        levels::on_message(&ctx, &new_message).await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, add_reaction: Reaction) {
+        starboard::on_reaction(&ctx, &add_reaction).await;
+    }
+
+    async fn reaction_remove(&self, ctx: Context, removed_reaction: Reaction) {
+        starboard::on_reaction(&ctx, &removed_reaction).await;
+    }
+
+    async fn reaction_remove_emoji(&self, ctx: Context, removed_reactions: Reaction) {
+        starboard::on_reaction(&ctx, &removed_reactions).await;
+    }
+
+    // Clearing reactions only matters for posted messages, whose guild is stored
+    async fn reaction_remove_all(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        removed_from_message_id: MessageId,
+    ) {
+        let post = get_database(&ctx)
+            .await
+            .get_starboard_post(removed_from_message_id)
+            .await;
+
+        if let Some(post) = post {
+            starboard::update(&ctx, post.guild_id, channel_id, removed_from_message_id).await;
+        }
+    }
+
+    // Commands are handled by the framework, components of persistent messages here.
+    // Clicks write like commands do, so they're refused and waited for the same way.
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(component) = interaction else {
+            return;
+        };
+
+        let shutdown = get_shutdown(&ctx).await;
+        if !shutdown.is_accepting() {
+            // Other components belong to collectors, which answer them
+            let custom_id = &component.data.custom_id;
+            if role_panels::parse_custom_id(custom_id).is_some()
+                || polls::parse_custom_id(custom_id).is_some()
+                || giveaways::is_custom_id(custom_id)
+            {
+                let response = restarting(&ctx, &component.locale).await;
+                if let Err(e) = component.create_response(&ctx, response).await {
+                    error!("Error: {}", e);
+                }
+            }
+            return;
+        }
+
+        shutdown
+            .commands()
+            .track_future(async {
+                role_panels::handle(&ctx, &component).await;
+                polls::handle(&ctx, &component).await;
+                giveaways::handle(&ctx, &component).await;
+            })
+            .await;
+    }
+
+    async fn shard_stage_update(&self, ctx: Context, event: ShardStageUpdateEvent) {
+        info!(
+            "Shard {} changed stage from {} to {}",
+            event.shard_id, event.old, event.new
+        );
+
+        if let Some(status) = ctx.data.read().await.get::<GatewayStatusContainer>() {
+            status.update(event.shard_id, event.new);
+        }
+    }
 }
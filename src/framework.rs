@@ -0,0 +1,230 @@
+use crate::{
+    cooldowns,
+    database::get_database,
+    embeds::EmbedKind,
+    i18n::Lang,
+    permissions,
+    shutdown::Shutdown,
+    structs::{Command, Context as PoiseContext, Data, Error, Theme},
+    tags,
+    utils::get_locales,
+};
+use poise::{
+    serenity_prelude::{
+        async_trait, Client, Context, CreateInteractionResponse, CreateInteractionResponseMessage,
+        Framework, FullEvent, Interaction, Message, UserId,
+    },
+    FrameworkError, MessageDispatchTrigger,
+};
+use std::sync::Arc;
+use tracing::{error, info, info_span, Instrument, Span};
+
+/// Wraps the poise framework so every command invocation runs inside its own
+/// tracing span and is tracked for graceful shutdown. Anything logged while the
+/// command runs, including database queries and HTTP requests, is recorded under
+/// that span.
+pub struct BotFramework {
+    pub inner: poise::Framework<Data, Error>,
+    pub shutdown: Arc<Shutdown>,
+}
+
+#[async_trait]
+impl Framework for BotFramework {
+    async fn init(&mut self, client: &Client) {
+        self.inner.init(client).await;
+    }
+
+    async fn dispatch(&self, ctx: Context, event: FullEvent) {
+        if !self.shutdown.is_accepting() {
+            return refuse(&ctx, &event).await;
+        }
+
+        let span = invocation_span(&event);
+        let dispatch = self.inner.dispatch(ctx, event);
+
+        match span {
+            Some(span) => {
+                self.shutdown
+                    .commands()
+                    .track_future(dispatch.instrument(span))
+                    .await;
+            }
+            None => self.shutdown.commands().track_future(dispatch).await,
+        }
+    }
+}
+
+fn invocation_span(event: &FullEvent) -> Option<Span> {
+    match event {
+        FullEvent::InteractionCreate {
+            interaction: Interaction::Command(interaction),
+        } => Some(info_span!(
+            "command",
+            command = %interaction.data.name,
+            guild_id = interaction.guild_id.map(u64::from),
+            user_id = %interaction.user.id,
+            invocation_id = %interaction.id,
+        )),
+        // Whether a message is a text command is only known once poise parses
+        // it, so every message from a user gets a span
+        FullEvent::Message { new_message } if !new_message.author.bot => Some(info_span!(
+            "message",
+            guild_id = new_message.guild_id.map(u64::from),
+            user_id = %new_message.author.id,
+            invocation_id = %new_message.id,
+        )),
+        _ => None,
+    }
+}
+
+async fn refuse(ctx: &Context, event: &FullEvent) {
+    let FullEvent::InteractionCreate {
+        interaction: Interaction::Command(interaction),
+    } = event
+    else {
+        return;
+    };
+
+    let response = restarting(ctx, &interaction.locale).await;
+    if let Err(e) = interaction.create_response(ctx, response).await {
+        error!("Error: {}", e);
+    }
+}
+
+/// The answer to interactions refused while shutting down. Nothing is looked up
+/// then, so it's in the client's language with the default theme.
+pub async fn restarting(ctx: &Context, locale: &str) -> CreateInteractionResponse {
+    let lang = Lang::new(get_locales(ctx).await, locale);
+
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .embed(
+                Theme::default()
+                    .embed(EmbedKind::Error)
+                    .description(lang.tr("restarting")),
+            )
+            .ephemeral(true),
+    )
+}
+
+pub fn options(commands: Vec<Command>) -> poise::FrameworkOptions<Data, Error> {
+    poise::FrameworkOptions {
+        commands,
+        pre_command: |ctx| {
+            Box::pin(async move {
+                info!(
+                    "Command {} was ran by user {}",
+                    ctx.command().name,
+                    ctx.author().tag()
+                );
+            })
+        },
+        post_command: |ctx| {
+            Box::pin(async move {
+                info!(
+                    "Command {} has successfully finished running by user {}",
+                    ctx.command().name,
+                    ctx.author().tag()
+                );
+            })
+        },
+        prefix_options: poise::PrefixFrameworkOptions {
+            stripped_dynamic_prefix: Some(|ctx, msg, data| Box::pin(strip_prefix(ctx, msg, data))),
+            mention_as_prefix: false,
+            ..Default::default()
+        },
+        command_check: Some(|ctx| Box::pin(command_check(ctx))),
+        on_error: |error| Box::pin(on_error(error)),
+        manual_cooldowns: true,
+        ..Default::default()
+    }
+}
+
+/// Splits a message into the prefix it starts with and the rest. A mention of
+/// `mention_of`, when given, also counts as a prefix.
+pub fn split_prefix<'a>(
+    content: &'a str,
+    prefix: &str,
+    mention_of: Option<UserId>,
+) -> Option<(&'a str, &'a str)> {
+    if content.starts_with(prefix) {
+        return Some(content.split_at(prefix.len()));
+    }
+
+    let id = mention_of?;
+
+    [format!("<@{id}>"), format!("<@!{id}>")]
+        .iter()
+        .find(|mention| content.starts_with(mention.as_str()))
+        .map(|mention| {
+            let (prefix, rest) = content.split_at(mention.len());
+            (prefix, rest.trim_start())
+        })
+}
+
+/// Uses the guild's prefix and mention setting, or the defaults in DMs.
+async fn strip_prefix<'a>(
+    ctx: &'a Context,
+    msg: &'a Message,
+    data: &'a Data,
+) -> Result<Option<(&'a str, &'a str)>, Error> {
+    let (prefix, mention_prefix) = match msg.guild_id {
+        Some(guild_id) => {
+            let guild = get_database(ctx).await.get_settings(guild_id).await;
+            (
+                guild.prefix.unwrap_or_else(|| data.default_prefix.clone()),
+                guild.mention_prefix,
+            )
+        }
+        None => (data.default_prefix.clone(), true),
+    };
+
+    let bot_id = ctx.cache.current_user().id;
+
+    Ok(split_prefix(
+        &msg.content,
+        &prefix,
+        mention_prefix.then_some(bot_id),
+    ))
+}
+
+/// Marks an invocation that already passed the checks, since poise runs the
+/// command check again for each parent of a subcommand.
+struct Checked;
+
+/// Runs before every command. Checks that refuse a command reply to the user
+/// themselves. Permissions go first so refused invocations don't start a cooldown.
+pub async fn command_check(ctx: PoiseContext<'_>) -> Result<bool, Error> {
+    if ctx.invocation_data::<Checked>().await.is_some() {
+        return Ok(true);
+    }
+
+    if !permissions::check(ctx).await || !cooldowns::check(ctx).await {
+        return Ok(false);
+    }
+
+    ctx.set_invocation_data(Checked).await;
+
+    Ok(true)
+}
+
+pub async fn on_error(error: FrameworkError<'_, Data, Error>) {
+    match error {
+        // The check already told the user why, so there is nothing to report
+        FrameworkError::CommandCheckFailed { error: None, .. } => {}
+        // Tags work as text commands too
+        FrameworkError::UnknownCommand {
+            ctx,
+            msg,
+            msg_content,
+            framework,
+            trigger: MessageDispatchTrigger::MessageCreate,
+            ..
+        } => tags::post_as_command(ctx, msg, msg_content, &framework.options.commands).await,
+        error => {
+            if let Err(e) = poise::builtins::on_error(error).await {
+                error!("Error: {}", e);
+            }
+        }
+    }
+}
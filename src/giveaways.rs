@@ -0,0 +1,527 @@
+use crate::{
+    database::{get_database, Database},
+    embeds::{join_limited, EmbedKind, DESCRIPTION_LIMIT},
+    i18n::{resolve, Lang, Locales},
+    reminders::relative_time,
+    scheduler,
+    shutdown::Shutdown,
+    structs::{Giveaway, ShardConfig, Theme},
+    utils::{defer_ephemeral, format_duration, get_locales, respond},
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteraction, Context, CreateActionRow, CreateAllowedMentions,
+    CreateButton, CreateEmbed, CreateEmbedAuthor, CreateMessage, EditMessage, Http, ReactionType,
+    RoleId, UserId,
+};
+use sqlx::types::time::OffsetDateTime;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+/// Giveaways can have at most this many winners.
+pub const MAX_WINNERS: i32 = 20;
+const CUSTOM_ID: &str = "giveaway";
+const EMOJI: &str = "🎉";
+
+/// `SplitMix64`, a tiny generator whose output only depends on its seed. Unlike
+/// the generators in `rand` it can't change between versions, so a logged seed
+/// reproduces its draw for good.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number below `bound`. Values from the uneven end of the range are
+    /// thrown away, so every number is equally likely.
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let value = self.next();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// Picks up to `count` different winners from the entrants, leaving out
+/// `exclude`. Entrants are sorted first, so the same entries and seed always
+/// draw the same winners, in the same order.
+pub fn draw(entrants: &[UserId], exclude: &[UserId], count: usize, seed: u64) -> Vec<UserId> {
+    let mut pool: Vec<_> = entrants
+        .iter()
+        .copied()
+        .filter(|user_id| !exclude.contains(user_id))
+        .collect();
+    pool.sort_unstable();
+    pool.dedup();
+
+    let count = count.min(pool.len());
+    let mut rng = SplitMix64(seed);
+
+    // A partial Fisher-Yates shuffle, the first `count` entrants are the winners
+    for i in 0..count {
+        let remaining = u64::try_from(pool.len() - i).unwrap_or(u64::MAX);
+        let j = i + usize::try_from(rng.below(remaining)).unwrap_or_default();
+        pool.swap(i, j);
+    }
+
+    pool.truncate(count);
+    pool
+}
+
+/// A requirement a member doesn't meet.
+#[derive(Debug, PartialEq)]
+pub enum Unmet {
+    Role(RoleId),
+    AccountAge(Duration),
+}
+
+pub fn check_requirements(
+    giveaway: &Giveaway,
+    member_roles: &[RoleId],
+    account_created: OffsetDateTime,
+    now: OffsetDateTime,
+) -> Option<Unmet> {
+    if let Some(role_id) = giveaway
+        .required_role_id
+        .filter(|role_id| !member_roles.contains(role_id))
+    {
+        return Some(Unmet::Role(role_id));
+    }
+
+    giveaway
+        .min_account_age
+        .filter(|age| now - account_created < *age)
+        .map(Unmet::AccountAge)
+}
+
+fn mentions(user_ids: &[UserId]) -> impl Iterator<Item = String> + '_ {
+    user_ids.iter().map(|user_id| format!("<@{user_id}>"))
+}
+
+/// The giveaway while it runs, or its winners once it ended.
+pub fn giveaway_embed(
+    theme: &Theme,
+    lang: &Lang,
+    giveaway: &Giveaway,
+    entries: i64,
+) -> CreateEmbed {
+    let time = relative_time(giveaway.ends_at);
+    let mut lines = Vec::new();
+
+    if giveaway.ended {
+        lines.push(lang.tr_with("giveaway-ended", &fluent_args!["time" => time]));
+
+        lines.push(if giveaway.winner_ids.is_empty() {
+            lang.tr("giveaway-no-winners")
+        } else {
+            let winners = join_limited(mentions(&giveaway.winner_ids), ", ", DESCRIPTION_LIMIT / 2);
+            lang.tr_with("giveaway-winners", &fluent_args!["winners" => winners])
+        });
+    } else {
+        lines.push(lang.tr_with("giveaway-ends", &fluent_args!["time" => time]));
+        lines.push(lang.tr_with(
+            "giveaway-winner-count",
+            &fluent_args!["count" => giveaway.winner_count],
+        ));
+    }
+
+    lines.push(lang.tr_with(
+        "giveaway-host",
+        &fluent_args!["host" => format!("<@{}>", giveaway.host_id)],
+    ));
+    lines.push(lang.tr_with("giveaway-entries", &fluent_args!["count" => entries]));
+
+    if let Some(role_id) = giveaway.required_role_id {
+        lines.push(lang.tr_with(
+            "giveaway-requires-role",
+            &fluent_args!["role" => format!("<@&{role_id}>")],
+        ));
+    }
+    if let Some(age) = giveaway.min_account_age {
+        lines.push(lang.tr_with(
+            "giveaway-requires-age",
+            &fluent_args!["age" => format_duration(age)],
+        ));
+    }
+    if let Some(seed) = giveaway.seed {
+        lines.push(lang.tr_with("giveaway-seed", &fluent_args!["seed" => seed.to_string()]));
+    }
+
+    let kind = if giveaway.ended {
+        EmbedKind::Success
+    } else {
+        EmbedKind::Primary
+    };
+
+    theme
+        .embed(kind)
+        .author(CreateEmbedAuthor::new(lang.tr("giveaway-title")))
+        .title(&giveaway.prize)
+        .description(lines.join("\n"))
+}
+
+/// The button to enter, which is taken away once the giveaway ends.
+pub fn giveaway_components(lang: &Lang, giveaway: &Giveaway) -> Vec<CreateActionRow> {
+    if giveaway.ended {
+        return Vec::new();
+    }
+
+    vec![CreateActionRow::Buttons(vec![CreateButton::new(CUSTOM_ID)
+        .label(lang.tr("giveaway-enter"))
+        .emoji(ReactionType::Unicode(EMOJI.to_string()))
+        .style(ButtonStyle::Primary)])]
+}
+
+/// Whether `custom_id` belongs to a giveaway's enter button.
+pub fn is_custom_id(custom_id: &str) -> bool {
+    custom_id == CUSTOM_ID
+}
+
+/// Stores giveaways and wakes the scheduler when one is added, in case it
+/// ends before the scheduler would next look.
+pub struct Giveaways {
+    db: Arc<dyn Database>,
+    wake: Arc<Notify>,
+}
+
+impl Giveaways {
+    pub fn new(db: Arc<dyn Database>) -> Self {
+        Self {
+            db,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn add(&self, giveaway: &Giveaway) -> Option<i64> {
+        let id = self.db.create_giveaway(giveaway).await;
+        self.wake.notify_one();
+        id
+    }
+}
+
+/// Posts the winners under the giveaway, mentioning only them.
+async fn announce(http: &Http, lang: &Lang, giveaway: &Giveaway, winners: &[UserId], id: &str) {
+    let link = giveaway
+        .message_id
+        .link(giveaway.channel_id, Some(giveaway.guild_id));
+    let content = lang.tr_with(
+        id,
+        &fluent_args![
+            "winners" => mentions(winners).collect::<Vec<_>>().join(", "),
+            "prize" => giveaway.prize.as_str(),
+            "link" => link,
+        ],
+    );
+
+    if let Err(e) = giveaway
+        .channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .allowed_mentions(CreateAllowedMentions::new().users(winners)),
+        )
+        .await
+    {
+        error!("Error announcing giveaway {}: {}", giveaway.id, e);
+    }
+}
+
+/// Edits the ended giveaway's winners into its message and takes away the
+/// enter button.
+async fn show_ended(http: &Http, theme: &Theme, lang: &Lang, giveaway: &Giveaway, entries: i64) {
+    if let Err(e) = giveaway
+        .channel_id
+        .edit_message(
+            http,
+            giveaway.message_id,
+            EditMessage::new()
+                .embed(giveaway_embed(theme, lang, giveaway, entries))
+                .components(Vec::new()),
+        )
+        .await
+    {
+        error!("Error updating giveaway {}: {}", giveaway.id, e);
+    }
+}
+
+/// Ends the giveaway together with drawing the winners from the entries it
+/// closed with, then edits them into the giveaway's message and announces them.
+/// Returns `false` if the giveaway had already ended, so a giveaway ended by
+/// hand and by the scheduler at the same time is only drawn once, or if it
+/// couldn't be stored, in which case the scheduler tries again later.
+pub async fn end(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    giveaway: &Giveaway,
+) -> bool {
+    let seed = rand::random();
+    let Some((entries, winners)) = db.end_giveaway(giveaway.id, seed).await else {
+        return false;
+    };
+
+    info!(
+        "Ended giveaway {} with seed {}, drawing {:?} from {} entries",
+        giveaway.id,
+        seed,
+        winners,
+        entries.len()
+    );
+
+    let ended = Giveaway {
+        ended: true,
+        seed: Some(seed),
+        winner_ids: winners.clone(),
+        ..giveaway.clone()
+    };
+    let lang = resolve(db, locales.clone(), Some(giveaway.guild_id), None).await;
+    let theme = db.get_settings(giveaway.guild_id).await.theme;
+    let entry_count = i64::try_from(entries.len()).unwrap_or(i64::MAX);
+
+    show_ended(http, &theme, &lang, &ended, entry_count).await;
+
+    if winners.is_empty() {
+        announce(
+            http,
+            &lang,
+            &ended,
+            &winners,
+            "giveaway-no-entries-announcement",
+        )
+        .await;
+    } else {
+        announce(
+            http,
+            &lang,
+            &ended,
+            &winners,
+            "giveaway-winners-announcement",
+        )
+        .await;
+    }
+
+    true
+}
+
+/// Draws `count` more winners from entrants who haven't won yet, adds them to
+/// the giveaway's message and announces them, returning who was drawn.
+pub async fn reroll(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    giveaway: &Giveaway,
+    count: usize,
+) -> Vec<UserId> {
+    let entries = db.get_giveaway_entries(giveaway.id).await;
+    let seed = rand::random();
+    let winners = draw(&entries, &giveaway.winner_ids, count, seed);
+
+    if winners.is_empty() {
+        return winners;
+    }
+
+    db.add_giveaway_draw(giveaway.id, seed, &giveaway.winner_ids, &winners)
+        .await;
+
+    info!(
+        "Rerolled giveaway {} with seed {}, drawing {:?} from {} entries excluding {:?}",
+        giveaway.id,
+        seed,
+        winners,
+        entries.len(),
+        giveaway.winner_ids
+    );
+
+    let rerolled = Giveaway {
+        seed: Some(seed),
+        winner_ids: [giveaway.winner_ids.as_slice(), &winners].concat(),
+        ..giveaway.clone()
+    };
+    let lang = resolve(db, locales.clone(), Some(giveaway.guild_id), None).await;
+    let theme = db.get_settings(giveaway.guild_id).await.theme;
+    let entry_count = i64::try_from(entries.len()).unwrap_or(i64::MAX);
+
+    show_ended(http, &theme, &lang, &rerolled, entry_count).await;
+    announce(
+        http,
+        &lang,
+        &rerolled,
+        &winners,
+        "giveaway-reroll-announcement",
+    )
+    .await;
+
+    winners
+}
+
+/// Ends every owned giveaway that is due, including ones that came due while
+/// the bot was offline, and returns when the next one within `lookahead` ends.
+pub async fn end_due(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    shard_config: &ShardConfig,
+    lookahead: Duration,
+) -> Option<OffsetDateTime> {
+    let now = OffsetDateTime::now_utc();
+
+    for giveaway in db
+        .get_giveaways_ending_before(now + lookahead)
+        .await
+        .into_iter()
+        .filter(|g| shard_config.owns_guild(g.guild_id))
+    {
+        if giveaway.ends_at > now {
+            return Some(giveaway.ends_at);
+        }
+
+        end(http, db, locales, &giveaway).await;
+    }
+
+    None
+}
+
+/// Handles clicks on the enter button, which enter members who meet the
+/// requirements or withdraw them if they already entered.
+pub async fn handle(ctx: &Context, interaction: &ComponentInteraction) {
+    if !is_custom_id(&interaction.data.custom_id) {
+        return;
+    }
+    let (Some(guild_id), Some(member)) = (interaction.guild_id, interaction.member.as_ref()) else {
+        return;
+    };
+    if !defer_ephemeral(ctx, interaction).await {
+        return;
+    }
+
+    let db = get_database(ctx).await;
+    let locales = get_locales(ctx).await;
+    let lang = resolve(
+        db.as_ref(),
+        locales.clone(),
+        Some(guild_id),
+        Some(interaction.user.id),
+    )
+    .await;
+    let theme = db.get_settings(guild_id).await.theme;
+    let now = OffsetDateTime::now_utc();
+
+    let error = |description: String| {
+        theme
+            .embed(EmbedKind::Error)
+            .author(
+                CreateEmbedAuthor::new(lang.tr("error-title")).icon_url(interaction.user.face()),
+            )
+            .description(description)
+    };
+
+    // The button stays until the scheduler gets to the giveaway
+    let giveaway = db
+        .get_giveaway_by_message(interaction.message.id)
+        .await
+        .filter(|giveaway| !giveaway.ended && giveaway.ends_at > now);
+    let Some(giveaway) = giveaway else {
+        return respond(ctx, interaction, error(lang.tr("giveaway-gone"))).await;
+    };
+
+    let user_id = interaction.user.id;
+    let description = if db.remove_giveaway_entry(giveaway.id, user_id).await {
+        lang.tr("giveaway-withdrawn")
+    } else {
+        let account_created =
+            OffsetDateTime::from_unix_timestamp(user_id.created_at().unix_timestamp())
+                .unwrap_or(now);
+
+        match check_requirements(&giveaway, &member.roles, account_created, now) {
+            Some(Unmet::Role(role_id)) => {
+                let msg = lang.tr_with(
+                    "giveaway-missing-role",
+                    &fluent_args!["role" => format!("<@&{role_id}>")],
+                );
+                return respond(ctx, interaction, error(msg)).await;
+            }
+            Some(Unmet::AccountAge(age)) => {
+                let msg = lang.tr_with(
+                    "giveaway-account-too-new",
+                    &fluent_args!["age" => format_duration(age)],
+                );
+                return respond(ctx, interaction, error(msg)).await;
+            }
+            None => {}
+        }
+
+        // The giveaway can end between looking it up and entering
+        if !db.add_giveaway_entry(giveaway.id, user_id).await {
+            return respond(ctx, interaction, error(lang.tr("giveaway-gone"))).await;
+        }
+
+        lang.tr_with(
+            "giveaway-entered",
+            &fluent_args!["prize" => giveaway.prize.as_str()],
+        )
+    };
+
+    let embed = theme
+        .embed(EmbedKind::Success)
+        .author(
+            CreateEmbedAuthor::new(lang.tr("giveaway-your-entry"))
+                .icon_url(interaction.user.face()),
+        )
+        .description(description);
+    respond(ctx, interaction, embed).await;
+
+    // The giveaway is shown to everyone, so it's in the server's language
+    let entries = db.count_giveaway_entries(giveaway.id).await;
+    let giveaway_lang = resolve(db.as_ref(), locales, Some(guild_id), None).await;
+    if let Err(e) = giveaway
+        .channel_id
+        .edit_message(
+            ctx,
+            giveaway.message_id,
+            EditMessage::new().embed(giveaway_embed(&theme, &giveaway_lang, &giveaway, entries)),
+        )
+        .await
+    {
+        error!("Error updating giveaway {}: {}", giveaway.id, e);
+    }
+}
+
+/// Ends giveaways as they come due, looking `interval` ahead.
+pub fn spawn(
+    http: Arc<Http>,
+    giveaways: Arc<Giveaways>,
+    locales: Arc<Locales>,
+    shard_config: Arc<ShardConfig>,
+    interval: Duration,
+    shutdown: &Shutdown,
+) {
+    let wake = giveaways.wake.clone();
+
+    scheduler::spawn(
+        (http, giveaways, locales, shard_config),
+        wake,
+        interval,
+        shutdown,
+        |(http, giveaways, locales, shard_config), lookahead| async move {
+            end_due(
+                &http,
+                giveaways.db.as_ref(),
+                &locales,
+                &shard_config,
+                lookahead,
+            )
+            .await
+        },
+    );
+}
@@ -0,0 +1,163 @@
+use crate::structs::HealthConfig;
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use poise::serenity_prelude::{ConnectionStage, ShardId, ShardManager};
+use serde::Serialize;
+use sqlx::{Pool, Postgres};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{error, info};
+
+/// Tracks how long each shard has been away from the gateway.
+pub struct GatewayStatus {
+    disconnected_since: Mutex<HashMap<ShardId, Instant>>,
+}
+
+impl GatewayStatus {
+    /// Starts with every shard disconnected, so one that never connects fails
+    /// the liveness check instead of passing it until its first stage update.
+    pub fn new(shard_ids: impl IntoIterator<Item = u32>) -> Self {
+        let now = Instant::now();
+
+        Self {
+            disconnected_since: Mutex::new(
+                shard_ids.into_iter().map(|id| (ShardId(id), now)).collect(),
+            ),
+        }
+    }
+
+    pub fn update(&self, shard_id: ShardId, stage: ConnectionStage) {
+        let mut disconnected_since = self.disconnected_since.lock().unwrap();
+
+        if stage == ConnectionStage::Connected {
+            disconnected_since.remove(&shard_id);
+        } else {
+            disconnected_since
+                .entry(shard_id)
+                .or_insert_with(Instant::now);
+        }
+    }
+
+    /// The longest time any single shard has currently been disconnected for.
+    pub fn longest_disconnect(&self) -> Option<Duration> {
+        self.disconnected_since
+            .lock()
+            .unwrap()
+            .values()
+            .map(Instant::elapsed)
+            .max()
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    shard_manager: Arc<ShardManager>,
+    db: Pool<Postgres>,
+    gateway: Arc<GatewayStatus>,
+    max_disconnect: Duration,
+}
+
+#[derive(Serialize)]
+struct Liveness {
+    alive: bool,
+    disconnected_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Readiness {
+    ready: bool,
+    database: bool,
+    shards: HashMap<u32, String>,
+}
+
+pub fn spawn(
+    config: &HealthConfig,
+    shard_manager: Arc<ShardManager>,
+    db: Pool<Postgres>,
+    gateway: Arc<GatewayStatus>,
+) {
+    let state = HealthState {
+        shard_manager,
+        db,
+        gateway,
+        max_disconnect: config.max_disconnect,
+    };
+
+    let app = Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+        .with_state(state);
+
+    let port = config.port;
+
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(listener) => listener,
+            Err(e) => return error!("Could not bind health server to port {}: {}", port, e),
+        };
+
+        info!("Health server listening on port {}", port);
+
+        if let Err(e) = axum::serve(listener, app).await {
+            error!("Health server error: {}", e);
+        }
+    });
+}
+
+async fn liveness(State(state): State<HealthState>) -> (StatusCode, Json<Liveness>) {
+    let disconnected = state.gateway.longest_disconnect();
+    let alive = disconnected.is_none_or(|d| d <= state.max_disconnect);
+
+    let status = if alive {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(Liveness {
+            alive,
+            disconnected_secs: disconnected.map(|d| d.as_secs()),
+        }),
+    )
+}
+
+async fn readiness(State(state): State<HealthState>) -> (StatusCode, Json<Readiness>) {
+    let shards: HashMap<u32, ConnectionStage> = state
+        .shard_manager
+        .runners
+        .lock()
+        .await
+        .iter()
+        .map(|(id, runner)| (id.0, runner.stage))
+        .collect();
+
+    let database = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
+
+    let ready = database
+        && !shards.is_empty()
+        && shards
+            .values()
+            .all(|stage| *stage == ConnectionStage::Connected);
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(Readiness {
+            ready,
+            database,
+            shards: shards
+                .into_iter()
+                .map(|(id, stage)| (id, stage.to_string()))
+                .collect(),
+        }),
+    )
+}
@@ -0,0 +1,244 @@
+use crate::{
+    database::{get_database, Database},
+    structs::{Command, Context},
+    utils::get_locales,
+};
+use fluent::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use poise::serenity_prelude::{AutocompleteChoice, GuildId, UserId};
+use std::{collections::HashMap, sync::Arc};
+use tracing::warn;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Message catalogs shipped with the binary, keyed by Discord locale.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en-US", include_str!("../locales/en-US.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+pub struct Locales {
+    bundles: HashMap<&'static str, FluentBundle<FluentResource>>,
+}
+
+impl Locales {
+    pub fn bundled() -> Self {
+        let bundles = CATALOGS
+            .into_iter()
+            .map(|(locale, source)| {
+                let resource = FluentResource::try_new(source.to_string())
+                    .unwrap_or_else(|(_, e)| panic!("Catalog {locale} is invalid: {e:?}"));
+
+                let mut bundle = FluentBundle::new_concurrent(vec![locale
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Catalog locale {locale} is invalid: {e}"))]);
+                // Isolation marks around arguments would break mentions and markdown
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|e| panic!("Catalog {locale} is invalid: {e:?}"));
+
+                (locale, bundle)
+            })
+            .collect();
+
+        Self { bundles }
+    }
+
+    pub fn is_supported(&self, locale: &str) -> bool {
+        self.bundles.contains_key(locale)
+    }
+
+    /// Every supported locale along with its name in that language.
+    pub fn available(&self) -> Vec<(&'static str, String)> {
+        let mut available: Vec<_> = self
+            .bundles
+            .keys()
+            .map(|locale| (*locale, self.get(locale, "language-name", None)))
+            .collect();
+        available.sort_unstable();
+        available
+    }
+
+    fn format(
+        &self,
+        locale: &str,
+        id: &str,
+        attribute: Option<&str>,
+        args: Option<&FluentArgs>,
+    ) -> Option<String> {
+        let bundle = self.bundles.get(locale)?;
+        let message = bundle.get_message(id)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+
+        let mut errors = vec![];
+        let formatted = bundle.format_pattern(pattern, args, &mut errors);
+
+        if !errors.is_empty() {
+            warn!("Errors formatting {} in {}: {:?}", id, locale, errors);
+        }
+
+        Some(formatted.into_owned())
+    }
+
+    /// Formats a message, falling back to the default locale when the catalog
+    /// has no translation for it.
+    pub fn get(&self, locale: &str, id: &str, args: Option<&FluentArgs>) -> String {
+        self.format(locale, id, None, args)
+            .or_else(|| self.format(DEFAULT_LOCALE, id, None, args))
+            .unwrap_or_else(|| {
+                warn!("Missing message {} in the {} catalog", id, DEFAULT_LOCALE);
+                id.to_string()
+            })
+    }
+
+    /// Adds the catalogs' translations of command names, descriptions and
+    /// parameters so Discord shows them in each user's client language.
+    pub fn localize_commands(&self, commands: &mut [Command]) {
+        self.localize_under("cmd", commands);
+    }
+
+    /// Subcommands are keyed under their parents, for example `cmd-config-show`.
+    /// Qualified names can't be used as poise only fills them in once the
+    /// framework is built.
+    fn localize_under(&self, parent_id: &str, commands: &mut [Command]) {
+        for command in commands {
+            let id = format!("{parent_id}-{}", command.name);
+
+            for locale in self.bundles.keys().filter(|l| **l != DEFAULT_LOCALE) {
+                let Some(name) = self.format(locale, &id, None, None) else {
+                    continue;
+                };
+
+                command.name_localizations.insert(locale.to_string(), name);
+
+                if let Some(description) = self.format(locale, &id, Some("description"), None) {
+                    command
+                        .description_localizations
+                        .insert(locale.to_string(), description);
+                }
+
+                for parameter in &mut command.parameters {
+                    if let Some(name) = self.format(locale, &id, Some(&parameter.name), None) {
+                        parameter
+                            .name_localizations
+                            .insert(locale.to_string(), name);
+                    }
+
+                    let description = format!("{}-description", parameter.name);
+                    if let Some(description) = self.format(locale, &id, Some(&description), None) {
+                        parameter
+                            .description_localizations
+                            .insert(locale.to_string(), description);
+                    }
+
+                    for choice in &mut parameter.choices {
+                        let choice_id = format!("choice-{}", choice.name.to_lowercase());
+                        if let Some(name) = self.format(locale, &choice_id, None, None) {
+                            choice.localizations.insert(locale.to_string(), name);
+                        }
+                    }
+                }
+            }
+
+            self.localize_under(&id, &mut command.subcommands);
+        }
+    }
+}
+
+/// The catalog to answer in, resolved for one user or guild.
+pub struct Lang {
+    locales: Arc<Locales>,
+    locale: String,
+}
+
+impl Lang {
+    /// Uses `locale` if there is a catalog for it, otherwise the default.
+    pub fn new(locales: Arc<Locales>, locale: &str) -> Self {
+        let locale = if locales.is_supported(locale) {
+            locale
+        } else {
+            DEFAULT_LOCALE
+        };
+
+        Self {
+            locale: locale.to_string(),
+            locales,
+        }
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn tr(&self, id: &str) -> String {
+        self.locales.get(&self.locale, id, None)
+    }
+
+    pub fn tr_with(&self, id: &str, args: &FluentArgs) -> String {
+        self.locales.get(&self.locale, id, Some(args))
+    }
+
+    /// Looks up an attribute of a message, without falling back to the default locale.
+    pub fn attribute(&self, id: &str, attribute: &str) -> Option<String> {
+        self.locales.format(&self.locale, id, Some(attribute), None)
+    }
+}
+
+/// Picks the user's own language, then the guild's, then the default.
+pub async fn resolve(
+    db: &dyn Database,
+    locales: Arc<Locales>,
+    guild_id: Option<GuildId>,
+    user_id: Option<UserId>,
+) -> Lang {
+    if let Some(user_id) = user_id {
+        if let Ok(Some(locale)) = db.get_user_locale(user_id).await {
+            if locales.is_supported(&locale) {
+                return Lang::new(locales, &locale);
+            }
+        }
+    }
+
+    if let Some(guild_id) = guild_id {
+        if let Some(locale) = db.get_settings(guild_id).await.locale {
+            return Lang::new(locales, &locale);
+        }
+    }
+
+    Lang::new(locales, DEFAULT_LOCALE)
+}
+
+/// The language to answer the invoking user in.
+pub async fn get_lang(ctx: Context<'_>) -> Lang {
+    let db = get_database(ctx.serenity_context()).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+
+    resolve(db.as_ref(), locales, ctx.guild_id(), Some(ctx.author().id)).await
+}
+
+/// The server's language, for messages posted for everyone rather than
+/// the invoking user.
+pub async fn get_guild_lang(ctx: Context<'_>) -> Lang {
+    let db = get_database(ctx.serenity_context()).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+
+    resolve(db.as_ref(), locales, ctx.guild_id(), None).await
+}
+
+/// Suggests supported locales, shown by their own name.
+pub async fn autocomplete_locale(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+
+    get_locales(ctx.serenity_context())
+        .await
+        .available()
+        .into_iter()
+        .filter(|(locale, name)| {
+            locale.to_lowercase().starts_with(&partial) || name.to_lowercase().starts_with(&partial)
+        })
+        .map(|(locale, name)| AutocompleteChoice::new(format!("{name} ({locale})"), locale))
+        .collect()
+}
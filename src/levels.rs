@@ -0,0 +1,268 @@
+use crate::{
+    database::{get_database, Database},
+    shutdown::Shutdown,
+    structs::{LevelReward, MemberXp},
+    utils::get_levels,
+};
+use poise::serenity_prelude::{Context, GuildId, Http, Message, RoleId, UserId};
+use rand::Rng;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+use tracing::{error, info};
+
+/// Members earn XP for at most one message in this long.
+pub const XP_COOLDOWN: Duration = Duration::from_mins(1);
+/// XP earned for a message, picked at random.
+pub const XP_PER_MESSAGE: RangeInclusive<i64> = 15..=25;
+
+/// XP needed to get from `level` to the next one.
+pub fn xp_to_next(level: u32) -> i64 {
+    let level = i64::from(level);
+    5 * level * level + 50 * level + 100
+}
+
+/// Where a member with some total XP is between levels.
+#[derive(Debug, PartialEq)]
+pub struct Progress {
+    pub level: u32,
+    /// XP earned since reaching `level`.
+    pub xp: i64,
+    /// XP needed to reach the next level.
+    pub needed: i64,
+}
+
+pub fn progress(total: i64) -> Progress {
+    let mut level = 0;
+    let mut xp = total.max(0);
+
+    while xp >= xp_to_next(level) {
+        xp -= xp_to_next(level);
+        level += 1;
+    }
+
+    Progress {
+        level,
+        xp,
+        needed: xp_to_next(level),
+    }
+}
+
+pub fn level_for(total: i64) -> u32 {
+    progress(total).level
+}
+
+/// A member reaching a higher level, possibly skipping some.
+#[derive(Debug, PartialEq)]
+pub struct LevelUp {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub from: u32,
+    pub to: u32,
+}
+
+/// The rewards for reaching any level after `from` up to `to`.
+pub fn rewards_between(rewards: &[LevelReward], from: u32, to: u32) -> Vec<RoleId> {
+    rewards
+        .iter()
+        .filter(|r| u32::try_from(r.level).is_ok_and(|level| level > from && level <= to))
+        .map(|r| r.role_id)
+        .collect()
+}
+
+/// Tracks XP earned from messages. Earned XP is kept in memory and written in
+/// one batch every flush, so busy guilds don't cause a query per message.
+pub struct Levels {
+    db: Arc<dyn Database>,
+    last_gain: Mutex<HashMap<(GuildId, UserId), Instant>>,
+    pending: Mutex<HashMap<(GuildId, UserId), i64>>,
+}
+
+impl Levels {
+    pub fn new(db: Arc<dyn Database>) -> Self {
+        Self {
+            db,
+            last_gain: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the member already got XP within [`XP_COOLDOWN`].
+    pub fn on_cooldown(&self, guild_id: GuildId, user_id: UserId, now: Instant) -> bool {
+        self.last_gain
+            .lock()
+            .unwrap()
+            .get(&(guild_id, user_id))
+            .is_some_and(|last| now.duration_since(*last) < XP_COOLDOWN)
+    }
+
+    /// Gives the member `xp` unless they already got some within [`XP_COOLDOWN`],
+    /// returning whether they did.
+    pub fn gain(&self, guild_id: GuildId, user_id: UserId, xp: i64, now: Instant) -> bool {
+        {
+            let mut last_gain = self.last_gain.lock().unwrap();
+            let key = (guild_id, user_id);
+
+            if last_gain
+                .get(&key)
+                .is_some_and(|last| now.duration_since(*last) < XP_COOLDOWN)
+            {
+                return false;
+            }
+
+            last_gain.insert(key, now);
+        }
+
+        *self
+            .pending
+            .lock()
+            .unwrap()
+            .entry((guild_id, user_id))
+            .or_default() += xp;
+
+        true
+    }
+
+    /// XP the member earned that hasn't been written yet.
+    pub fn pending(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(&(guild_id, user_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Writes the pending XP and returns who reached a new level. XP that
+    /// couldn't be written is kept for the next flush.
+    pub async fn flush(&self, now: Instant) -> Vec<LevelUp> {
+        self.last_gain
+            .lock()
+            .unwrap()
+            .retain(|_, last| now.duration_since(*last) < XP_COOLDOWN);
+
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return Vec::new();
+        }
+
+        let gains: Vec<_> = pending
+            .iter()
+            .map(|((guild_id, user_id), xp)| MemberXp {
+                guild_id: *guild_id,
+                user_id: *user_id,
+                xp: *xp,
+            })
+            .collect();
+
+        let Some(totals) = self.db.add_xp(&gains).await else {
+            let mut unsaved = self.pending.lock().unwrap();
+            for (key, xp) in pending {
+                *unsaved.entry(key).or_default() += xp;
+            }
+            return Vec::new();
+        };
+
+        totals
+            .into_iter()
+            .filter_map(|total| {
+                let gained = pending
+                    .get(&(total.guild_id, total.user_id))
+                    .copied()
+                    .unwrap_or_default();
+                let from = level_for(total.xp - gained);
+                let to = level_for(total.xp);
+
+                (to > from).then_some(LevelUp {
+                    guild_id: total.guild_id,
+                    user_id: total.user_id,
+                    from,
+                    to,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Gives members XP for chatting, outside of channels the guild excluded.
+pub async fn on_message(ctx: &Context, msg: &Message) {
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return;
+    }
+
+    // Most messages are sent on cooldown, so don't load settings for them
+    let levels = get_levels(ctx).await;
+    let now = Instant::now();
+    if levels.on_cooldown(guild_id, msg.author.id, now) {
+        return;
+    }
+
+    let settings = get_database(ctx).await.get_settings(guild_id).await;
+    if settings.no_xp_channels.contains(&msg.channel_id) {
+        return;
+    }
+
+    let xp = rand::thread_rng().gen_range(XP_PER_MESSAGE);
+    levels.gain(guild_id, msg.author.id, xp, now);
+}
+
+/// Gives members the rewards for the levels they reached.
+async fn grant_rewards(http: &Http, db: &dyn Database, level_ups: &[LevelUp]) {
+    let mut rewards: HashMap<GuildId, Vec<LevelReward>> = HashMap::new();
+
+    for level_up in level_ups {
+        info!(
+            "User {} reached level {} in guild {}",
+            level_up.user_id, level_up.to, level_up.guild_id
+        );
+
+        if let Entry::Vacant(entry) = rewards.entry(level_up.guild_id) {
+            entry.insert(db.get_level_rewards(level_up.guild_id).await);
+        }
+
+        for role_id in rewards_between(&rewards[&level_up.guild_id], level_up.from, level_up.to) {
+            if let Err(e) = http
+                .add_member_role(
+                    level_up.guild_id,
+                    level_up.user_id,
+                    role_id,
+                    Some("Level reward"),
+                )
+                .await
+            {
+                error!(
+                    "Error giving level reward {} to {}: {}",
+                    role_id, level_up.user_id, e
+                );
+            }
+        }
+    }
+}
+
+/// Writes earned XP every `interval`, and once more when shutting down.
+pub fn spawn(http: Arc<Http>, levels: Arc<Levels>, interval: Duration, shutdown: &Shutdown) {
+    let token = shutdown.token();
+
+    shutdown.jobs().spawn(async move {
+        loop {
+            let stopping = tokio::select! {
+                () = sleep(interval) => false,
+                () = token.cancelled() => true,
+            };
+
+            let level_ups = levels.flush(Instant::now()).await;
+            grant_rewards(&http, levels.db.as_ref(), &level_ups).await;
+
+            if stopping {
+                break;
+            }
+        }
+    });
+}
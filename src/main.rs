@@ -1,109 +1,232 @@
 #![warn(clippy::pedantic)]
 
+mod cdn;
 mod commands;
+mod config;
+mod cooldowns;
 mod database;
+mod embeds;
 mod events;
+mod framework;
+mod giveaways;
+mod health;
+mod i18n;
+mod levels;
+mod modlog;
+mod permissions;
+mod polls;
+mod providers;
+mod reminders;
+mod role_panels;
+mod scheduler;
+mod shutdown;
+mod starboard;
 mod structs;
+mod tags;
+#[cfg(test)]
+mod tests;
 mod utils;
 
 use crate::{
     commands::commands,
-    database::connect,
+    cooldowns::Cooldowns,
+    database::{connect, PostgresDatabase, SettingsCache},
     events::Handler,
-    structs::{Data, DbConfig, PostgresContainer, ShardManagerContainer},
+    framework::BotFramework,
+    giveaways::Giveaways,
+    health::GatewayStatus,
+    i18n::Locales,
+    levels::Levels,
+    permissions::PermissionOverrides,
+    polls::Polls,
+    providers::ImageProviders,
+    reminders::Reminders,
+    shutdown::{wait_for_signal, Shutdown},
+    starboard::Starboard,
+    structs::{
+        CooldownsContainer, Data, DatabaseContainer, GatewayStatusContainer, GiveawaysContainer,
+        ImageProvidersContainer, LevelsContainer, LocalesContainer, ModlogContainer,
+        PermissionOverridesContainer, PollsContainer, PostgresContainer, RemindersContainer,
+        SettingsCacheContainer, ShardConfig, ShardConfigContainer, ShardManagerContainer,
+        ShutdownContainer, StarboardContainer,
+    },
 };
 use dotenvy::dotenv;
-use poise::serenity_prelude::{ClientBuilder, GatewayIntents};
-use std::env::var;
+use poise::serenity_prelude::{Client, ClientBuilder, GatewayIntents};
+use std::{env::var, sync::Arc};
 use structs::ReqwestClientContainer;
 use tracing::{error, info};
 
+fn intents() -> GatewayIntents {
+    // Message content is needed for text commands, reactions for the starboard
+    GatewayIntents::GUILD_MEMBERS
+        | GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT
+}
+
+/// Starts the background jobs and stores their handles for commands to use.
+async fn start_jobs(
+    client: &Client,
+    db: &Arc<SettingsCache>,
+    locales: &Arc<Locales>,
+    shard_config: &Arc<ShardConfig>,
+    shutdown: &Shutdown,
+) {
+    let modlog_queue = modlog::spawn(client.http.clone(), shutdown);
+
+    let cooldowns = Arc::new(Cooldowns::new(db.clone(), config::cooldown_persist_after()));
+    cooldowns::spawn(cooldowns.clone(), config::cooldown_cleanup(), shutdown);
+
+    let reminders = Arc::new(Reminders::new(db.clone()));
+    reminders::spawn(
+        client.http.clone(),
+        reminders.clone(),
+        locales.clone(),
+        shard_config.clone(),
+        config::reminder_poll(),
+        shutdown,
+    );
+
+    let polls = Arc::new(Polls::new(db.clone()));
+    polls::spawn(
+        client.http.clone(),
+        polls.clone(),
+        locales.clone(),
+        shard_config.clone(),
+        config::poll_check(),
+        shutdown,
+    );
+
+    let giveaways = Arc::new(Giveaways::new(db.clone()));
+    giveaways::spawn(
+        client.http.clone(),
+        giveaways.clone(),
+        locales.clone(),
+        shard_config.clone(),
+        config::giveaway_check(),
+        shutdown,
+    );
+
+    let levels = Arc::new(Levels::new(db.clone()));
+    levels::spawn(
+        client.http.clone(),
+        levels.clone(),
+        config::xp_flush(),
+        shutdown,
+    );
+
+    let mut data = client.data.write().await;
+    data.insert::<ModlogContainer>(modlog_queue);
+    data.insert::<CooldownsContainer>(cooldowns);
+    data.insert::<RemindersContainer>(reminders);
+    data.insert::<PollsContainer>(polls);
+    data.insert::<GiveawaysContainer>(giveaways);
+    data.insert::<LevelsContainer>(levels);
+}
+
 #[tokio::main]
 async fn main() {
     dotenv().ok();
 
-    tracing_subscriber::fmt::init();
-
-    let host = var("DATABASE_HOST").expect("missing DATABASE_HOST env");
-    let port = var("DATABASE_PORT")
-        .expect("missing DATABASE_PORT env")
-        .parse::<u16>()
-        .expect("DATABASE_PORT env is not of type u16");
-    let username = var("DATABASE_USER").expect("missing DATABASE_USER env");
-    let password = var("DATABASE_PASS").expect("missing DATABASE_PASS env");
-
-    let db_config = DbConfig {
-        host,
-        port,
-        username,
-        password,
-    };
+    match var("LOG_FORMAT").as_deref() {
+        Ok("json") => tracing_subscriber::fmt().json().init(),
+        _ => tracing_subscriber::fmt::init(),
+    }
 
-    let db = connect(&db_config).await;
+    let db = connect(&config::db_config()).await;
 
-    let options = poise::FrameworkOptions {
-        commands: commands(),
-        pre_command: |ctx| {
-            Box::pin(async move {
-                info!(
-                    "Command {} was ran by user {}",
-                    ctx.command().name,
-                    ctx.author().tag()
-                );
-            })
-        },
-        post_command: |ctx| {
-            Box::pin(async move {
-                info!(
-                    "Command {} has successfully finished running by user {}",
-                    ctx.command().name,
-                    ctx.author().tag()
-                );
-            })
-        },
-        ..Default::default()
-    };
+    let image_providers = config::image_providers();
+    let locales = Arc::new(Locales::bundled());
+
+    let options = framework::options(commands(&image_providers, &locales));
 
     let token = std::env::var("TOKEN").expect("missing TOKEN");
 
-    let intents = GatewayIntents::GUILD_MEMBERS | GatewayIntents::GUILDS;
+    let default_prefix = config::default_prefix();
 
     let framework = poise::Framework::builder()
         .options(options)
         .setup(move |ctx, _ready, framework| {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                Ok(Data {})
+                Ok(Data { default_prefix })
             })
         })
         .build();
 
-    let mut client = ClientBuilder::new(token, intents)
-        .framework(framework)
+    let shutdown = Arc::new(Shutdown::new());
+
+    let mut client = ClientBuilder::new(token, intents())
+        .framework(BotFramework {
+            inner: framework,
+            shutdown: shutdown.clone(),
+        })
         .event_handler(Handler)
         .await
         .expect("Error creating client");
 
     let reqwest_client = reqwest::Client::new();
+    let shard_config = Arc::new(config::shard_config());
+    let settings_cache = Arc::new(SettingsCache::new(Arc::new(PostgresDatabase::new(
+        db.clone(),
+    ))));
+    let gateway_status = Arc::new(GatewayStatus::new(shard_config.shard_ids()));
+    start_jobs(&client, &settings_cache, &locales, &shard_config, &shutdown).await;
 
     {
         let mut data = client.data.write().await;
         data.insert::<ShardManagerContainer>(client.shard_manager.clone());
         data.insert::<PostgresContainer>(db.clone());
+        data.insert::<DatabaseContainer>(settings_cache.clone());
+        data.insert::<PermissionOverridesContainer>(Arc::new(PermissionOverrides::new(
+            settings_cache.clone(),
+        )));
+        data.insert::<SettingsCacheContainer>(settings_cache);
+        data.insert::<LocalesContainer>(locales);
         data.insert::<ReqwestClientContainer>(reqwest_client.clone());
+        data.insert::<ImageProvidersContainer>(Arc::new(ImageProviders::new(&image_providers)));
+        data.insert::<GatewayStatusContainer>(gateway_status.clone());
+        data.insert::<ShutdownContainer>(shutdown.clone());
+        data.insert::<ShardConfigContainer>(shard_config.clone());
+        data.insert::<StarboardContainer>(Arc::new(Starboard::default()));
+    }
+
+    if let Some(health_config) = config::health_config() {
+        health::spawn(
+            &health_config,
+            client.shard_manager.clone(),
+            db.clone(),
+            gateway_status,
+        );
     }
 
     let shard_manager = client.shard_manager.clone();
 
-    tokio::spawn(async move {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("Could not register ctrl+c handler");
+    let shutdown_task = tokio::spawn(async move {
+        wait_for_signal().await;
+        shutdown.drain(config::shutdown_grace()).await;
         shard_manager.shutdown_all().await;
         db.close().await;
+        info!("Shutdown complete");
     });
 
-    if let Err(why) = client.start().await {
+    let started = match *shard_config {
+        ShardConfig::Auto => client.start_autosharded().await,
+        // serenity treats the end of this range as inclusive
+        ShardConfig::Range { first, last, total } => {
+            client.start_shard_range(first..last, total).await
+        }
+    };
+
+    if let Err(why) = started {
         error!("Client error: {:?}", why);
+        return;
+    }
+
+    if let Err(e) = shutdown_task.await {
+        error!("Shutdown task failed: {}", e);
     }
 }
@@ -0,0 +1,58 @@
+use crate::shutdown::Shutdown;
+use poise::serenity_prelude::{ChannelId, CreateEmbed, CreateMessage, Http};
+use std::sync::Arc;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tracing::error;
+
+pub struct ModlogPost {
+    pub channel_id: ChannelId,
+    pub embed: CreateEmbed,
+}
+
+/// Queue of posts for guild modlog channels, delivered by a background worker
+/// so moderation commands never wait on them. Pending posts are flushed on shutdown.
+#[derive(Clone)]
+pub struct ModlogQueue(UnboundedSender<ModlogPost>);
+
+impl ModlogQueue {
+    pub fn push(&self, post: ModlogPost) {
+        if self.0.send(post).is_err() {
+            error!("Modlog queue is closed, dropping post");
+        }
+    }
+}
+
+pub fn spawn(http: Arc<Http>, shutdown: &Shutdown) -> ModlogQueue {
+    let (tx, mut rx) = unbounded_channel::<ModlogPost>();
+    let token = shutdown.token();
+
+    shutdown.jobs().spawn(async move {
+        loop {
+            tokio::select! {
+                post = rx.recv() => match post {
+                    Some(post) => deliver(&http, post).await,
+                    None => return,
+                },
+                () = token.cancelled() => break,
+            }
+        }
+
+        rx.close();
+
+        while let Some(post) = rx.recv().await {
+            deliver(&http, post).await;
+        }
+    });
+
+    ModlogQueue(tx)
+}
+
+async fn deliver(http: &Http, post: ModlogPost) {
+    if let Err(e) = post
+        .channel_id
+        .send_message(http, CreateMessage::new().embed(post.embed))
+        .await
+    {
+        error!("Error posting to modlog channel {}: {}", post.channel_id, e);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{
+    database::Database,
+    embeds::{embed, EmbedKind},
+    i18n::get_lang,
+    structs::{Command, Context, Guild, PermissionOverride, PermissionTarget},
+    utils::get_permission_overrides,
+};
+use fluent::fluent_args;
+use poise::{
+    serenity_prelude::{
+        ChannelId, ChannelType, Context as SerenityContext, CreateEmbedAuthor, GuildChannel,
+        GuildId, Member, Message, Permissions, RoleId, UserId,
+    },
+    CreateReply,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use tracing::error;
+
+/// The permissions a command needs when no override applies. Taken out of
+/// `required_permissions` so poise doesn't refuse the command before the
+/// overrides are looked at.
+struct DefaultPermissions(Permissions);
+
+/// Moves each command's `required_permissions` into its defaults, for the
+/// command check to enforce instead.
+pub fn take_required_permissions(commands: &mut [Command]) {
+    for command in commands {
+        command.custom_data = Box::new(DefaultPermissions(command.required_permissions));
+        command.required_permissions = Permissions::empty();
+
+        take_required_permissions(&mut command.subcommands);
+    }
+}
+
+pub fn default_permissions(command: &Command) -> Permissions {
+    command
+        .custom_data
+        .downcast_ref::<DefaultPermissions>()
+        .map_or(Permissions::empty(), |defaults| defaults.0)
+}
+
+/// Guild permission overrides, cached per guild and written through on change.
+pub struct PermissionOverrides {
+    db: Arc<dyn Database>,
+    rules: RwLock<HashMap<GuildId, Vec<PermissionOverride>>>,
+}
+
+impl PermissionOverrides {
+    pub fn new(db: Arc<dyn Database>) -> Self {
+        Self {
+            db,
+            rules: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn rules(&self, guild_id: GuildId) -> Vec<PermissionOverride> {
+        if let Some(rules) = self.rules.read().unwrap().get(&guild_id) {
+            return rules.clone();
+        }
+
+        let rules = self.db.get_permission_overrides(guild_id).await;
+        self.rules.write().unwrap().insert(guild_id, rules.clone());
+
+        rules
+    }
+
+    pub async fn set_rule(&self, rule: &PermissionOverride) {
+        self.db.insert_guild(&Guild::new(rule.guild_id)).await;
+        self.db.set_permission_override(rule).await;
+        self.rules.write().unwrap().remove(&rule.guild_id);
+    }
+
+    pub async fn remove_rule(
+        &self,
+        guild_id: GuildId,
+        command: &str,
+        target: PermissionTarget,
+        target_id: u64,
+    ) {
+        self.db
+            .remove_permission_override(guild_id, command, target, target_id)
+            .await;
+        self.rules.write().unwrap().remove(&guild_id);
+    }
+}
+
+/// Who is using a command and where, as far as overrides are concerned.
+pub struct Invoker<'a> {
+    pub user_id: UserId,
+    pub roles: &'a [RoleId],
+    pub channel_id: ChannelId,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny,
+    /// No override applies, so the command's default permissions decide.
+    Default,
+}
+
+/// Finds the override that applies to an invocation. `commands` is the invoked
+/// command's qualified name followed by its parents', and the most specific
+/// command with any matching rule decides. For a single command a channel deny
+/// wins, then a user rule, then role rules where any allow beats a deny, then
+/// a channel allow.
+pub fn evaluate(
+    rules: &[PermissionOverride],
+    commands: &[&str],
+    invoker: &Invoker<'_>,
+) -> Decision {
+    for command in commands {
+        let matching = |target: PermissionTarget, id: u64| {
+            rules
+                .iter()
+                .find(|r| r.command == *command && r.target == target && r.target_id == id)
+                .map(|r| r.allow)
+        };
+
+        let channel = matching(PermissionTarget::Channel, invoker.channel_id.get());
+
+        if channel == Some(false) {
+            return Decision::Deny;
+        }
+
+        match matching(PermissionTarget::User, invoker.user_id.get()) {
+            Some(true) => return Decision::Allow,
+            Some(false) => return Decision::Deny,
+            None => {}
+        }
+
+        let role_rules: Vec<bool> = invoker
+            .roles
+            .iter()
+            .filter_map(|role| matching(PermissionTarget::Role, role.get()))
+            .collect();
+
+        if role_rules.contains(&true) {
+            return Decision::Allow;
+        }
+
+        if role_rules.contains(&false) {
+            return Decision::Deny;
+        }
+
+        if channel == Some(true) {
+            return Decision::Allow;
+        }
+    }
+
+    Decision::Default
+}
+
+/// The channel whose rules and permission overwrites apply. Threads have none
+/// of their own, so they use their parent's, or running a command in a thread
+/// would get around a deny on its channel.
+pub fn rules_channel(channel: &GuildChannel) -> ChannelId {
+    match channel.kind {
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => {
+            channel.parent_id.unwrap_or(channel.id)
+        }
+        _ => channel.id,
+    }
+}
+
+/// Whether a member may run a command, given the guild's overrides.
+pub enum Verdict {
+    Allowed,
+    Denied,
+    /// No override applies and the member lacks these default permissions.
+    Missing(Permissions),
+}
+
+/// Everything about the invoking member that decides which commands they may
+/// run, looked up once so several commands can be checked.
+pub struct MemberAccess {
+    user_id: UserId,
+    channel_id: ChannelId,
+    roles: Vec<RoleId>,
+    permissions: Permissions,
+    rules: Vec<PermissionOverride>,
+}
+
+impl MemberAccess {
+    /// Reads the member's roles and channel permissions from the cache.
+    /// `None` outside guilds or when the member isn't cached.
+    pub async fn resolve(ctx: Context<'_>) -> Option<Self> {
+        let guild_id = ctx.guild_id()?;
+        let member = ctx.author_member().await?.into_owned();
+        let channel = ctx.guild_channel().await?;
+
+        Self::lookup(ctx.serenity_context(), guild_id, member, channel).await
+    }
+
+    /// Like [`MemberAccess::resolve`], for the author of a message that didn't
+    /// invoke a command.
+    pub async fn resolve_message(ctx: &SerenityContext, msg: &Message) -> Option<Self> {
+        let guild_id = msg.guild_id?;
+        let member = msg.member(ctx).await.ok()?;
+        let channel = msg.channel(ctx).await.ok()?.guild()?;
+
+        Self::lookup(ctx, guild_id, member, channel).await
+    }
+
+    async fn lookup(
+        ctx: &SerenityContext,
+        guild_id: GuildId,
+        member: Member,
+        mut channel: GuildChannel,
+    ) -> Option<Self> {
+        let rules_channel_id = rules_channel(&channel);
+        if rules_channel_id != channel.id {
+            channel = rules_channel_id.to_channel(ctx).await.ok()?.guild()?;
+        }
+
+        let permissions = ctx
+            .cache
+            .guild(guild_id)?
+            .user_permissions_in(&channel, &member);
+
+        let overrides = get_permission_overrides(ctx).await;
+
+        Some(Self {
+            user_id: member.user.id,
+            channel_id: channel.id,
+            roles: member.roles,
+            permissions,
+            rules: overrides.rules(guild_id).await,
+        })
+    }
+
+    /// `parents` are listed root first, as poise gives them. Administrators are
+    /// never restricted.
+    pub fn verdict(&self, command: &Command, parents: &[&Command]) -> Verdict {
+        if self.permissions.administrator() {
+            return Verdict::Allowed;
+        }
+
+        let required = parents
+            .iter()
+            .fold(default_permissions(command), |required, parent| {
+                required | default_permissions(parent)
+            });
+
+        // The most specific command should decide
+        let mut commands = vec![command.qualified_name.as_str()];
+        commands.extend(parents.iter().rev().map(|c| c.qualified_name.as_str()));
+
+        let invoker = Invoker {
+            user_id: self.user_id,
+            roles: &self.roles,
+            channel_id: self.channel_id,
+        };
+
+        match evaluate(&self.rules, &commands, &invoker) {
+            Decision::Allow => Verdict::Allowed,
+            Decision::Deny => Verdict::Denied,
+            Decision::Default if self.permissions.contains(required) => Verdict::Allowed,
+            Decision::Default => Verdict::Missing(required - self.permissions),
+        }
+    }
+}
+
+/// Command check enforcing the guild's overrides, falling back to the
+/// command's default permissions.
+pub async fn check(ctx: Context<'_>) -> bool {
+    if ctx.guild_id().is_none() {
+        return true;
+    }
+
+    let Some(access) = MemberAccess::resolve(ctx).await else {
+        error!("Could not resolve permissions for {}", ctx.author().id);
+        deny(ctx, None).await;
+        return false;
+    };
+
+    match access.verdict(ctx.command(), ctx.parent_commands()) {
+        Verdict::Allowed => true,
+        Verdict::Denied => {
+            deny(ctx, None).await;
+            false
+        }
+        Verdict::Missing(missing) => {
+            deny(ctx, Some(missing)).await;
+            false
+        }
+    }
+}
+
+async fn deny(ctx: Context<'_>, missing: Option<Permissions>) {
+    let lang = get_lang(ctx).await;
+    let command = ctx.command().qualified_name.as_str();
+
+    let description = match missing {
+        Some(missing) => lang.tr_with(
+            "permission-missing",
+            &fluent_args!["command" => command, "permissions" => missing.to_string()],
+        ),
+        None => lang.tr_with("permission-denied", &fluent_args!["command" => command]),
+    };
+
+    if let Err(e) = ctx
+        .send(
+            CreateReply::default()
+                .embed(
+                    embed(ctx, EmbedKind::Error)
+                        .await
+                        .author(
+                            CreateEmbedAuthor::new(lang.tr("error-title"))
+                                .icon_url(ctx.author().face()),
+                        )
+                        .description(description),
+                )
+                .ephemeral(true),
+        )
+        .await
+    {
+        error!("Error: {}", e);
+    }
+}
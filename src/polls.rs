@@ -0,0 +1,436 @@
+use crate::{
+    database::{get_database, Database},
+    embeds::{bar, join_limited, EmbedKind, EMBED_LIMIT, FIELD_LIMIT},
+    i18n::{resolve, Lang, Locales},
+    reminders::relative_time,
+    scheduler,
+    shutdown::Shutdown,
+    structs::{Poll, PollVote, ShardConfig, Theme},
+    utils::{defer_ephemeral, get_locales, is_gone, respond},
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteraction, Context, CreateActionRow, CreateButton, CreateEmbed,
+    CreateEmbedAuthor, EditMessage, Http,
+};
+use sqlx::types::time::OffsetDateTime;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+/// Polls can have at most this many options, two rows of buttons.
+pub const MAX_OPTIONS: usize = 10;
+/// Options are limited to the length of a button label.
+pub const MAX_OPTION_LENGTH: usize = 80;
+/// What separates options when starting a poll.
+pub const OPTION_SEPARATOR: char = '|';
+const BUTTONS_PER_ROW: usize = 5;
+const BAR_WIDTH: usize = 12;
+const CUSTOM_ID_PREFIX: &str = "poll";
+
+/// Custom ID of an option's button. The poll is the message the button is on.
+pub fn button_id(option: usize) -> String {
+    format!("{CUSTOM_ID_PREFIX}:{option}")
+}
+
+/// Reads the option from a button's custom ID.
+pub fn parse_custom_id(custom_id: &str) -> Option<i16> {
+    let (prefix, option) = custom_id.split_once(':')?;
+    if prefix != CUSTOM_ID_PREFIX {
+        return None;
+    }
+
+    option.parse().ok().filter(|option| *option >= 0)
+}
+
+/// Reads options separated by `|`, such as `Pizza | Pasta | Salad`. Returns
+/// `None` unless there are 2 to [`MAX_OPTIONS`] different options of at most
+/// [`MAX_OPTION_LENGTH`] characters.
+pub fn parse_options(input: &str) -> Option<Vec<String>> {
+    let mut options: Vec<String> = Vec::new();
+
+    for option in input.split(OPTION_SEPARATOR).map(str::trim) {
+        if option.is_empty() {
+            continue;
+        }
+
+        if option.chars().count() > MAX_OPTION_LENGTH
+            || options.iter().any(|o| o.eq_ignore_ascii_case(option))
+        {
+            return None;
+        }
+
+        options.push(option.to_string());
+    }
+
+    (2..=MAX_OPTIONS)
+        .contains(&options.len())
+        .then_some(options)
+}
+
+/// The member's votes after clicking `clicked`. Clicking an option they voted
+/// for takes that vote back, otherwise it's added, or replaces their vote on
+/// single choice polls.
+pub fn toggle(current: &[i16], clicked: i16, multi_choice: bool) -> Vec<i16> {
+    if current.contains(&clicked) {
+        return current.iter().copied().filter(|o| *o != clicked).collect();
+    }
+
+    if !multi_choice {
+        return vec![clicked];
+    }
+
+    let mut votes = current.to_vec();
+    votes.push(clicked);
+    votes.sort_unstable();
+    votes
+}
+
+/// How many votes each option got, in the poll's order.
+pub fn tally(poll: &Poll, votes: &[PollVote]) -> Vec<i64> {
+    let mut counts = vec![0; poll.options.len()];
+
+    for vote in votes {
+        if let Some(count) = usize::try_from(vote.option)
+            .ok()
+            .and_then(|option| counts.get_mut(option))
+        {
+            *count += 1;
+        }
+    }
+
+    counts
+}
+
+/// How many members voted, which is less than the votes on multiple choice polls.
+pub fn count_voters(votes: &[PollVote]) -> i64 {
+    let members: HashSet<_> = votes.iter().map(|vote| vote.user_id).collect();
+    i64::try_from(members.len()).unwrap_or(i64::MAX)
+}
+
+fn notes(lang: &Lang, poll: &Poll, voters: i64, closed: bool) -> String {
+    let time = relative_time(poll.closes_at);
+    let mut notes = vec![
+        if closed {
+            lang.tr_with("poll-closed", &fluent_args!["time" => time])
+        } else {
+            lang.tr_with("poll-closes", &fluent_args!["time" => time])
+        },
+        lang.tr_with("poll-voters", &fluent_args!["count" => voters]),
+    ];
+
+    if poll.multi_choice {
+        notes.push(lang.tr("poll-multi-choice"));
+    }
+    if poll.anonymous {
+        notes.push(lang.tr("poll-anonymous"));
+    }
+
+    notes.join("\n")
+}
+
+/// The poll while it's open, which only shows how many members voted so far.
+pub fn poll_embed(theme: &Theme, lang: &Lang, poll: &Poll, votes: &[PollVote]) -> CreateEmbed {
+    let options = poll
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| format!("**{}.** {option}", i + 1))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    theme
+        .embed(EmbedKind::Primary)
+        .author(CreateEmbedAuthor::new(lang.tr("poll-title")))
+        .title(&poll.question)
+        .description(format!(
+            "{options}\n\n{}",
+            notes(lang, poll, count_voters(votes), false)
+        ))
+}
+
+/// The closed poll, with a bar for each option and, unless the poll is
+/// anonymous, who voted for it. Voters are listed in whatever room the rest of
+/// the embed leaves, shared evenly between the options.
+pub fn results_embed(theme: &Theme, lang: &Lang, poll: &Poll, votes: &[PollVote]) -> CreateEmbed {
+    let counts = tally(poll, votes);
+    let turnout = count_voters(votes);
+    let most = counts.iter().copied().max().unwrap_or_default();
+
+    let title = lang.tr("poll-results-title");
+    let description = notes(lang, poll, turnout, true);
+
+    let fields: Vec<_> = poll
+        .options
+        .iter()
+        .zip(&counts)
+        .map(|(option, count)| {
+            let name = if most > 0 && *count == most {
+                format!("🏆 {option}")
+            } else {
+                option.clone()
+            };
+
+            let percent = if turnout > 0 {
+                count * 100 / turnout
+            } else {
+                0
+            };
+            let value = lang.tr_with(
+                "poll-result",
+                &fluent_args![
+                    "bar" => bar(*count, turnout, BAR_WIDTH),
+                    "count" => *count,
+                    "percent" => percent,
+                ],
+            );
+
+            (name, value)
+        })
+        .collect();
+
+    let used: usize = [&title, &poll.question, &description]
+        .into_iter()
+        .chain(theme.footer_text.as_ref())
+        .chain(fields.iter().flat_map(|(name, value)| [name, value]))
+        .map(String::len)
+        .sum();
+    let listed = if poll.anonymous {
+        0
+    } else {
+        counts.iter().filter(|count| **count > 0).count()
+    };
+    let share = EMBED_LIMIT.saturating_sub(used) / listed.max(1);
+
+    let mut embed = theme
+        .embed(EmbedKind::Success)
+        .author(CreateEmbedAuthor::new(title))
+        .title(&poll.question)
+        .description(description);
+
+    for (i, ((name, mut value), count)) in fields.into_iter().zip(&counts).enumerate() {
+        // Room for the mentions after the line break that separates them
+        let limit = share
+            .min(FIELD_LIMIT.saturating_sub(value.len()))
+            .saturating_sub(1);
+
+        if !poll.anonymous && *count > 0 && limit > 1 {
+            let mentions = votes
+                .iter()
+                .filter(|vote| usize::try_from(vote.option) == Ok(i))
+                .map(|vote| format!("<@{}>", vote.user_id));
+            value = format!("{value}\n{}", join_limited(mentions, ", ", limit));
+        }
+
+        embed = embed.field(name, value, false);
+    }
+
+    embed
+}
+
+pub fn poll_components(poll: &Poll) -> Vec<CreateActionRow> {
+    let buttons: Vec<_> = poll
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            CreateButton::new(button_id(i))
+                .label(option)
+                .style(ButtonStyle::Secondary)
+        })
+        .collect();
+
+    buttons
+        .chunks(BUTTONS_PER_ROW)
+        .map(|row| CreateActionRow::Buttons(row.to_vec()))
+        .collect()
+}
+
+/// Stores polls and wakes the scheduler when one is added, in case it closes
+/// before the scheduler would next look.
+pub struct Polls {
+    db: Arc<dyn Database>,
+    wake: Arc<Notify>,
+}
+
+impl Polls {
+    pub fn new(db: Arc<dyn Database>) -> Self {
+        Self {
+            db,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn add(&self, poll: &Poll) -> bool {
+        let created = self.db.create_poll(poll).await;
+        self.wake.notify_one();
+        created
+    }
+}
+
+/// Closes every owned poll that is due, including ones that came due while the
+/// bot was offline, and returns when the next one within `lookahead` is due.
+pub async fn close_due(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    shard_config: &ShardConfig,
+    lookahead: Duration,
+) -> Option<OffsetDateTime> {
+    let now = OffsetDateTime::now_utc();
+
+    for poll in db
+        .get_polls_closing_before(now + lookahead)
+        .await
+        .into_iter()
+        .filter(|p| shard_config.owns_guild(p.guild_id))
+    {
+        if poll.closes_at > now {
+            return Some(poll.closes_at);
+        }
+
+        close(http, db, locales, &poll).await;
+    }
+
+    None
+}
+
+/// Edits the results into the poll's message and removes its buttons. The poll
+/// is deleted once that worked or the message is gone, and is otherwise kept
+/// for the next pass.
+async fn close(http: &Http, db: &dyn Database, locales: &Arc<Locales>, poll: &Poll) {
+    let lang = resolve(db, locales.clone(), Some(poll.guild_id), None).await;
+    let theme = db.get_settings(poll.guild_id).await.theme;
+    let votes = db.get_poll_votes(poll.message_id).await;
+
+    match poll
+        .channel_id
+        .edit_message(
+            http,
+            poll.message_id,
+            EditMessage::new()
+                .embed(results_embed(&theme, &lang, poll, &votes))
+                .components(Vec::new()),
+        )
+        .await
+    {
+        Ok(_) => info!("Closed poll {}", poll.message_id),
+        Err(e) if is_gone(&e) => {
+            error!("Poll {} is gone, dropping it: {}", poll.message_id, e);
+        }
+        Err(e) => {
+            error!("Error closing poll {}, will retry: {}", poll.message_id, e);
+            return;
+        }
+    }
+
+    db.delete_poll(poll.message_id).await;
+}
+
+/// Handles votes. The poll is looked up by the message the button is on, so
+/// votes keep working across restarts.
+pub async fn handle(ctx: &Context, interaction: &ComponentInteraction) {
+    let Some(option) = parse_custom_id(&interaction.data.custom_id) else {
+        return;
+    };
+    let Some(guild_id) = interaction.guild_id else {
+        return;
+    };
+    if !defer_ephemeral(ctx, interaction).await {
+        return;
+    }
+
+    let db = get_database(ctx).await;
+    let locales = get_locales(ctx).await;
+    let lang = resolve(
+        db.as_ref(),
+        locales.clone(),
+        Some(guild_id),
+        Some(interaction.user.id),
+    )
+    .await;
+    let theme = db.get_settings(guild_id).await.theme;
+
+    // Buttons can outlive their poll until the scheduler closes it
+    let poll = db.get_poll(interaction.message.id).await.filter(|poll| {
+        poll.closes_at > OffsetDateTime::now_utc()
+            && usize::try_from(option).is_ok_and(|option| option < poll.options.len())
+    });
+    let Some(poll) = poll else {
+        let embed = theme
+            .embed(EmbedKind::Error)
+            .author(
+                CreateEmbedAuthor::new(lang.tr("error-title")).icon_url(interaction.user.face()),
+            )
+            .description(lang.tr("poll-gone"));
+        return respond(ctx, interaction, embed).await;
+    };
+
+    let Some(chosen) = db
+        .toggle_poll_vote(poll.message_id, interaction.user.id, option)
+        .await
+    else {
+        let embed = theme
+            .embed(EmbedKind::Error)
+            .author(
+                CreateEmbedAuthor::new(lang.tr("error-title")).icon_url(interaction.user.face()),
+            )
+            .description(lang.tr("poll-vote-failed"));
+        return respond(ctx, interaction, embed).await;
+    };
+
+    let description = if chosen.is_empty() {
+        lang.tr("poll-vote-removed")
+    } else {
+        let options = chosen
+            .iter()
+            .filter_map(|o| poll.options.get(usize::try_from(*o).ok()?))
+            .map(|o| format!("**{o}**"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lang.tr_with("poll-voted", &fluent_args!["options" => options])
+    };
+
+    let embed = theme
+        .embed(EmbedKind::Success)
+        .author(CreateEmbedAuthor::new(lang.tr("poll-your-vote")).icon_url(interaction.user.face()))
+        .description(description);
+    respond(ctx, interaction, embed).await;
+
+    // The poll is shown to everyone, so it's in the server's language
+    let votes = db.get_poll_votes(poll.message_id).await;
+    let poll_lang = resolve(db.as_ref(), locales, Some(guild_id), None).await;
+    if let Err(e) = poll
+        .channel_id
+        .edit_message(
+            ctx,
+            poll.message_id,
+            EditMessage::new().embed(poll_embed(&theme, &poll_lang, &poll, &votes)),
+        )
+        .await
+    {
+        error!("Error updating poll {}: {}", poll.message_id, e);
+    }
+}
+
+/// Closes polls as they come due, looking `interval` ahead.
+pub fn spawn(
+    http: Arc<Http>,
+    polls: Arc<Polls>,
+    locales: Arc<Locales>,
+    shard_config: Arc<ShardConfig>,
+    interval: Duration,
+    shutdown: &Shutdown,
+) {
+    let wake = polls.wake.clone();
+
+    scheduler::spawn(
+        (http, polls, locales, shard_config),
+        wake,
+        interval,
+        shutdown,
+        |(http, polls, locales, shard_config), lookahead| async move {
+            close_due(&http, polls.db.as_ref(), &locales, &shard_config, lookahead).await
+        },
+    );
+}
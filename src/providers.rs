@@ -0,0 +1,297 @@
+use crate::structs::Error;
+use poise::serenity_prelude::async_trait;
+use rand::seq::IteratorRandom;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+fn default_timeout_secs() -> u64 {
+    5
+}
+
+fn default_retries() -> u32 {
+    1
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn default_open_secs() -> u64 {
+    30
+}
+
+fn default_fallback_size() -> usize {
+    50
+}
+
+/// One entry of the image provider config file. Each entry becomes its own slash command.
+#[derive(Deserialize, Clone)]
+pub struct ProviderConfig {
+    /// The slash command name, e.g. `cat`.
+    pub name: String,
+    pub description: String,
+    /// Shown as the embed author above the image.
+    pub title: String,
+    pub url: String,
+    /// JSON pointer to the image URL in the response, e.g. `/0/url`.
+    pub pointer: String,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Consecutive failed fetches before the provider's circuit opens.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before the API is tried again.
+    #[serde(default = "default_open_secs")]
+    pub open_secs: u64,
+    /// How many recently fetched image URLs are kept to fall back on.
+    #[serde(default = "default_fallback_size")]
+    pub fallback_size: usize,
+}
+
+#[async_trait]
+pub trait ImageProvider: Send + Sync {
+    fn name(&self) -> &str;
+    fn title(&self) -> &str;
+    /// Fetches the URL of a random image.
+    async fn fetch(&self, client: &Client) -> Result<String, Error>;
+}
+
+/// Fetches images from an HTTP API returning JSON, using the config's pointer
+/// to find the image URL.
+pub struct JsonImageProvider {
+    config: ProviderConfig,
+}
+
+impl JsonImageProvider {
+    pub fn new(config: ProviderConfig) -> Self {
+        Self { config }
+    }
+
+    async fn fetch_once(&self, client: &Client) -> Result<String, Error> {
+        let body = client
+            .get(&self.config.url)
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        body.pointer(&self.config.pointer)
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| {
+                format!(
+                    "{} response has no image URL at {}",
+                    self.config.name, self.config.pointer
+                )
+                .into()
+            })
+    }
+}
+
+#[async_trait]
+impl ImageProvider for JsonImageProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn title(&self) -> &str {
+        &self.config.title
+    }
+
+    async fn fetch(&self, client: &Client) -> Result<String, Error> {
+        let mut attempt = 0;
+
+        loop {
+            match self.fetch_once(client).await {
+                Ok(url) => return Ok(url),
+                Err(e) if attempt < self.config.retries => {
+                    attempt += 1;
+                    warn!(
+                        "Error fetching {} image, retrying ({}/{}): {}",
+                        self.config.name, attempt, self.config.retries, e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Stops calling a failing API for a while so commands fail fast instead of
+/// waiting on timeouts. After `open_for` one trial request is let through; if
+/// it succeeds the circuit closes again, otherwise it stays open.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_for: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_for,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a request may be made right now.
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        match state.opened_at {
+            None => true,
+            Some(opened_at) if opened_at.elapsed() >= self.open_for => {
+                // Let this request through as the trial and keep failing fast
+                // for everyone else until it completes.
+                state.opened_at = Some(Instant::now());
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::default();
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+
+        if state.opened_at.is_some() || state.consecutive_failures >= self.failure_threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Wraps a provider with a [`CircuitBreaker`] and a rolling cache of recently
+/// fetched image URLs, which is served from when the API can't be reached.
+pub struct GuardedProvider {
+    inner: Arc<dyn ImageProvider>,
+    breaker: CircuitBreaker,
+    recent: Mutex<VecDeque<String>>,
+    fallback_size: usize,
+}
+
+impl GuardedProvider {
+    pub fn new(
+        inner: Arc<dyn ImageProvider>,
+        breaker: CircuitBreaker,
+        fallback_size: usize,
+    ) -> Self {
+        Self {
+            inner,
+            breaker,
+            recent: Mutex::new(VecDeque::with_capacity(fallback_size)),
+            fallback_size,
+        }
+    }
+
+    fn remember(&self, url: &str) {
+        if self.fallback_size == 0 {
+            return;
+        }
+
+        let mut recent = self.recent.lock().unwrap();
+
+        if recent.iter().any(|u| u == url) {
+            return;
+        }
+
+        if recent.len() >= self.fallback_size {
+            recent.pop_front();
+        }
+
+        recent.push_back(url.to_string());
+    }
+
+    fn fallback(&self, error: Error) -> Result<String, Error> {
+        let recent = self.recent.lock().unwrap();
+
+        match recent.iter().choose(&mut rand::thread_rng()) {
+            Some(url) => {
+                warn!(
+                    "Serving a cached {} image because the API is unavailable: {}",
+                    self.inner.name(),
+                    error
+                );
+                Ok(url.clone())
+            }
+            None => Err(error),
+        }
+    }
+}
+
+#[async_trait]
+impl ImageProvider for GuardedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn title(&self) -> &str {
+        self.inner.title()
+    }
+
+    async fn fetch(&self, client: &Client) -> Result<String, Error> {
+        if !self.breaker.allow() {
+            return self.fallback(format!("{} circuit is open", self.name()).into());
+        }
+
+        match self.inner.fetch(client).await {
+            Ok(url) => {
+                self.breaker.record_success();
+                self.remember(&url);
+                Ok(url)
+            }
+            Err(e) => {
+                self.breaker.record_failure();
+                self.fallback(e)
+            }
+        }
+    }
+}
+
+pub struct ImageProviders(HashMap<String, Arc<dyn ImageProvider>>);
+
+impl ImageProviders {
+    pub fn new(configs: &[ProviderConfig]) -> Self {
+        Self(
+            configs
+                .iter()
+                .map(|config| {
+                    let provider: Arc<dyn ImageProvider> = Arc::new(GuardedProvider::new(
+                        Arc::new(JsonImageProvider::new(config.clone())),
+                        CircuitBreaker::new(
+                            config.failure_threshold,
+                            Duration::from_secs(config.open_secs),
+                        ),
+                        config.fallback_size,
+                    ));
+                    (config.name.clone(), provider)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ImageProvider>> {
+        self.0.get(name).cloned()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    database::Database,
+    embeds::EmbedKind,
+    i18n::{resolve, Locales},
+    scheduler,
+    shutdown::Shutdown,
+    structs::{Reminder, ShardConfig, Theme},
+    utils::is_permanent,
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{CreateAllowedMentions, CreateEmbedAuthor, CreateMessage, Http};
+use sqlx::types::time::OffsetDateTime;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+/// Reminders delivered more than this late mention that they are late.
+const LATE_AFTER: Duration = Duration::from_mins(1);
+
+/// Stores reminders and wakes the scheduler when one is added, in case it is
+/// due before the scheduler would next look.
+pub struct Reminders {
+    db: Arc<dyn Database>,
+    wake: Arc<Notify>,
+}
+
+impl Reminders {
+    pub fn new(db: Arc<dyn Database>) -> Self {
+        Self {
+            db,
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub async fn add(&self, reminder: &Reminder) -> Option<i64> {
+        let id = self.db.add_reminder(reminder).await;
+        self.wake.notify_one();
+        id
+    }
+}
+
+/// Whether this process delivers the reminder. DM reminders go to whichever
+/// process runs shard 0.
+fn owns(shard_config: &ShardConfig, reminder: &Reminder) -> bool {
+    match reminder.guild_id {
+        Some(guild_id) => shard_config.owns_guild(guild_id),
+        None => shard_config.owns_dms(),
+    }
+}
+
+/// Delivers every owned reminder that is due, including ones that came due
+/// while the bot was offline, and returns when the next one within `lookahead`
+/// is due.
+pub async fn deliver_due(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    shard_config: &ShardConfig,
+    lookahead: Duration,
+) -> Option<OffsetDateTime> {
+    let now = OffsetDateTime::now_utc();
+
+    for reminder in db
+        .get_reminders_before(now + lookahead)
+        .await
+        .into_iter()
+        .filter(|r| owns(shard_config, r))
+    {
+        if reminder.remind_at > now {
+            return Some(reminder.remind_at);
+        }
+
+        deliver(http, db, locales, &reminder, now).await;
+    }
+
+    None
+}
+
+/// Sends the reminder to its channel, or by DM if it has none or the channel
+/// can't be used anymore. The reminder is deleted once it's sent, or when
+/// Discord won't ever take it, and is otherwise left for the next pass.
+async fn deliver(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    reminder: &Reminder,
+    now: OffsetDateTime,
+) {
+    let lang = resolve(
+        db,
+        locales.clone(),
+        reminder.guild_id,
+        Some(reminder.user_id),
+    )
+    .await;
+    let theme = match reminder.guild_id {
+        Some(guild_id) => db.get_settings(guild_id).await.theme,
+        None => Theme::default(),
+    };
+
+    let mut author = CreateEmbedAuthor::new(lang.tr("reminder-title"));
+    match reminder.user_id.to_user(http).await {
+        Ok(user) => author = author.icon_url(user.face()),
+        Err(e) => error!("Error: {}", e),
+    }
+
+    let mut notes = vec![lang.tr_with(
+        "reminder-set",
+        &fluent_args!["time" => relative_time(reminder.created_at)],
+    )];
+
+    if now - reminder.remind_at > LATE_AFTER {
+        notes.push(lang.tr_with(
+            "reminder-late",
+            &fluent_args!["time" => relative_time(reminder.remind_at)],
+        ));
+    }
+
+    let embed = theme
+        .embed(EmbedKind::Primary)
+        .author(author)
+        .description(format!("{}\n\n{}", reminder.text, notes.join("\n")));
+
+    let message = CreateMessage::new()
+        .content(format!("<@{}>", reminder.user_id))
+        .embed(embed)
+        .allowed_mentions(CreateAllowedMentions::new().users([reminder.user_id]));
+
+    if let Some(channel_id) = reminder.channel_id {
+        match channel_id.send_message(http, message.clone()).await {
+            Ok(_) => {
+                db.delete_reminder(reminder.id).await;
+                info!("Delivered reminder {}", reminder.id);
+                return;
+            }
+            Err(e) if is_permanent(&e) => error!(
+                "Error sending reminder {} to {}, sending it by DM: {}",
+                reminder.id, channel_id, e
+            ),
+            Err(e) => {
+                error!(
+                    "Error sending reminder {} to {}, will retry: {}",
+                    reminder.id, channel_id, e
+                );
+                return;
+            }
+        }
+    }
+
+    match reminder.user_id.direct_message(http, message).await {
+        Ok(_) => info!("Delivered reminder {} by DM", reminder.id),
+        Err(e) if is_permanent(&e) => {
+            error!(
+                "Error sending reminder {} by DM, dropping it: {}",
+                reminder.id, e
+            );
+        }
+        Err(e) => {
+            error!(
+                "Error sending reminder {} by DM, will retry: {}",
+                reminder.id, e
+            );
+            return;
+        }
+    }
+
+    db.delete_reminder(reminder.id).await;
+}
+
+/// A Discord timestamp that shows how long ago or until `time` it is.
+pub fn relative_time(time: OffsetDateTime) -> String {
+    format!("<t:{}:R>", time.unix_timestamp())
+}
+
+/// Delivers reminders as they come due, looking `poll` ahead.
+pub fn spawn(
+    http: Arc<Http>,
+    reminders: Arc<Reminders>,
+    locales: Arc<Locales>,
+    shard_config: Arc<ShardConfig>,
+    poll: Duration,
+    shutdown: &Shutdown,
+) {
+    let wake = reminders.wake.clone();
+
+    scheduler::spawn(
+        (http, reminders, locales, shard_config),
+        wake,
+        poll,
+        shutdown,
+        |(http, reminders, locales, shard_config), lookahead| async move {
+            deliver_due(
+                &http,
+                reminders.db.as_ref(),
+                &locales,
+                &shard_config,
+                lookahead,
+            )
+            .await
+        },
+    );
+}
@@ -0,0 +1,349 @@
+use crate::{
+    database::get_database,
+    embeds::EmbedKind,
+    i18n::{resolve, Lang},
+    structs::{RolePanel, RolePanelKind, Theme},
+    utils::{defer_ephemeral, get_locales, highest_role_position, respond},
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{
+    ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, Context, CreateActionRow,
+    CreateButton, CreateEmbed, CreateEmbedAuthor, CreateSelectMenu, CreateSelectMenuKind,
+    CreateSelectMenuOption, Guild, GuildId, Http, ReactionType, RoleId, UserId,
+};
+use tracing::error;
+
+/// Panels can have at most this many roles, the most buttons or select menu
+/// options a message can have.
+pub const MAX_ROLES: usize = 25;
+const BUTTONS_PER_ROW: usize = 5;
+const CUSTOM_ID_PREFIX: &str = "rolepanel";
+
+/// Custom ID of a button, which carries the role it toggles so clicks can be
+/// handled after a restart.
+pub fn button_id(panel_id: i64, role_id: RoleId) -> String {
+    format!("{CUSTOM_ID_PREFIX}:{panel_id}:{role_id}")
+}
+
+/// Custom ID of a select menu, its options' values are role IDs.
+pub fn menu_id(panel_id: i64) -> String {
+    format!("{CUSTOM_ID_PREFIX}:{panel_id}")
+}
+
+/// Reads the panel, and for buttons the role, from a component's custom ID.
+pub fn parse_custom_id(custom_id: &str) -> Option<(i64, Option<RoleId>)> {
+    let mut parts = custom_id.split(':');
+    if parts.next() != Some(CUSTOM_ID_PREFIX) {
+        return None;
+    }
+
+    let panel_id = parts.next()?.parse().ok()?;
+    let role_id = match parts.next() {
+        Some(role_id) => Some(RoleId::new(role_id.parse().ok().filter(|id| *id != 0)?)),
+        None => None,
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((panel_id, role_id))
+}
+
+/// Accepts unicode emoji and custom emoji mentions such as `<:ferris:123>`.
+pub fn parse_emoji(emoji: &str) -> Option<ReactionType> {
+    let emoji = emoji.trim();
+
+    if emoji.starts_with('<') {
+        return ReactionType::try_from(emoji).ok();
+    }
+
+    // Anything with letters or spaces is text, not an emoji
+    let valid = !emoji.is_empty()
+        && emoji.chars().count() <= 10
+        && !emoji
+            .chars()
+            .any(|c| c.is_ascii_alphabetic() || c.is_whitespace());
+
+    valid.then(|| ReactionType::Unicode(emoji.to_string()))
+}
+
+pub fn panel_embed(
+    theme: &Theme,
+    lang: &Lang,
+    panel: &RolePanel,
+    icon: Option<String>,
+) -> CreateEmbed {
+    let mut author = CreateEmbedAuthor::new(&panel.title);
+    if let Some(icon) = icon {
+        author = author.icon_url(icon);
+    }
+
+    let roles = if panel.roles.is_empty() {
+        lang.tr("rolepanel-no-roles")
+    } else {
+        panel
+            .roles
+            .iter()
+            .map(|role| match &role.emoji {
+                Some(emoji) => format!("{emoji} <@&{}>", role.role_id),
+                None => format!("<@&{}>", role.role_id),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let description = match &panel.description {
+        Some(description) => format!("{description}\n\n{roles}"),
+        None => roles,
+    };
+
+    theme
+        .embed(EmbedKind::Primary)
+        .author(author)
+        .description(description)
+}
+
+pub fn panel_components(lang: &Lang, panel: &RolePanel) -> Vec<CreateActionRow> {
+    if panel.roles.is_empty() {
+        return Vec::new();
+    }
+
+    match panel.kind {
+        RolePanelKind::Buttons => panel
+            .roles
+            .chunks(BUTTONS_PER_ROW)
+            .map(|roles| {
+                CreateActionRow::Buttons(
+                    roles
+                        .iter()
+                        .map(|role| {
+                            let button = CreateButton::new(button_id(panel.id, role.role_id))
+                                .label(&role.label)
+                                .style(ButtonStyle::Secondary);
+
+                            match role.emoji.as_deref().and_then(parse_emoji) {
+                                Some(emoji) => button.emoji(emoji),
+                                None => button,
+                            }
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+        RolePanelKind::Menu => {
+            let options = panel
+                .roles
+                .iter()
+                .map(|role| {
+                    let option = CreateSelectMenuOption::new(&role.label, role.role_id.to_string());
+
+                    match role.emoji.as_deref().and_then(parse_emoji) {
+                        Some(emoji) => option.emoji(emoji),
+                        None => option,
+                    }
+                })
+                .collect();
+
+            #[allow(clippy::cast_possible_truncation)]
+            let max_values = panel.roles.len() as u8;
+
+            vec![CreateActionRow::SelectMenu(
+                CreateSelectMenu::new(menu_id(panel.id), CreateSelectMenuKind::String { options })
+                    .placeholder(lang.tr("rolepanel-placeholder"))
+                    .min_values(1)
+                    .max_values(max_values),
+            )]
+        }
+    }
+}
+
+/// Whether the bot can give out the role: it has to be below the bot's
+/// highest role and can't be @everyone or managed by an integration.
+pub fn assignable(guild: &Guild, bot_roles: &[RoleId], role_id: RoleId) -> bool {
+    let Some(role) = guild.roles.get(&role_id) else {
+        return false;
+    };
+
+    role.id.get() != guild.id.get()
+        && !role.managed
+        && role.position < highest_role_position(guild, bot_roles)
+}
+
+/// What clicking roles on a panel does to a member.
+#[derive(Debug, Default, PartialEq)]
+pub struct RoleChanges {
+    pub added: Vec<RoleId>,
+    pub removed: Vec<RoleId>,
+    /// Roles the bot can't give out or take away.
+    pub refused: Vec<RoleId>,
+}
+
+/// Toggles each clicked role: members lose the ones they have and get the rest.
+pub fn plan(
+    guild: &Guild,
+    bot_roles: &[RoleId],
+    member_roles: &[RoleId],
+    clicked: &[RoleId],
+) -> RoleChanges {
+    let mut changes = RoleChanges::default();
+
+    for role_id in clicked {
+        if !assignable(guild, bot_roles, *role_id) {
+            changes.refused.push(*role_id);
+        } else if member_roles.contains(role_id) {
+            changes.removed.push(*role_id);
+        } else {
+            changes.added.push(*role_id);
+        }
+    }
+
+    changes
+}
+
+/// Carries out the changes, moving roles that couldn't be changed to `refused`.
+pub async fn apply(
+    http: &Http,
+    guild_id: GuildId,
+    user_id: UserId,
+    changes: RoleChanges,
+) -> RoleChanges {
+    let reason = Some("Role panel");
+    let mut applied = RoleChanges {
+        refused: changes.refused,
+        ..RoleChanges::default()
+    };
+
+    for role_id in changes.added {
+        match http
+            .add_member_role(guild_id, user_id, role_id, reason)
+            .await
+        {
+            Ok(()) => applied.added.push(role_id),
+            Err(e) => {
+                error!("Error adding role {} to {}: {}", role_id, user_id, e);
+                applied.refused.push(role_id);
+            }
+        }
+    }
+
+    for role_id in changes.removed {
+        match http
+            .remove_member_role(guild_id, user_id, role_id, reason)
+            .await
+        {
+            Ok(()) => applied.removed.push(role_id),
+            Err(e) => {
+                error!("Error removing role {} from {}: {}", role_id, user_id, e);
+                applied.refused.push(role_id);
+            }
+        }
+    }
+
+    applied
+}
+
+fn summary(lang: &Lang, changes: &RoleChanges) -> String {
+    let lines: Vec<_> = [
+        ("rolepanel-added", &changes.added),
+        ("rolepanel-removed", &changes.removed),
+        ("rolepanel-refused", &changes.refused),
+    ]
+    .into_iter()
+    .flat_map(|(id, roles)| {
+        roles
+            .iter()
+            .map(move |role_id| lang.tr_with(id, &fluent_args!["role" => format!("<@&{role_id}>")]))
+    })
+    .collect();
+
+    if lines.is_empty() {
+        return lang.tr("rolepanel-nothing");
+    }
+
+    lines.join("\n")
+}
+
+/// Handles clicks on role panels. Panels are looked up by the ID in the
+/// component, so they keep working across restarts.
+pub async fn handle(ctx: &Context, interaction: &ComponentInteraction) {
+    let Some((panel_id, button_role)) = parse_custom_id(&interaction.data.custom_id) else {
+        return;
+    };
+    let (Some(guild_id), Some(member)) = (interaction.guild_id, interaction.member.as_ref()) else {
+        return;
+    };
+
+    if !defer_ephemeral(ctx, interaction).await {
+        return;
+    }
+
+    let db = get_database(ctx).await;
+    let lang = resolve(
+        db.as_ref(),
+        get_locales(ctx).await,
+        Some(guild_id),
+        Some(interaction.user.id),
+    )
+    .await;
+    let theme = db.get_settings(guild_id).await.theme;
+
+    let gone = || {
+        theme
+            .embed(EmbedKind::Error)
+            .author(
+                CreateEmbedAuthor::new(lang.tr("error-title")).icon_url(interaction.user.face()),
+            )
+            .description(lang.tr("rolepanel-gone"))
+    };
+
+    let Some(panel) = db.get_role_panel(guild_id, panel_id).await else {
+        return respond(ctx, interaction, gone()).await;
+    };
+
+    let mut clicked = match (&interaction.data.kind, button_role) {
+        (ComponentInteractionDataKind::Button, Some(role_id)) => vec![role_id],
+        (ComponentInteractionDataKind::StringSelect { values }, None) => values
+            .iter()
+            .filter_map(|value| value.parse().ok())
+            .filter(|id| *id != 0)
+            .map(RoleId::new)
+            .collect(),
+        // A component the panel no longer has, left on a stale message
+        _ => return respond(ctx, interaction, gone()).await,
+    };
+    // Roles removed from the panel can still be on messages that weren't updated
+    clicked.retain(|role_id| panel.roles.iter().any(|role| role.role_id == *role_id));
+
+    let bot_id = ctx.cache.current_user().id;
+    let bot_roles = match guild_id.member(ctx, bot_id).await {
+        Ok(bot) => Some(bot.roles),
+        Err(e) => {
+            error!("Error: {}", e);
+            None
+        }
+    };
+
+    let planned = bot_roles.and_then(|bot_roles| {
+        let guild = ctx.cache.guild(guild_id)?;
+        Some(plan(&guild, &bot_roles, &member.roles, &clicked))
+    });
+    // Say which roles couldn't be changed rather than leaving the click unanswered
+    let changes = match planned {
+        Some(changes) => apply(&ctx.http, guild_id, interaction.user.id, changes).await,
+        None => RoleChanges {
+            refused: clicked,
+            ..RoleChanges::default()
+        },
+    };
+
+    let embed = theme
+        .embed(EmbedKind::Success)
+        .author(
+            CreateEmbedAuthor::new(lang.tr("rolepanel-your-roles"))
+                .icon_url(interaction.user.face()),
+        )
+        .description(summary(&lang, &changes));
+
+    respond(ctx, interaction, embed).await;
+}
@@ -0,0 +1,41 @@
+use crate::shutdown::Shutdown;
+use sqlx::types::time::OffsetDateTime;
+use std::{future::Future, sync::Arc, time::Duration};
+use tokio::{sync::Notify, time::sleep};
+
+/// Runs `run` until shutdown to handle items as they come due, such as
+/// reminders. Each run handles whatever is due and returns when the next item
+/// within the lookahead it's given comes due. The scheduler sleeps until then,
+/// for at most `lookahead`, and wakes early when `wake` is notified, such as
+/// when an item is added. `state` is handed to every run.
+pub fn spawn<S, F, Fut>(
+    state: S,
+    wake: Arc<Notify>,
+    lookahead: Duration,
+    shutdown: &Shutdown,
+    run: F,
+) where
+    S: Clone + Send + 'static,
+    F: Fn(S, Duration) -> Fut + Send + 'static,
+    Fut: Future<Output = Option<OffsetDateTime>> + Send,
+{
+    let token = shutdown.token();
+
+    shutdown.jobs().spawn(async move {
+        loop {
+            let next = run(state.clone(), lookahead).await;
+
+            let wait = next.map_or(lookahead, |at| {
+                Duration::try_from(at - OffsetDateTime::now_utc())
+                    .unwrap_or_default()
+                    .min(lookahead)
+            });
+
+            tokio::select! {
+                () = sleep(wait) => {}
+                () = wake.notified() => {}
+                () = token.cancelled() => break,
+            }
+        }
+    });
+}
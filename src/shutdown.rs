@@ -0,0 +1,92 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::time::timeout;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tracing::{info, warn};
+
+/// Coordinates a graceful shutdown: new interactions are refused, in-flight
+/// commands get a chance to finish, and background jobs are told to stop.
+pub struct Shutdown {
+    accepting: AtomicBool,
+    commands: TaskTracker,
+    jobs: TaskTracker,
+    token: CancellationToken,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            accepting: AtomicBool::new(true),
+            commands: TaskTracker::new(),
+            jobs: TaskTracker::new(),
+            token: CancellationToken::new(),
+        }
+    }
+
+    pub fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::Acquire)
+    }
+
+    /// Tracks work done on behalf of an incoming event.
+    pub fn commands(&self) -> &TaskTracker {
+        &self.commands
+    }
+
+    /// Tracks background jobs. Jobs should stop once [`Shutdown::token`] is cancelled.
+    pub fn jobs(&self) -> &TaskTracker {
+        &self.jobs
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Stops accepting new work, then waits for in-flight commands and background
+    /// jobs, in that order, giving each up to `grace` to finish.
+    pub async fn drain(&self, grace: Duration) {
+        self.accepting.store(false, Ordering::Release);
+
+        self.commands.close();
+        info!("Waiting for {} in-flight commands", self.commands.len());
+        if timeout(grace, self.commands.wait()).await.is_err() {
+            warn!(
+                "Timed out waiting for {} in-flight commands",
+                self.commands.len()
+            );
+        }
+
+        self.token.cancel();
+        self.jobs.close();
+        info!("Waiting for {} background jobs", self.jobs.len());
+        if timeout(grace, self.jobs.wait()).await.is_err() {
+            warn!("Timed out waiting for {} background jobs", self.jobs.len());
+        }
+    }
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM where supported.
+pub async fn wait_for_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Could not register ctrl+c handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Could not register SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => info!("Received ctrl+c, shutting down"),
+        () = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
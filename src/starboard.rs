@@ -0,0 +1,246 @@
+use crate::{
+    database::get_database,
+    embeds::{EmbedKind, DESCRIPTION_LIMIT},
+    i18n::{resolve, Lang},
+    structs::{StarboardPost, Theme},
+    utils::{get_locales, get_starboard, is_gone},
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{
+    ChannelId, Context, CreateEmbed, CreateEmbedAuthor, CreateMessage, EditMessage, GuildId,
+    Message, MessageId, Reaction, ReactionType,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tracing::{error, info};
+
+/// The reaction that stars a message.
+pub const STAR: &str = "⭐";
+
+pub fn is_star(emoji: &ReactionType) -> bool {
+    matches!(emoji, ReactionType::Unicode(emoji) if emoji == STAR)
+}
+
+pub fn count_stars(message: &Message) -> i32 {
+    message
+        .reactions
+        .iter()
+        .find(|reaction| is_star(&reaction.reaction_type))
+        .map_or(0, |reaction| {
+            i32::try_from(reaction.count).unwrap_or(i32::MAX)
+        })
+}
+
+/// What a change in a message's stars does to its starboard post.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Post,
+    Edit,
+    Remove,
+    Nothing,
+}
+
+pub fn plan(stars: i32, threshold: i32, post: Option<&StarboardPost>) -> Action {
+    match post {
+        None if stars >= threshold => Action::Post,
+        Some(_) if stars < threshold => Action::Remove,
+        Some(post) if post.stars != stars => Action::Edit,
+        _ => Action::Nothing,
+    }
+}
+
+/// The text above a starboard post, which is all that changes with the count.
+pub fn star_count(lang: &Lang, stars: i32, channel_id: ChannelId) -> String {
+    lang.tr_with(
+        "starboard-stars",
+        &fluent_args!["stars" => stars, "channel" => format!("<#{channel_id}>")],
+    )
+}
+
+/// Reposts the message's content, first image and author, with a link back.
+pub fn repost_embed(
+    lang: &Lang,
+    theme: &Theme,
+    message: &Message,
+    guild_id: GuildId,
+) -> CreateEmbed {
+    let jump = lang.tr_with(
+        "starboard-jump",
+        &fluent_args!["link" => message.id.link(message.channel_id, Some(guild_id))],
+    );
+    let description = if message.content.is_empty() {
+        jump
+    } else {
+        // Messages sent with Nitro can nearly fill a description on their own
+        let room = DESCRIPTION_LIMIT - jump.chars().count() - "\n\n".len();
+        if message.content.chars().count() > room {
+            let content: String = message.content.chars().take(room - 1).collect();
+            format!("{content}…\n\n{jump}")
+        } else {
+            format!("{}\n\n{jump}", message.content)
+        }
+    };
+
+    let mut embed = theme
+        .embed(EmbedKind::Primary)
+        .author(CreateEmbedAuthor::new(&message.author.name).icon_url(message.author.face()))
+        .description(description)
+        .timestamp(message.timestamp);
+
+    if let Some(image) = message.attachments.iter().find(|attachment| {
+        attachment
+            .content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with("image/"))
+    }) {
+        embed = embed.image(&image.url);
+    }
+
+    embed
+}
+
+/// Makes updates for the same message wait for each other, so stars added at
+/// the same time don't repost it twice.
+#[derive(Default)]
+pub struct Starboard {
+    updating: Mutex<HashMap<MessageId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl Starboard {
+    fn lock(&self, message_id: MessageId) -> Arc<tokio::sync::Mutex<()>> {
+        self.updating
+            .lock()
+            .unwrap()
+            .entry(message_id)
+            .or_default()
+            .clone()
+    }
+
+    fn release(&self, message_id: MessageId, lock: &Arc<tokio::sync::Mutex<()>>) {
+        let mut updating = self.updating.lock().unwrap();
+
+        // Nobody else is waiting once only the map and this update hold the lock
+        if Arc::strong_count(lock) <= 2 {
+            updating.remove(&message_id);
+        }
+    }
+}
+
+pub async fn on_reaction(ctx: &Context, reaction: &Reaction) {
+    let Some(guild_id) = reaction.guild_id else {
+        return;
+    };
+
+    if is_star(&reaction.emoji) {
+        update(ctx, guild_id, reaction.channel_id, reaction.message_id).await;
+    }
+}
+
+/// Posts, edits or removes the message's starboard post to match its stars.
+pub async fn update(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) {
+    let db = get_database(ctx).await;
+    let settings = db.get_settings(guild_id).await;
+
+    // Starring starboard posts would repost them again
+    let Some(starboard_id) = settings.starboard_id.filter(|id| *id != channel_id) else {
+        return;
+    };
+
+    let starboard = get_starboard(ctx).await;
+    let lock = starboard.lock(message_id);
+    let guard = lock.lock().await;
+
+    // The cache doesn't track reactions, so the count has to come from Discord
+    let message = match ctx.http.get_message(channel_id, message_id).await {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Error fetching starred message {}: {}", message_id, e);
+            drop(guard);
+            return starboard.release(message_id, &lock);
+        }
+    };
+
+    let stars = count_stars(&message);
+    let post = db.get_starboard_post(message_id).await;
+    let lang = resolve(db.as_ref(), get_locales(ctx).await, Some(guild_id), None).await;
+
+    let mut action = plan(stars, settings.starboard_threshold, post.as_ref());
+    if let (Action::Edit, Some(post)) = (&action, &post) {
+        let edited = post
+            .starboard_channel_id
+            .edit_message(
+                ctx,
+                post.starboard_message_id,
+                EditMessage::new().content(star_count(&lang, stars, channel_id)),
+            )
+            .await;
+
+        match edited {
+            Ok(_) => {
+                db.set_starboard_post(&StarboardPost {
+                    stars,
+                    ..post.clone()
+                })
+                .await;
+            }
+            // Someone deleted the starboard post, so the message is posted again
+            Err(e) if is_gone(&e) => {
+                db.delete_starboard_post(message_id).await;
+                action = Action::Post;
+            }
+            Err(e) => error!("Error editing starboard post for {}: {}", message_id, e),
+        }
+    }
+
+    match (action, post) {
+        (Action::Post, _) => {
+            let sent = starboard_id
+                .send_message(
+                    ctx,
+                    CreateMessage::new()
+                        .content(star_count(&lang, stars, channel_id))
+                        .embed(repost_embed(&lang, &settings.theme, &message, guild_id)),
+                )
+                .await;
+
+            match sent {
+                Ok(sent) => {
+                    db.set_starboard_post(&StarboardPost {
+                        guild_id,
+                        channel_id,
+                        message_id,
+                        starboard_channel_id: starboard_id,
+                        starboard_message_id: sent.id,
+                        stars,
+                    })
+                    .await;
+                    info!("Posted message {} to the starboard", message_id);
+                }
+                Err(e) => error!("Error posting to starboard {}: {}", starboard_id, e),
+            }
+        }
+        (Action::Remove, Some(post)) => {
+            if let Err(e) = post
+                .starboard_channel_id
+                .delete_message(ctx, post.starboard_message_id)
+                .await
+            {
+                error!("Error deleting starboard post for {}: {}", message_id, e);
+            }
+
+            db.delete_starboard_post(message_id).await;
+            info!("Removed message {} from the starboard", message_id);
+        }
+        _ => {}
+    }
+
+    drop(guard);
+    starboard.release(message_id, &lock);
+}
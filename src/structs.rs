@@ -1,13 +1,28 @@
-use poise::serenity_prelude::{prelude::TypeMapKey, ShardManager};
+use crate::{
+    cooldowns::Cooldowns,
+    database::{Database, SettingsCache},
+    giveaways::Giveaways,
+    health::GatewayStatus,
+    i18n::Locales,
+    levels::Levels,
+    modlog::ModlogQueue,
+    permissions::PermissionOverrides,
+    polls::Polls,
+    providers::ImageProviders,
+    reminders::Reminders,
+    shutdown::Shutdown,
+    starboard::Starboard,
+};
+use poise::serenity_prelude::{
+    prelude::TypeMapKey, utils::shard_id, ChannelId, Color, GuildId, MessageId, RoleId,
+    ShardManager, UserId,
+};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use sqlx::{types::time::PrimitiveDateTime, Pool, Postgres, Type};
-use std::sync::Arc;
-
-#[derive(Serialize, Deserialize)]
-pub struct PetResponse {
-    pub url: String,
-}
+use sqlx::{
+    types::time::{OffsetDateTime, PrimitiveDateTime},
+    Pool, Postgres, Type,
+};
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
 
 #[derive(Type, Debug, Clone, Copy)]
 #[sqlx(type_name = "infraction_type", rename_all = "lowercase")]
@@ -18,22 +33,263 @@ pub enum InfractionType {
     Warn,
 }
 
+#[derive(Clone)]
 pub struct Guild {
-    pub id: String,
-    pub mod_id: Option<String>,
-    pub audit_id: Option<String>,
-    pub welcome_id: Option<String>,
+    pub id: GuildId,
+    pub mod_id: Option<ChannelId>,
+    pub audit_id: Option<ChannelId>,
+    pub welcome_id: Option<ChannelId>,
+    /// Language for members without their own, `None` uses the default.
+    pub locale: Option<String>,
+    /// Prefix for text commands, `None` uses the default.
+    pub prefix: Option<String>,
+    /// Whether mentioning the bot also works as a prefix.
+    pub mention_prefix: bool,
+    pub theme: Theme,
+    /// Channels where messages give no XP.
+    pub no_xp_channels: Vec<ChannelId>,
+    /// Where starred messages are reposted, `None` turns the starboard off.
+    pub starboard_id: Option<ChannelId>,
+    /// Stars a message needs to be reposted.
+    pub starboard_threshold: i32,
+}
+
+impl Guild {
+    pub fn new(id: GuildId) -> Self {
+        Self {
+            id,
+            mod_id: None,
+            audit_id: None,
+            welcome_id: None,
+            locale: None,
+            prefix: None,
+            mention_prefix: true,
+            theme: Theme::default(),
+            no_xp_channels: Vec::new(),
+            starboard_id: None,
+            starboard_threshold: 3,
+        }
+    }
+}
+
+/// How a guild's embeds look. Colors left as `None` use the bot's defaults.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub primary: Option<Color>,
+    pub success: Option<Color>,
+    pub error: Option<Color>,
+    pub footer_text: Option<String>,
+    /// Only shown alongside footer text.
+    pub footer_icon: Option<String>,
+    pub timestamps: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            primary: None,
+            success: None,
+            error: None,
+            footer_text: None,
+            footer_icon: None,
+            timestamps: true,
+        }
+    }
 }
 
+#[allow(dead_code, clippy::struct_field_names)]
+#[derive(Clone)]
 pub struct Infraction {
-    pub guild_id: String,
-    pub member_id: String,
-    pub moderator_id: String,
+    pub guild_id: GuildId,
+    pub member_id: UserId,
+    pub moderator_id: UserId,
     pub reason: String,
     pub infraction_type: InfractionType,
     pub created_at: Option<PrimitiveDateTime>,
 }
 
+#[derive(Type, poise::ChoiceParameter, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "cooldown_bucket", rename_all = "lowercase")]
+pub enum CooldownBucket {
+    User,
+    Channel,
+    Guild,
+}
+
+#[derive(Type, poise::ChoiceParameter, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "permission_target", rename_all = "lowercase")]
+pub enum PermissionTarget {
+    Role,
+    User,
+    Channel,
+}
+
+/// Allows or denies a command for a role, user or channel, regardless of the
+/// command's default permissions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PermissionOverride {
+    pub guild_id: GuildId,
+    pub command: String,
+    pub target: PermissionTarget,
+    pub target_id: u64,
+    pub allow: bool,
+}
+
+/// Something a user asked to be reminded of. Reminders without a channel are sent by DM.
+#[derive(Clone, Debug)]
+pub struct Reminder {
+    /// Assigned by the database, ignored when adding.
+    pub id: i64,
+    pub user_id: UserId,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<ChannelId>,
+    pub text: String,
+    pub created_at: OffsetDateTime,
+    pub remind_at: OffsetDateTime,
+}
+
+/// A named snippet members can post with `/tag show`, or as a text command.
+#[derive(Clone, Debug)]
+pub struct Tag {
+    pub guild_id: GuildId,
+    /// Lowercase, see [`crate::tags::normalize_name`].
+    pub name: String,
+    pub content: String,
+    /// Whether the content is posted in an embed instead of as plain text.
+    pub embed: bool,
+    pub owner_id: UserId,
+    pub uses: i64,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Type, poise::ChoiceParameter, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "role_panel_kind", rename_all = "lowercase")]
+pub enum RolePanelKind {
+    Buttons,
+    Menu,
+}
+
+/// A message members click to give themselves roles, or take them away again.
+#[derive(Clone, Debug)]
+pub struct RolePanel {
+    /// Assigned by the database, ignored when creating.
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub kind: RolePanelKind,
+    pub title: String,
+    pub description: Option<String>,
+    /// In the order they were added.
+    pub roles: Vec<PanelRole>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanelRole {
+    pub role_id: RoleId,
+    pub label: String,
+    /// A unicode emoji or a custom emoji mention.
+    pub emoji: Option<String>,
+}
+
+/// A member's XP in a guild, either their total or what they gained since the
+/// last write.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemberXp {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub xp: i64,
+}
+
+/// A role members get once they reach `level`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelReward {
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+    pub level: i32,
+}
+
+/// A message reposted to the starboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StarboardPost {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub starboard_channel_id: ChannelId,
+    pub starboard_message_id: MessageId,
+    pub stars: i32,
+}
+
+/// A question members answer by clicking its buttons, until it closes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Poll {
+    pub message_id: MessageId,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    /// Who started the poll.
+    pub user_id: UserId,
+    pub question: String,
+    pub options: Vec<String>,
+    /// Whether members can vote for more than one option.
+    pub multi_choice: bool,
+    /// Whether the results leave out who voted for what.
+    pub anonymous: bool,
+    pub closes_at: OffsetDateTime,
+}
+
+/// One option a member voted for, members picking several have one per option.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollVote {
+    pub user_id: UserId,
+    /// Index into [`Poll::options`].
+    pub option: i16,
+}
+
+/// Members enter by clicking its button, and once it ends winners are drawn
+/// from the entries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Giveaway {
+    /// Assigned by the database, ignored when creating.
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub host_id: UserId,
+    pub prize: String,
+    pub winner_count: i32,
+    /// Only members with this role can enter.
+    pub required_role_id: Option<RoleId>,
+    /// Only accounts at least this old can enter.
+    pub min_account_age: Option<Duration>,
+    pub ends_at: OffsetDateTime,
+    pub ended: bool,
+    /// Seed of the latest draw, see [`crate::giveaways::draw`]. Read from the
+    /// stored draws, ignored when creating.
+    pub seed: Option<u64>,
+    /// Everyone drawn so far, including by rerolls. Read from the stored
+    /// draws, ignored when creating.
+    pub winner_ids: Vec<UserId>,
+}
+
+/// A guild's override of a command's default cooldown. A zero duration disables the bucket.
+#[derive(Clone)]
+pub struct CooldownPolicy {
+    pub guild_id: GuildId,
+    pub command: String,
+    pub bucket: CooldownBucket,
+    pub duration: Duration,
+}
+
+/// Identifies one running cooldown. `scope_id` is the guild the command was used in,
+/// so user and channel cooldowns set in one guild don't apply in another.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CooldownKey {
+    pub scope_id: Option<GuildId>,
+    pub command: String,
+    pub bucket: CooldownBucket,
+    pub target_id: u64,
+}
+
 pub struct DbConfig {
     pub host: String,
     pub port: u16,
@@ -41,14 +297,75 @@ pub struct DbConfig {
     pub password: String,
 }
 
-pub struct Data {}
+pub struct HealthConfig {
+    pub port: u16,
+    pub max_disconnect: Duration,
+}
+
+pub enum ShardConfig {
+    /// Use Discord's recommended shard count and run every shard in this process.
+    Auto,
+    /// Run shards `first` through `last` (inclusive) out of `total`.
+    Range { first: u32, last: u32, total: u32 },
+}
+
+impl ShardConfig {
+    /// Whether this process runs the shard that receives events for `guild_id`.
+    /// Guild-scoped background jobs must check this before doing any work.
+    pub fn owns_guild(&self, guild_id: GuildId) -> bool {
+        match self {
+            Self::Auto => true,
+            Self::Range { first, last, total } => {
+                (*first..=*last).contains(&shard_id(guild_id, *total))
+            }
+        }
+    }
+
+    /// The shards this process runs. Autosharding only learns the count when it
+    /// connects, but there is always a shard 0.
+    pub fn shard_ids(&self) -> RangeInclusive<u32> {
+        match self {
+            Self::Auto => 0..=0,
+            Self::Range { first, last, .. } => *first..=*last,
+        }
+    }
+
+    /// Whether this process handles work that belongs to no guild, such as DM
+    /// reminders. Only the process running shard 0 does, so it happens once.
+    pub fn owns_dms(&self) -> bool {
+        match self {
+            Self::Auto => true,
+            Self::Range { first, .. } => *first == 0,
+        }
+    }
+}
+
+pub struct Data {
+    /// Prefix for text commands in guilds without their own, and in DMs.
+    pub default_prefix: String,
+}
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 pub type Command = poise::Command<Data, Error>;
 
 pub struct ShardManagerContainer;
 pub struct PostgresContainer;
+pub struct DatabaseContainer;
+pub struct SettingsCacheContainer;
 pub struct ReqwestClientContainer;
+pub struct ImageProvidersContainer;
+pub struct GatewayStatusContainer;
+pub struct ShutdownContainer;
+pub struct ModlogContainer;
+pub struct ShardConfigContainer;
+pub struct CooldownsContainer;
+pub struct LocalesContainer;
+pub struct PermissionOverridesContainer;
+pub struct RemindersContainer;
+pub struct LevelsContainer;
+pub struct StarboardContainer;
+pub struct PollsContainer;
+pub struct GiveawaysContainer;
 
 impl TypeMapKey for ShardManagerContainer {
     type Value = Arc<ShardManager>;
@@ -58,6 +375,66 @@ impl TypeMapKey for PostgresContainer {
     type Value = Pool<Postgres>;
 }
 
+impl TypeMapKey for DatabaseContainer {
+    type Value = Arc<dyn Database>;
+}
+
+impl TypeMapKey for SettingsCacheContainer {
+    type Value = Arc<SettingsCache>;
+}
+
 impl TypeMapKey for ReqwestClientContainer {
     type Value = Client;
 }
+
+impl TypeMapKey for ImageProvidersContainer {
+    type Value = Arc<ImageProviders>;
+}
+
+impl TypeMapKey for GatewayStatusContainer {
+    type Value = Arc<GatewayStatus>;
+}
+
+impl TypeMapKey for ShutdownContainer {
+    type Value = Arc<Shutdown>;
+}
+
+impl TypeMapKey for ModlogContainer {
+    type Value = ModlogQueue;
+}
+
+impl TypeMapKey for ShardConfigContainer {
+    type Value = Arc<ShardConfig>;
+}
+
+impl TypeMapKey for CooldownsContainer {
+    type Value = Arc<Cooldowns>;
+}
+
+impl TypeMapKey for LocalesContainer {
+    type Value = Arc<Locales>;
+}
+
+impl TypeMapKey for PermissionOverridesContainer {
+    type Value = Arc<PermissionOverrides>;
+}
+
+impl TypeMapKey for RemindersContainer {
+    type Value = Arc<Reminders>;
+}
+
+impl TypeMapKey for LevelsContainer {
+    type Value = Arc<Levels>;
+}
+
+impl TypeMapKey for StarboardContainer {
+    type Value = Arc<Starboard>;
+}
+
+impl TypeMapKey for PollsContainer {
+    type Value = Arc<Polls>;
+}
+
+impl TypeMapKey for GiveawaysContainer {
+    type Value = Arc<Giveaways>;
+}
@@ -0,0 +1,159 @@
+use crate::{
+    cooldowns::Invocation,
+    database::get_database,
+    embeds::EmbedKind,
+    permissions::{MemberAccess, Verdict},
+    structs::{Command, Tag, Theme},
+    utils::get_cooldowns,
+};
+use poise::serenity_prelude::{
+    ChannelId, Context, CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateMessage,
+    Message, User,
+};
+use tracing::error;
+
+/// Tag names are at most this many characters.
+pub const NAME_LIMIT: usize = 32;
+/// Message content is limited to 2000 characters.
+const MESSAGE_LIMIT: usize = 2000;
+
+/// Lowercases a tag name, or returns `None` unless it is made of letters,
+/// digits, `-` and `_` and fits in [`NAME_LIMIT`].
+pub fn normalize_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+
+    let valid = !name.is_empty()
+        && name.chars().count() <= NAME_LIMIT
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+
+    valid.then_some(name)
+}
+
+/// Who a tag is shown to and where, for filling in its placeholders.
+pub struct Placeholders<'a> {
+    pub user: &'a User,
+    pub server: &'a str,
+    pub channel_id: ChannelId,
+}
+
+/// Fills in `{user}`, `{user.name}`, `{server}` and `{channel}`.
+pub fn render(content: &str, placeholders: &Placeholders<'_>) -> String {
+    content
+        .replace("{user}", &format!("<@{}>", placeholders.user.id))
+        .replace("{user.name}", &placeholders.user.name)
+        .replace("{server}", placeholders.server)
+        .replace("{channel}", &format!("<#{}>", placeholders.channel_id))
+}
+
+/// A rendered tag, posted either as plain text or in an embed.
+pub enum TagBody {
+    Text(String),
+    Embed(Box<CreateEmbed>),
+}
+
+pub fn body(theme: &Theme, tag: &Tag, placeholders: &Placeholders<'_>) -> TagBody {
+    let text = render(&tag.content, placeholders);
+
+    if !tag.embed {
+        // Placeholders can push a tag that fit when it was saved over the limit
+        return TagBody::Text(text.chars().take(MESSAGE_LIMIT).collect());
+    }
+
+    TagBody::Embed(Box::new(
+        theme
+            .embed(EmbedKind::Primary)
+            .author(CreateEmbedAuthor::new(&tag.name).icon_url(placeholders.user.face()))
+            .description(text),
+    ))
+}
+
+/// Tags can only ping the member using them, whatever their content mentions.
+pub fn allowed_mentions(user: &User) -> CreateAllowedMentions {
+    CreateAllowedMentions::new().users([user.id])
+}
+
+/// Runs the checks `tag show` gets, so using a tag as a text command follows
+/// the same permission overrides and cooldowns. Refused uses are ignored like
+/// any other message that isn't a command.
+async fn allowed(ctx: &Context, msg: &Message, commands: &[Command]) -> bool {
+    let Some(tag) = commands.iter().find(|c| c.name == "tag") else {
+        return false;
+    };
+    let Some(show) = tag.subcommands.iter().find(|c| c.name == "show") else {
+        return false;
+    };
+    let Some(access) = MemberAccess::resolve_message(ctx, msg).await else {
+        return false;
+    };
+
+    if !matches!(access.verdict(show, &[tag]), Verdict::Allowed) {
+        return false;
+    }
+
+    let invocation = Invocation {
+        command: &show.qualified_name,
+        guild_id: msg.guild_id,
+        channel_id: msg.channel_id,
+        user_id: msg.author.id,
+    };
+    let defaults = show.cooldown_config.read().unwrap().clone();
+
+    get_cooldowns(ctx)
+        .await
+        .hit(&invocation, &defaults)
+        .await
+        .is_none()
+}
+
+/// Posts the tag named by the first word of `invocation`, if the guild has one,
+/// so tags also work as text commands such as `!rules`.
+pub async fn post_as_command(ctx: &Context, msg: &Message, invocation: &str, commands: &[Command]) {
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    let Some(name) = invocation
+        .split_whitespace()
+        .next()
+        .and_then(normalize_name)
+    else {
+        return;
+    };
+
+    let db = get_database(ctx).await;
+    let Some(tag) = db.get_tag(guild_id, &name).await else {
+        return;
+    };
+
+    if !allowed(ctx, msg, commands).await {
+        return;
+    }
+
+    let server = msg
+        .guild(&ctx.cache)
+        .map(|guild| guild.name.clone())
+        .unwrap_or_default();
+    let theme = db.get_settings(guild_id).await.theme;
+    let placeholders = Placeholders {
+        user: &msg.author,
+        server: &server,
+        channel_id: msg.channel_id,
+    };
+
+    let message = match body(&theme, &tag, &placeholders) {
+        TagBody::Text(text) => CreateMessage::new().content(text),
+        TagBody::Embed(embed) => CreateMessage::new().embed(*embed),
+    };
+
+    if let Err(e) = msg
+        .channel_id
+        .send_message(ctx, message.allowed_mentions(allowed_mentions(&msg.author)))
+        .await
+    {
+        error!("Error: {}", e);
+        return;
+    }
+
+    db.use_tag(guild_id, &tag.name).await;
+}
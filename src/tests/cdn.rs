@@ -0,0 +1,41 @@
+use crate::cdn::{image_size, ImageFormat, UserImage};
+use poise::serenity_prelude::{GuildId, ImageHash, UserId};
+
+const USER_ID: UserId = UserId::new(1);
+
+fn hash(hash: &str) -> ImageHash {
+    hash.parse().unwrap()
+}
+
+#[test]
+fn sizes_are_rounded_to_powers_of_two() {
+    assert_eq!(image_size(1), 16);
+    assert_eq!(image_size(512), 512);
+    assert_eq!(image_size(600), 1024);
+    assert_eq!(image_size(u16::MAX), 4096);
+}
+
+#[test]
+fn only_animated_images_have_gifs() {
+    let still = UserImage::Avatar(USER_ID, hash("0123456789abcdef0123456789abcdef"));
+    let animated = UserImage::Avatar(USER_ID, hash("a_0123456789abcdef0123456789abcdef"));
+
+    assert!(!still.formats().contains(&ImageFormat::Gif));
+    assert_eq!(still.default_format(), ImageFormat::Png);
+    assert!(animated.formats().contains(&ImageFormat::Gif));
+    assert_eq!(animated.default_format(), ImageFormat::Gif);
+}
+
+#[test]
+fn urls_point_at_the_right_image() {
+    let hash = hash("0123456789abcdef0123456789abcdef");
+
+    assert_eq!(
+        UserImage::MemberAvatar(GuildId::new(2), USER_ID, hash).url(ImageFormat::Webp, 256),
+        "https://cdn.discordapp.com/guilds/2/users/1/avatars/0123456789abcdef0123456789abcdef.webp?size=256"
+    );
+    assert_eq!(
+        UserImage::Banner(USER_ID, hash).url(ImageFormat::Png, 4096),
+        "https://cdn.discordapp.com/banners/1/0123456789abcdef0123456789abcdef.png?size=4096"
+    );
+}
@@ -0,0 +1,163 @@
+use crate::{
+    cooldowns::{Cooldowns, Invocation},
+    database::{memory::MemoryDatabase, CooldownRepository, PostgresDatabase},
+    structs::{CooldownBucket, CooldownKey, CooldownPolicy},
+};
+use poise::{
+    serenity_prelude::{ChannelId, GuildId, UserId},
+    CooldownConfig,
+};
+use sqlx::{types::time::OffsetDateTime, PgPool};
+use std::{sync::Arc, time::Duration};
+
+const GUILD_ID: GuildId = GuildId::new(100);
+
+fn cooldowns() -> (Arc<MemoryDatabase>, Cooldowns) {
+    let db = Arc::new(MemoryDatabase::default());
+    let cooldowns = Cooldowns::new(db.clone(), Duration::from_mins(1));
+    (db, cooldowns)
+}
+
+fn invocation(user_id: u64) -> Invocation<'static> {
+    Invocation {
+        command: "cat",
+        guild_id: Some(GUILD_ID),
+        channel_id: ChannelId::new(10),
+        user_id: UserId::new(user_id),
+    }
+}
+
+fn user_cooldown(secs: u64) -> CooldownConfig {
+    CooldownConfig {
+        user: Some(Duration::from_secs(secs)),
+        ..Default::default()
+    }
+}
+
+fn policy(bucket: CooldownBucket, secs: u64) -> CooldownPolicy {
+    CooldownPolicy {
+        guild_id: GUILD_ID,
+        command: String::from("cat"),
+        bucket,
+        duration: Duration::from_secs(secs),
+    }
+}
+
+#[tokio::test]
+async fn second_use_is_refused_with_remaining_time() {
+    let (_, cooldowns) = cooldowns();
+
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3))
+        .await
+        .is_none());
+
+    let remaining = cooldowns.hit(&invocation(1), &user_cooldown(3)).await;
+    assert!(remaining.is_some_and(|r| r <= Duration::from_secs(3) && !r.is_zero()));
+
+    assert!(cooldowns
+        .hit(&invocation(2), &user_cooldown(3))
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn guild_override_replaces_default() {
+    let (_, cooldowns) = cooldowns();
+    cooldowns.set_policy(&policy(CooldownBucket::User, 0)).await;
+
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3))
+        .await
+        .is_none());
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3))
+        .await
+        .is_none());
+
+    cooldowns
+        .remove_policy(GUILD_ID, "cat", CooldownBucket::User)
+        .await;
+
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3))
+        .await
+        .is_none());
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3))
+        .await
+        .is_some());
+}
+
+#[tokio::test]
+async fn guild_bucket_applies_to_everyone() {
+    let (_, cooldowns) = cooldowns();
+    cooldowns
+        .set_policy(&policy(CooldownBucket::Guild, 10))
+        .await;
+
+    let defaults = CooldownConfig::default();
+    assert!(cooldowns.hit(&invocation(1), &defaults).await.is_none());
+    assert!(cooldowns.hit(&invocation(2), &defaults).await.is_some());
+}
+
+#[tokio::test]
+async fn long_cooldowns_survive_restarts() {
+    let (db, cooldowns) = cooldowns();
+
+    assert!(cooldowns
+        .hit(&invocation(1), &user_cooldown(3600))
+        .await
+        .is_none());
+    assert!(cooldowns
+        .hit(&invocation(2), &user_cooldown(3))
+        .await
+        .is_none());
+    assert_eq!(db.cooldowns.lock().unwrap().len(), 1);
+
+    let restarted = Cooldowns::new(db, Duration::from_mins(1));
+
+    assert!(restarted
+        .hit(&invocation(1), &user_cooldown(3600))
+        .await
+        .is_some());
+    assert!(restarted
+        .hit(&invocation(2), &user_cooldown(3))
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn only_one_of_simultaneous_uses_gets_through() {
+    let (_, cooldowns) = cooldowns();
+    let (user, defaults) = (invocation(1), user_cooldown(3600));
+
+    let (first, second) = tokio::join!(
+        cooldowns.hit(&user, &defaults),
+        cooldowns.hit(&user, &defaults)
+    );
+    assert!(first.is_none() != second.is_none());
+}
+
+#[sqlx::test]
+async fn expired_cooldowns_are_cleaned_up(pool: PgPool) {
+    let db = PostgresDatabase::new(pool.clone());
+    let now = OffsetDateTime::now_utc();
+    let key = |target_id| CooldownKey {
+        scope_id: None,
+        command: String::from("cat"),
+        bucket: CooldownBucket::User,
+        target_id,
+    };
+
+    db.set_cooldown(&key(1), now - Duration::from_mins(1)).await;
+    db.set_cooldown(&key(2), now + Duration::from_mins(1)).await;
+    db.delete_expired_cooldowns().await;
+
+    let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cooldowns")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(left, 1);
+    assert!(db.get_cooldown(&key(2)).await.is_some());
+}
@@ -0,0 +1,75 @@
+use crate::{
+    commands::config::parse_color,
+    database::{GuildRepository, PostgresDatabase, SettingsRepository},
+    embeds::{EmbedKind, DEFAULT_ERROR, DEFAULT_PRIMARY},
+    structs::{Guild, Theme},
+};
+use poise::serenity_prelude::{Color, GuildId};
+use serde_json::Value;
+use sqlx::PgPool;
+
+fn render(theme: &Theme, kind: EmbedKind) -> Value {
+    serde_json::to_value(theme.embed(kind)).unwrap()
+}
+
+#[test]
+fn default_theme_matches_previous_colors() {
+    let theme = Theme::default();
+
+    assert_eq!(
+        render(&theme, EmbedKind::Primary)["color"],
+        DEFAULT_PRIMARY.0
+    );
+    assert_eq!(render(&theme, EmbedKind::Error)["color"], DEFAULT_ERROR.0);
+    assert!(render(&theme, EmbedKind::Primary)["timestamp"].is_string());
+    assert!(render(&theme, EmbedKind::Primary).get("footer").is_none());
+}
+
+#[test]
+fn custom_theme_is_applied() {
+    let theme = Theme {
+        success: Some(Color::new(0x0011_2233)),
+        footer_text: Some(String::from("Powered by Rusty")),
+        footer_icon: Some(String::from("https://example.com/icon.png")),
+        timestamps: false,
+        ..Theme::default()
+    };
+
+    let embed = render(&theme, EmbedKind::Success);
+
+    assert_eq!(embed["color"], 0x0011_2233);
+    assert_eq!(embed["footer"]["text"], "Powered by Rusty");
+    assert_eq!(embed["footer"]["icon_url"], "https://example.com/icon.png");
+    assert!(embed.get("timestamp").is_none());
+}
+
+#[test]
+fn parses_hex_colors() {
+    assert_eq!(parse_color("#5865F2"), Some(Color::new(0x0058_65F2)));
+    assert_eq!(parse_color("5865f2"), Some(Color::new(0x0058_65F2)));
+    assert_eq!(parse_color("blue"), None);
+    assert_eq!(parse_color("#FFF"), None);
+}
+
+#[sqlx::test]
+async fn theme_round_trips_through_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    let guild_id = GuildId::new(100);
+    db.insert_guild(&Guild::new(guild_id)).await;
+
+    assert_eq!(db.get_settings(guild_id).await.theme, Theme::default());
+
+    let theme = Theme {
+        primary: Some(Color::new(0x00FF_FFFF)),
+        footer_text: Some(String::from("footer")),
+        timestamps: false,
+        ..Theme::default()
+    };
+    db.update_settings(&Guild {
+        theme: theme.clone(),
+        ..Guild::new(guild_id)
+    })
+    .await;
+
+    assert_eq!(db.get_settings(guild_id).await.theme, theme);
+}
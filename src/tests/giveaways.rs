@@ -0,0 +1,300 @@
+use super::{from_now, mock_discord::MockDiscord};
+use crate::{
+    database::{memory::MemoryDatabase, GiveawayRepository, PostgresDatabase, SettingsRepository},
+    giveaways::{check_requirements, draw, end, end_due, reroll, Unmet},
+    i18n::Locales,
+    structs::{Giveaway, Guild, ShardConfig},
+};
+use axum::http::Method;
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use sqlx::{types::time::OffsetDateTime, PgPool};
+use std::{sync::Arc, time::Duration};
+
+const GUILD_ID: GuildId = GuildId::new(100);
+const CHANNEL_ID: u64 = 500;
+const MESSAGE_ID: u64 = 600;
+
+/// A giveaway ending `ends_in` seconds from now, negative if it's overdue.
+fn giveaway(message_id: u64, ends_in: i64) -> Giveaway {
+    Giveaway {
+        id: 0,
+        guild_id: GUILD_ID,
+        channel_id: ChannelId::new(CHANNEL_ID),
+        message_id: MessageId::new(message_id),
+        host_id: UserId::new(200),
+        prize: String::from("Nitro"),
+        winner_count: 2,
+        required_role_id: None,
+        min_account_age: None,
+        ends_at: from_now(ends_in),
+        ended: false,
+        seed: None,
+        winner_ids: Vec::new(),
+    }
+}
+
+fn users(ids: impl IntoIterator<Item = u64>) -> Vec<UserId> {
+    ids.into_iter().map(UserId::new).collect()
+}
+
+#[test]
+fn draws_are_reproducible() {
+    let entrants = users(1..=50);
+    let winners = draw(&entrants, &[], 3, 42);
+
+    assert_eq!(winners.len(), 3);
+    assert!(winners.iter().all(|w| entrants.contains(w)));
+
+    // The order entries came in doesn't matter, only the seed does
+    let mut shuffled = entrants.clone();
+    shuffled.reverse();
+    assert_eq!(draw(&shuffled, &[], 3, 42), winners);
+    assert_ne!(draw(&entrants, &[], 3, 43), winners);
+}
+
+#[test]
+fn draws_skip_excluded_and_duplicate_entrants() {
+    let entrants = users([1, 2, 2, 3]);
+
+    let mut winners = draw(&entrants, &users([2]), 5, 7);
+    winners.sort_unstable();
+    assert_eq!(winners, users([1, 3]));
+
+    assert!(draw(&entrants, &users([1, 2, 3]), 1, 7).is_empty());
+    assert!(draw(&[], &[], 1, 7).is_empty());
+}
+
+#[test]
+fn draws_are_fair() {
+    // Every entrant should win about as often as the others
+    let entrants = users(1..=4);
+    let mut wins = [0; 4];
+    for seed in 0..4000 {
+        let winner = draw(&entrants, &[], 1, seed)[0];
+        wins[usize::try_from(winner.get() - 1).unwrap()] += 1;
+    }
+
+    assert!(wins.iter().all(|&w| (800..1200).contains(&w)), "{wins:?}");
+}
+
+#[test]
+fn checks_entry_requirements() {
+    let now = OffsetDateTime::now_utc();
+    let role_id = RoleId::new(300);
+    let giveaway = Giveaway {
+        required_role_id: Some(role_id),
+        min_account_age: Some(Duration::from_hours(24 * 7)),
+        ..giveaway(MESSAGE_ID, 60)
+    };
+    let old = now - Duration::from_hours(24 * 30);
+    let new = now - Duration::from_hours(24);
+
+    assert_eq!(check_requirements(&giveaway, &[role_id], old, now), None);
+    assert_eq!(
+        check_requirements(&giveaway, &[], old, now),
+        Some(Unmet::Role(role_id))
+    );
+    assert_eq!(
+        check_requirements(&giveaway, &[role_id], new, now),
+        Some(Unmet::AccountAge(Duration::from_hours(24 * 7)))
+    );
+}
+
+#[tokio::test]
+async fn due_giveaways_are_ended_once() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    let id = db.create_giveaway(&giveaway(MESSAGE_ID, -5)).await.unwrap();
+    let later = giveaway(MESSAGE_ID + 1, 30);
+    db.create_giveaway(&later).await;
+    for user_id in users(1..=3) {
+        db.add_giveaway_entry(id, user_id).await;
+    }
+
+    let locales = Arc::new(Locales::bundled());
+    let next = end_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &ShardConfig::Auto,
+        Duration::from_mins(1),
+    )
+    .await;
+    assert_eq!(next, Some(later.ends_at));
+
+    let ended = db.get_giveaway(GUILD_ID, id).await.unwrap();
+    assert!(ended.ended);
+    assert_eq!(ended.winner_ids.len(), 2);
+    assert_eq!(
+        draw(&users(1..=3), &[], 2, ended.seed.unwrap()),
+        ended.winner_ids
+    );
+
+    let edit = mock
+        .find(
+            &Method::PATCH,
+            &format!("/channels/{CHANNEL_ID}/messages/{MESSAGE_ID}"),
+        )
+        .expect("giveaway was not updated");
+    assert!(edit.body.contains(r#""components":[]"#));
+    let announcement = mock
+        .find(&Method::POST, &format!("/channels/{CHANNEL_ID}/messages"))
+        .expect("winners were not announced");
+    assert!(announcement.body.contains("Congratulations"));
+
+    // Ending it again, say by hand, doesn't draw again
+    let requests = mock.requests().len();
+    assert!(!end(&mock.http(), &db, &locales, &ended).await);
+    assert_eq!(mock.requests().len(), requests);
+    assert_eq!(db.giveaway_draws.lock().unwrap().len(), 1);
+
+    // Entries are closed along with the giveaway
+    assert!(!db.add_giveaway_entry(id, UserId::new(4)).await);
+    assert!(!db.remove_giveaway_entry(id, UserId::new(1)).await);
+}
+
+#[tokio::test]
+async fn rerolls_draw_new_winners() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    let id = db.create_giveaway(&giveaway(MESSAGE_ID, -5)).await.unwrap();
+    for user_id in users(1..=3) {
+        db.add_giveaway_entry(id, user_id).await;
+    }
+
+    let locales = Arc::new(Locales::bundled());
+    let ended = db.get_giveaway(GUILD_ID, id).await.unwrap();
+    assert!(end(&mock.http(), &db, &locales, &ended).await);
+    let ended = db.get_giveaway(GUILD_ID, id).await.unwrap();
+
+    let drawn = reroll(&mock.http(), &db, &locales, &ended, 5).await;
+    assert_eq!(drawn.len(), 1);
+    assert!(!ended.winner_ids.contains(&drawn[0]));
+
+    let rerolled = db.get_giveaway(GUILD_ID, id).await.unwrap();
+    assert_eq!(rerolled.winner_ids.len(), 3);
+
+    // The giveaway's message lists the new winner too
+    let edit = mock
+        .requests()
+        .into_iter()
+        .rfind(|r| r.method == Method::PATCH)
+        .expect("giveaway was not updated");
+    assert!(edit.body.contains(&format!("<@{}>", drawn[0])));
+
+    // Both draws are kept, the reroll with the winners it left out
+    let stored = db.giveaway_draws.lock().unwrap().clone();
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[1].excluded_ids, ended.winner_ids);
+    assert_eq!(stored[1].winner_ids, drawn);
+    assert_eq!(Some(stored[1].seed), rerolled.seed);
+
+    // Everyone has won now
+    assert!(reroll(&mock.http(), &db, &locales, &rerolled, 1)
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn giveaways_of_other_processes_are_left_alone() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.create_giveaway(&giveaway(MESSAGE_ID, -5)).await;
+
+    let shard_config = ShardConfig::Range {
+        first: 1,
+        last: 1,
+        total: 2,
+    };
+    assert!(!shard_config.owns_guild(GUILD_ID));
+
+    let locales = Arc::new(Locales::bundled());
+    end_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &shard_config,
+        Duration::from_mins(1),
+    )
+    .await;
+
+    assert!(mock.requests().is_empty());
+    assert!(!db.giveaways.lock().unwrap()[0].ended);
+}
+
+#[sqlx::test]
+async fn giveaways_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool.clone());
+    db.update_settings(&Guild::new(GUILD_ID)).await;
+
+    let due = Giveaway {
+        required_role_id: Some(RoleId::new(300)),
+        min_account_age: Some(Duration::from_hours(24)),
+        ..giveaway(MESSAGE_ID, -5)
+    };
+    let id = db.create_giveaway(&due).await.unwrap();
+    db.create_giveaway(&giveaway(MESSAGE_ID + 1, 60 * 60))
+        .await
+        .unwrap();
+    assert_eq!(db.create_giveaway(&due).await, None);
+
+    let stored = db
+        .get_giveaway_by_message(MessageId::new(MESSAGE_ID))
+        .await
+        .unwrap();
+    assert_eq!(stored.id, id);
+    assert_eq!(stored.required_role_id, due.required_role_id);
+    assert_eq!(stored.min_account_age, due.min_account_age);
+    assert_eq!(db.get_giveaways(GUILD_ID).await.len(), 2);
+
+    let ending = db
+        .get_giveaways_ending_before(OffsetDateTime::now_utc())
+        .await;
+    assert_eq!(ending.len(), 1);
+    assert_eq!(ending[0].id, id);
+
+    assert!(db.add_giveaway_entry(id, UserId::new(1)).await);
+    assert!(db.add_giveaway_entry(id, UserId::new(2)).await);
+    assert!(!db.add_giveaway_entry(id, UserId::new(2)).await);
+    assert!(db.remove_giveaway_entry(id, UserId::new(1)).await);
+    assert!(!db.remove_giveaway_entry(id, UserId::new(1)).await);
+    assert_eq!(db.get_giveaway_entries(id).await, users([2]));
+    assert_eq!(db.count_giveaway_entries(id).await, 1);
+
+    // Seeds use all 64 bits
+    let seed = u64::MAX - 1;
+    assert_eq!(
+        db.end_giveaway(id, seed).await,
+        Some((users([2]), users([2])))
+    );
+    assert!(db.end_giveaway(id, 5).await.is_none());
+    assert!(db
+        .get_giveaways_ending_before(OffsetDateTime::now_utc())
+        .await
+        .is_empty());
+
+    // Entries are closed once the giveaway ended
+    assert!(!db.add_giveaway_entry(id, UserId::new(3)).await);
+    assert!(!db.remove_giveaway_entry(id, UserId::new(2)).await);
+    assert_eq!(db.get_giveaway_entries(id).await, users([2]));
+
+    let ended = db.get_giveaway(GUILD_ID, id).await.unwrap();
+    assert!(ended.ended);
+    assert_eq!(ended.seed, Some(seed));
+    assert_eq!(ended.winner_ids, users([2]));
+
+    db.add_giveaway_draw(id, 5, &users([2]), &users([3])).await;
+    let rerolled = db.get_giveaway(GUILD_ID, id).await.unwrap();
+    assert_eq!(rerolled.seed, Some(5));
+    assert_eq!(rerolled.winner_ids, users([2, 3]));
+
+    // Every draw is kept
+    let draws: Vec<(i64, Vec<i64>)> = sqlx::query_as(
+        "SELECT seed, excluded_ids FROM giveaway_draws WHERE giveaway_id = $1 ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(draws, [(seed.cast_signed(), Vec::new()), (5, vec![2])]);
+}
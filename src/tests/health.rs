@@ -0,0 +1,27 @@
+use crate::{health::GatewayStatus, structs::ShardConfig};
+use poise::serenity_prelude::{ConnectionStage, ShardId};
+
+#[test]
+fn shards_count_as_disconnected_until_they_connect() {
+    let shard_config = ShardConfig::Range {
+        first: 2,
+        last: 3,
+        total: 4,
+    };
+    let status = GatewayStatus::new(shard_config.shard_ids());
+    assert!(status.longest_disconnect().is_some());
+
+    status.update(ShardId(2), ConnectionStage::Connected);
+    assert!(status.longest_disconnect().is_some());
+
+    status.update(ShardId(3), ConnectionStage::Connected);
+    assert_eq!(status.longest_disconnect(), None);
+
+    status.update(ShardId(3), ConnectionStage::Resuming);
+    assert!(status.longest_disconnect().is_some());
+}
+
+#[test]
+fn autosharding_expects_shard_zero() {
+    assert_eq!(ShardConfig::Auto.shard_ids(), 0..=0);
+}
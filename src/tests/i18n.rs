@@ -0,0 +1,98 @@
+use crate::{
+    commands::commands,
+    database::{memory::MemoryDatabase, UserSettingsRepository},
+    i18n::{resolve, Lang, Locales},
+    structs::Guild,
+};
+use fluent::fluent_args;
+use poise::serenity_prelude::{GuildId, UserId};
+use std::sync::Arc;
+
+const GUILD_ID: GuildId = GuildId::new(100);
+const USER_ID: UserId = UserId::new(200);
+
+#[test]
+fn formats_arguments_without_isolation_marks() {
+    let lang = Lang::new(Arc::new(Locales::bundled()), "de");
+
+    let title = lang.tr_with(
+        "mod-dm-title",
+        &fluent_args!["action" => "ban", "server" => "Test Guild"],
+    );
+
+    assert_eq!(title, "Du wurdest auf Test Guild gebannt");
+}
+
+#[test]
+fn unknown_locale_uses_default() {
+    let lang = Lang::new(Arc::new(Locales::bundled()), "xx");
+
+    assert_eq!(lang.tr("no-reason"), "No reason provided");
+}
+
+#[tokio::test]
+async fn user_override_wins_over_guild_locale() {
+    let locales = Arc::new(Locales::bundled());
+    let db = MemoryDatabase::default();
+    db.guilds.lock().unwrap().insert(
+        GUILD_ID,
+        Guild {
+            locale: Some(String::from("de")),
+            ..Guild::new(GUILD_ID)
+        },
+    );
+
+    let lang = resolve(&db, locales.clone(), Some(GUILD_ID), Some(USER_ID)).await;
+    assert_eq!(lang.tr("no-reason"), "Kein Grund angegeben");
+
+    db.set_user_locale(USER_ID, Some("en-US")).await;
+
+    let lang = resolve(&db, locales.clone(), Some(GUILD_ID), Some(USER_ID)).await;
+    assert_eq!(lang.tr("no-reason"), "No reason provided");
+
+    let lang = resolve(&db, locales, Some(GUILD_ID), None).await;
+    assert_eq!(lang.tr("no-reason"), "Kein Grund angegeben");
+}
+
+#[test]
+fn commands_are_localized() {
+    let locales = Locales::bundled();
+    let commands = commands(&crate::config::image_providers(), &locales);
+
+    let ban = commands.iter().find(|c| c.name == "ban").unwrap();
+    assert_eq!(ban.name_localizations["de"], "bannen");
+    assert_eq!(ban.parameters[0].name_localizations["de"], "nutzer");
+
+    let config = commands.iter().find(|c| c.name == "config").unwrap();
+    let cooldown = config
+        .subcommands
+        .iter()
+        .find(|c| c.name == "cooldown")
+        .unwrap();
+    assert_eq!(cooldown.name_localizations["de"], "abklingzeit");
+
+    let bucket = cooldown
+        .parameters
+        .iter()
+        .find(|p| p.name == "bucket")
+        .unwrap();
+    assert!(bucket
+        .choices
+        .iter()
+        .all(|c| c.localizations.contains_key("de")));
+}
+
+#[test]
+fn help_categories_have_names() {
+    let locales = Arc::new(Locales::bundled());
+    let commands = commands(&crate::config::image_providers(), &locales);
+
+    for (locale, _) in locales.available() {
+        let lang = Lang::new(locales.clone(), locale);
+
+        for command in &commands {
+            let id = format!("help-category-{}", command.category.as_deref().unwrap());
+            assert_ne!(lang.tr(&id), id, "{id} is missing in {locale}");
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    database::{
+        memory::MemoryDatabase, GuildRepository, LevelRepository, PostgresDatabase,
+        SettingsRepository,
+    },
+    embeds::bar,
+    levels::{progress, rewards_between, LevelUp, Levels, Progress, XP_COOLDOWN},
+    structs::{Guild, LevelReward, MemberXp},
+};
+use poise::serenity_prelude::{ChannelId, GuildId, RoleId, UserId};
+use sqlx::PgPool;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+const GUILD_ID: GuildId = GuildId::new(100);
+const USER_ID: UserId = UserId::new(300);
+
+fn xp(user_id: u64, xp: i64) -> MemberXp {
+    MemberXp {
+        guild_id: GUILD_ID,
+        user_id: UserId::new(user_id),
+        xp,
+    }
+}
+
+fn reward(role_id: u64, level: i32) -> LevelReward {
+    LevelReward {
+        guild_id: GUILD_ID,
+        role_id: RoleId::new(role_id),
+        level,
+    }
+}
+
+#[test]
+fn levels_get_harder_to_reach() {
+    assert_eq!(
+        progress(0),
+        Progress {
+            level: 0,
+            xp: 0,
+            needed: 100
+        }
+    );
+    assert_eq!(progress(99).level, 0);
+    assert_eq!(
+        progress(100),
+        Progress {
+            level: 1,
+            xp: 0,
+            needed: 155
+        }
+    );
+    assert_eq!(progress(300).level, 2);
+    assert_eq!(progress(-5).level, 0);
+}
+
+#[test]
+fn rewards_are_given_for_levels_reached() {
+    let rewards = [reward(1, 5), reward(2, 10), reward(3, 10), reward(4, 20)];
+
+    assert_eq!(
+        rewards_between(&rewards, 4, 10),
+        [RoleId::new(1), RoleId::new(2), RoleId::new(3)]
+    );
+    assert!(rewards_between(&rewards, 5, 9).is_empty());
+    assert_eq!(rewards_between(&rewards, 10, 25), [RoleId::new(4)]);
+}
+
+#[test]
+fn bars_are_filled_proportionally() {
+    assert_eq!(bar(0, 100, 4), "░░░░");
+    assert_eq!(bar(50, 100, 4), "██░░");
+    assert_eq!(bar(150, 100, 4), "████");
+    assert_eq!(bar(5, 0, 4), "░░░░");
+}
+
+#[test]
+fn xp_is_given_once_per_cooldown() {
+    let levels = Levels::new(Arc::new(MemoryDatabase::default()));
+    let start = Instant::now();
+    let other_guild = GuildId::new(200);
+
+    assert!(!levels.on_cooldown(GUILD_ID, USER_ID, start));
+    assert!(levels.gain(GUILD_ID, USER_ID, 20, start));
+    assert!(levels.on_cooldown(GUILD_ID, USER_ID, start + Duration::from_secs(30)));
+    assert!(!levels.gain(GUILD_ID, USER_ID, 20, start + Duration::from_secs(30)));
+    assert!(levels.gain(other_guild, USER_ID, 15, start + Duration::from_secs(30)));
+    assert!(!levels.on_cooldown(GUILD_ID, USER_ID, start + XP_COOLDOWN));
+    assert!(levels.gain(GUILD_ID, USER_ID, 25, start + XP_COOLDOWN));
+
+    assert_eq!(levels.pending(GUILD_ID, USER_ID), 45);
+    assert_eq!(levels.pending(other_guild, USER_ID), 15);
+}
+
+#[tokio::test]
+async fn flushes_write_pending_xp_and_report_level_ups() {
+    let db = Arc::new(MemoryDatabase::default());
+    db.levels.lock().unwrap().insert((GUILD_ID, USER_ID), 90);
+    let levels = Levels::new(db.clone());
+    let now = Instant::now();
+
+    levels.gain(GUILD_ID, USER_ID, 20, now);
+    levels.gain(GUILD_ID, UserId::new(301), 20, now);
+
+    assert_eq!(
+        levels.flush(now).await,
+        [LevelUp {
+            guild_id: GUILD_ID,
+            user_id: USER_ID,
+            from: 0,
+            to: 1,
+        }]
+    );
+    assert_eq!(levels.pending(GUILD_ID, USER_ID), 0);
+    assert_eq!(db.get_xp(GUILD_ID, USER_ID).await, 110);
+    assert_eq!(db.get_xp(GUILD_ID, UserId::new(301)).await, 20);
+
+    assert!(levels.flush(now).await.is_empty());
+}
+
+#[sqlx::test]
+async fn levels_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+
+    // Guilds are created as XP comes in
+    let totals = db.add_xp(&[xp(1, 20), xp(2, 50)]).await.unwrap();
+    assert_eq!(totals.len(), 2);
+    assert!(db.get_guild(GUILD_ID).await.unwrap().is_some());
+
+    let totals = db.add_xp(&[xp(1, 40), xp(3, 10)]).await.unwrap();
+    assert!(totals.contains(&xp(1, 60)));
+    assert!(totals.contains(&xp(3, 10)));
+
+    assert_eq!(db.get_xp(GUILD_ID, UserId::new(1)).await, 60);
+    assert_eq!(db.get_xp(GUILD_ID, UserId::new(4)).await, 0);
+    assert_eq!(db.count_above(GUILD_ID, 50).await, 1);
+    assert_eq!(db.count_ranked(GUILD_ID).await, 3);
+
+    assert_eq!(
+        db.get_leaderboard(GUILD_ID, 0, 2).await,
+        [xp(1, 60), xp(2, 50)]
+    );
+    assert_eq!(db.get_leaderboard(GUILD_ID, 2, 2).await, [xp(3, 10)]);
+
+    db.set_level_reward(&reward(10, 5)).await;
+    db.set_level_reward(&reward(20, 1)).await;
+    db.set_level_reward(&reward(10, 3)).await;
+    assert_eq!(
+        db.get_level_rewards(GUILD_ID).await,
+        [reward(20, 1), reward(10, 3)]
+    );
+
+    assert!(db.remove_level_reward(GUILD_ID, RoleId::new(20)).await);
+    assert!(!db.remove_level_reward(GUILD_ID, RoleId::new(20)).await);
+    assert_eq!(db.get_level_rewards(GUILD_ID).await, [reward(10, 3)]);
+}
+
+#[sqlx::test]
+async fn channels_without_xp_are_stored_in_settings(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    let channels = vec![ChannelId::new(500), ChannelId::new(501)];
+
+    db.update_settings(&Guild {
+        no_xp_channels: channels.clone(),
+        ..Guild::new(GUILD_ID)
+    })
+    .await;
+
+    assert_eq!(db.get_settings(GUILD_ID).await.no_xp_channels, channels);
+}
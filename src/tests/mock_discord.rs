@@ -0,0 +1,154 @@
+use axum::{
+    extract::State,
+    http::{header, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use poise::serenity_prelude::{Http, HttpBuilder};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+pub const DM_CHANNEL_ID: u64 = 900;
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Clone, Default)]
+struct MockState {
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    failures: Arc<Mutex<Vec<(Method, String, StatusCode)>>>,
+}
+
+/// A local stand-in for Discord's REST API. Every request is recorded, and the
+/// handful of endpoints whose responses serenity parses get a minimal valid body.
+pub struct MockDiscord {
+    url: String,
+    state: MockState,
+}
+
+impl MockDiscord {
+    pub async fn start() -> Self {
+        let state = MockState::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        Self { url, state }
+    }
+
+    /// Makes every later request to `path` fail with `status`.
+    pub fn fail(&self, method: Method, path: &str, status: StatusCode) {
+        self.state
+            .failures
+            .lock()
+            .unwrap()
+            .push((method, format!("/api/v10{path}"), status));
+    }
+
+    pub fn http(&self) -> Http {
+        HttpBuilder::new("test-token")
+            .proxy(self.url.as_str())
+            .ratelimiter_disabled(true)
+            .build()
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    pub fn find(&self, method: &Method, path: &str) -> Option<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .find(|r| r.method == method && r.path == format!("/api/v10{path}"))
+    }
+}
+
+async fn handle(
+    State(state): State<MockState>,
+    method: Method,
+    uri: Uri,
+    body: String,
+) -> Response {
+    let path = uri.path().to_string();
+
+    state.requests.lock().unwrap().push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body,
+    });
+
+    let failure = state
+        .failures
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(m, p, _)| *m == method && *p == path)
+        .map(|(_, _, status)| *status);
+    if let Some(status) = failure {
+        return (
+            status,
+            json(String::from(r#"{"code":0,"message":"Mock failure"}"#)),
+        )
+            .into_response();
+    }
+
+    if method == Method::POST && path == "/api/v10/users/@me/channels" {
+        return json(dm_channel());
+    }
+
+    if (method == Method::POST && path.ends_with("/messages"))
+        || (method == Method::PATCH && path.contains("/messages/"))
+    {
+        let channel_id = path.split('/').nth(4).unwrap_or_default();
+        return json(message(channel_id));
+    }
+
+    if method == Method::GET && path.starts_with("/api/v10/users/") {
+        let user_id = path.rsplit('/').next().unwrap_or_default();
+        return json(user(user_id.parse().unwrap_or(1)));
+    }
+
+    if method == Method::PATCH && path.contains("/members/") {
+        let user_id = path.rsplit('/').next().unwrap_or_default();
+        return json(member(user_id));
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+fn json(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+fn user(id: u64) -> String {
+    format!(
+        r#"{{"id":"{id}","username":"user{id}","discriminator":"0","global_name":null,"avatar":null}}"#
+    )
+}
+
+fn member(user_id: &str) -> String {
+    format!(
+        r#"{{"user":{},"roles":[],"joined_at":"2024-01-01T00:00:00+00:00","deaf":false,"mute":false,"flags":0}}"#,
+        user(user_id.parse().unwrap_or(1))
+    )
+}
+
+fn dm_channel() -> String {
+    format!(
+        r#"{{"id":"{DM_CHANNEL_ID}","type":1,"last_message_id":null,"recipients":[{}]}}"#,
+        user(1)
+    )
+}
+
+fn message(channel_id: &str) -> String {
+    format!(
+        r#"{{"id":"1","channel_id":"{channel_id}","author":{},"content":"","timestamp":"2024-01-01T00:00:00+00:00","edited_timestamp":null,"tts":false,"mention_everyone":false,"mentions":[],"mention_roles":[],"attachments":[],"embeds":[],"pinned":false,"type":0}}"#,
+        user(2)
+    )
+}
@@ -0,0 +1,33 @@
+mod cdn;
+mod cooldowns;
+mod embeds;
+mod giveaways;
+mod health;
+mod i18n;
+mod levels;
+mod mock_discord;
+mod moderation;
+mod permissions;
+mod polls;
+mod prefixes;
+mod providers;
+mod reminders;
+mod role_panels;
+mod settings_cache;
+mod starboard;
+mod tags;
+
+use sqlx::types::time::OffsetDateTime;
+use std::time::Duration;
+
+/// The time `secs` seconds from now, in the past if it's negative.
+fn from_now(secs: i64) -> OffsetDateTime {
+    let now = OffsetDateTime::now_utc();
+    let offset = Duration::from_secs(secs.unsigned_abs());
+
+    if secs < 0 {
+        now - offset
+    } else {
+        now + offset
+    }
+}
@@ -0,0 +1,186 @@
+use super::mock_discord::{MockDiscord, DM_CHANNEL_ID};
+use crate::{
+    database::{memory::MemoryDatabase, Database, InfractionRepository, PostgresDatabase},
+    i18n::Locales,
+    modlog,
+    shutdown::Shutdown,
+    structs::{Guild, InfractionType},
+    utils::{apply_moderation, ModAction},
+};
+use axum::http::Method;
+use poise::serenity_prelude::{ChannelId, GuildId, User, UserId};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+const GUILD_ID: u64 = 100;
+const MODERATOR_ID: u64 = 200;
+const TARGET_ID: u64 = 300;
+const MODLOG_ID: u64 = 400;
+
+fn target(bot: bool) -> User {
+    let mut user = User::default();
+    user.id = UserId::new(TARGET_ID);
+    user.name = String::from("target");
+    user.bot = bot;
+    user
+}
+
+fn with_modlog(db: MemoryDatabase) -> MemoryDatabase {
+    db.guilds.lock().unwrap().insert(
+        GuildId::new(GUILD_ID),
+        Guild {
+            mod_id: Some(ChannelId::new(MODLOG_ID)),
+            ..Guild::new(GuildId::new(GUILD_ID))
+        },
+    );
+    db
+}
+
+/// Runs a moderation action against the mock and waits for queued modlog posts.
+async fn run(mock: &MockDiscord, db: &dyn Database, kind: InfractionType, user: &User) {
+    let http = Arc::new(mock.http());
+    let shutdown = Shutdown::new();
+    let queue = modlog::spawn(http.clone(), &shutdown);
+
+    let action = ModAction {
+        guild_id: GuildId::new(GUILD_ID),
+        guild_name: "Test Guild",
+        moderator_id: UserId::new(MODERATOR_ID),
+        target: user,
+        reason: "spam",
+        kind,
+        duration: matches!(kind, InfractionType::Mute).then(|| Duration::from_mins(10)),
+    };
+
+    let locales = Arc::new(Locales::bundled());
+
+    apply_moderation(&http, db, &locales, &queue, &action)
+        .await
+        .unwrap();
+
+    shutdown.drain(Duration::from_secs(5)).await;
+}
+
+#[tokio::test]
+async fn ban_dms_bans_and_posts_to_modlog() {
+    let mock = MockDiscord::start().await;
+    let db = with_modlog(MemoryDatabase::default());
+
+    run(&mock, &db, InfractionType::Ban, &target(false)).await;
+
+    let dm = mock
+        .find(
+            &Method::POST,
+            &format!("/channels/{DM_CHANNEL_ID}/messages"),
+        )
+        .expect("target was not DMed");
+    assert!(dm.body.contains("You have been banned from Test Guild"));
+
+    assert!(mock
+        .find(
+            &Method::PUT,
+            &format!("/guilds/{GUILD_ID}/bans/{TARGET_ID}")
+        )
+        .is_some());
+
+    let post = mock
+        .find(&Method::POST, &format!("/channels/{MODLOG_ID}/messages"))
+        .expect("modlog post was not sent");
+    assert!(post.body.contains("Banned target"));
+
+    let infractions = db.infractions.lock().unwrap();
+    assert_eq!(infractions.len(), 1);
+    assert_eq!(infractions[0].member_id, UserId::new(TARGET_ID));
+    assert_eq!(infractions[0].moderator_id, UserId::new(MODERATOR_ID));
+    assert_eq!(infractions[0].reason, "spam");
+}
+
+#[tokio::test]
+async fn kick_removes_member() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+
+    run(&mock, &db, InfractionType::Kick, &target(false)).await;
+
+    assert!(mock
+        .find(
+            &Method::DELETE,
+            &format!("/guilds/{GUILD_ID}/members/{TARGET_ID}")
+        )
+        .is_some());
+    assert!(mock
+        .find(
+            &Method::PUT,
+            &format!("/guilds/{GUILD_ID}/bans/{TARGET_ID}")
+        )
+        .is_none());
+}
+
+#[tokio::test]
+async fn mute_times_out_member_without_removing_them() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+
+    run(&mock, &db, InfractionType::Mute, &target(false)).await;
+
+    let timeout = mock
+        .find(
+            &Method::PATCH,
+            &format!("/guilds/{GUILD_ID}/members/{TARGET_ID}"),
+        )
+        .expect("member was not timed out");
+    assert!(timeout.body.contains("communication_disabled_until"));
+
+    assert!(mock
+        .requests()
+        .iter()
+        .all(|r| r.method != Method::PUT && r.method != Method::DELETE));
+
+    let infractions = db.infractions.lock().unwrap();
+    assert!(matches!(
+        infractions[0].infraction_type,
+        InfractionType::Mute
+    ));
+}
+
+#[tokio::test]
+async fn bots_are_not_dmed() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+
+    run(&mock, &db, InfractionType::Kick, &target(true)).await;
+
+    assert!(mock.find(&Method::POST, "/users/@me/channels").is_none());
+}
+
+#[tokio::test]
+async fn no_modlog_post_without_modlog_channel() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+
+    run(&mock, &db, InfractionType::Ban, &target(false)).await;
+
+    assert!(mock
+        .find(&Method::POST, &format!("/channels/{MODLOG_ID}/messages"))
+        .is_none());
+    assert!(db
+        .guilds
+        .lock()
+        .unwrap()
+        .contains_key(&GuildId::new(GUILD_ID)));
+}
+
+#[sqlx::test]
+async fn ban_writes_infraction_to_postgres(pool: PgPool) {
+    let mock = MockDiscord::start().await;
+    let db = PostgresDatabase::new(pool);
+
+    run(&mock, &db, InfractionType::Ban, &target(false)).await;
+
+    let infractions = db
+        .get_infractions(GuildId::new(GUILD_ID), UserId::new(TARGET_ID))
+        .await;
+    assert_eq!(infractions.len(), 1);
+    assert_eq!(infractions[0].reason, "spam");
+    assert!(infractions[0].created_at.is_some());
+}
@@ -0,0 +1,132 @@
+use crate::{
+    commands::commands,
+    database::memory::MemoryDatabase,
+    i18n::Locales,
+    permissions::{
+        default_permissions, evaluate, rules_channel, Decision, Invoker, PermissionOverrides,
+    },
+    structs::{PermissionOverride, PermissionTarget},
+};
+use poise::serenity_prelude::{ChannelId, GuildChannel, GuildId, Permissions, RoleId, UserId};
+use std::sync::Arc;
+
+const GUILD_ID: GuildId = GuildId::new(100);
+const CHANNEL_ID: ChannelId = ChannelId::new(10);
+const USER_ID: UserId = UserId::new(1);
+const ROLES: [RoleId; 2] = [RoleId::new(20), RoleId::new(21)];
+
+fn rule(
+    command: &str,
+    target: PermissionTarget,
+    target_id: u64,
+    allow: bool,
+) -> PermissionOverride {
+    PermissionOverride {
+        guild_id: GUILD_ID,
+        command: String::from(command),
+        target,
+        target_id,
+        allow,
+    }
+}
+
+fn invoker() -> Invoker<'static> {
+    Invoker {
+        user_id: USER_ID,
+        roles: &ROLES,
+        channel_id: CHANNEL_ID,
+    }
+}
+
+#[test]
+fn channel_deny_beats_everything() {
+    let rules = [
+        rule("cat", PermissionTarget::Channel, CHANNEL_ID.get(), false),
+        rule("cat", PermissionTarget::User, USER_ID.get(), true),
+        rule("cat", PermissionTarget::Role, ROLES[0].get(), true),
+    ];
+
+    assert_eq!(evaluate(&rules, &["cat"], &invoker()), Decision::Deny);
+}
+
+#[test]
+fn user_rule_beats_roles_and_any_role_allow_beats_deny() {
+    let rules = [
+        rule("cat", PermissionTarget::User, USER_ID.get(), false),
+        rule("cat", PermissionTarget::Role, ROLES[0].get(), true),
+    ];
+    assert_eq!(evaluate(&rules, &["cat"], &invoker()), Decision::Deny);
+
+    let rules = [
+        rule("cat", PermissionTarget::Role, ROLES[0].get(), false),
+        rule("cat", PermissionTarget::Role, ROLES[1].get(), true),
+    ];
+    assert_eq!(evaluate(&rules, &["cat"], &invoker()), Decision::Allow);
+}
+
+#[test]
+fn subcommands_fall_back_to_parent_rules() {
+    let rules = [
+        rule("config", PermissionTarget::Role, ROLES[0].get(), true),
+        rule("config theme", PermissionTarget::User, USER_ID.get(), false),
+    ];
+
+    assert_eq!(
+        evaluate(&rules, &["config show", "config"], &invoker()),
+        Decision::Allow
+    );
+    assert_eq!(
+        evaluate(
+            &rules,
+            &["config theme set", "config theme", "config"],
+            &invoker()
+        ),
+        Decision::Deny
+    );
+    assert_eq!(evaluate(&rules, &["cat"], &invoker()), Decision::Default);
+}
+
+#[test]
+fn required_permissions_become_defaults() {
+    let commands = commands(&[], &Locales::bundled());
+    let ban = commands.iter().find(|c| c.name == "ban").unwrap();
+
+    assert!(ban.required_permissions.is_empty());
+    assert_eq!(default_permissions(ban), Permissions::BAN_MEMBERS);
+}
+
+#[tokio::test]
+async fn rules_are_cached_until_changed() {
+    let db = Arc::new(MemoryDatabase::default());
+    let overrides = PermissionOverrides::new(db.clone());
+    assert!(overrides.rules(GUILD_ID).await.is_empty());
+
+    let deny = rule("cat", PermissionTarget::Channel, CHANNEL_ID.get(), false);
+    overrides.set_rule(&deny).await;
+    assert_eq!(overrides.rules(GUILD_ID).await, vec![deny]);
+
+    overrides
+        .remove_rule(GUILD_ID, "cat", PermissionTarget::Channel, CHANNEL_ID.get())
+        .await;
+    assert!(overrides.rules(GUILD_ID).await.is_empty());
+}
+
+#[test]
+fn threads_use_their_parent_channels_rules() {
+    let channel = |kind: u8, id: u64| -> GuildChannel {
+        serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "type": kind,
+            "guild_id": GUILD_ID.to_string(),
+            "parent_id": CHANNEL_ID.to_string(),
+            "name": "channel",
+            "position": 0,
+        }))
+        .unwrap()
+    };
+
+    // A text channel inside a category keeps its own rules
+    assert_eq!(rules_channel(&channel(0, 11)), ChannelId::new(11));
+    assert_eq!(rules_channel(&channel(11, 12)), CHANNEL_ID);
+    assert_eq!(rules_channel(&channel(12, 13)), CHANNEL_ID);
+}
@@ -0,0 +1,337 @@
+use super::{from_now, mock_discord::MockDiscord};
+use crate::{
+    database::{memory::MemoryDatabase, PollRepository, PostgresDatabase, SettingsRepository},
+    i18n::{Lang, Locales},
+    polls::{close_due, parse_custom_id, parse_options, results_embed, tally, toggle},
+    structs::{Guild, Poll, PollVote, ShardConfig, Theme},
+};
+use axum::http::{Method, StatusCode};
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, UserId};
+use sqlx::{types::time::OffsetDateTime, PgPool};
+use std::{sync::Arc, time::Duration};
+
+const GUILD_ID: GuildId = GuildId::new(100);
+const CHANNEL_ID: u64 = 500;
+const MESSAGE_ID: u64 = 600;
+
+/// A poll closing `closes_in` seconds from now, negative if it's overdue.
+fn poll(message_id: u64, closes_in: i64) -> Poll {
+    Poll {
+        message_id: MessageId::new(message_id),
+        guild_id: GUILD_ID,
+        channel_id: ChannelId::new(CHANNEL_ID),
+        user_id: UserId::new(200),
+        question: String::from("Lunch?"),
+        options: vec![String::from("Pizza"), String::from("Pasta")],
+        multi_choice: false,
+        anonymous: false,
+        closes_at: from_now(closes_in),
+    }
+}
+
+fn vote(user_id: u64, option: i16) -> PollVote {
+    PollVote {
+        user_id: UserId::new(user_id),
+        option,
+    }
+}
+
+#[test]
+fn parses_options() {
+    assert_eq!(
+        parse_options("Pizza | Pasta |  | Salad"),
+        Some(vec![
+            String::from("Pizza"),
+            String::from("Pasta"),
+            String::from("Salad")
+        ])
+    );
+
+    let too_many = (0..11)
+        .map(|i| format!("Option {i}"))
+        .collect::<Vec<_>>()
+        .join("|");
+    let too_long = format!("Pizza | {}", "a".repeat(81));
+
+    for invalid in ["Pizza", "Pizza | pizza", "|", &too_many, &too_long] {
+        assert_eq!(parse_options(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn reads_custom_ids() {
+    assert_eq!(parse_custom_id("poll:3"), Some(3));
+    assert_eq!(parse_custom_id("poll:-1"), None);
+    assert_eq!(parse_custom_id("rolepanel:1:2"), None);
+}
+
+#[test]
+fn clicking_changes_votes() {
+    // Single choice polls move the vote, clicking it again takes it back
+    assert_eq!(toggle(&[], 1, false), [1]);
+    assert_eq!(toggle(&[1], 0, false), [0]);
+    assert!(toggle(&[1], 1, false).is_empty());
+
+    assert_eq!(toggle(&[2], 0, true), [0, 2]);
+    assert_eq!(toggle(&[0, 2], 2, true), [0]);
+}
+
+#[test]
+fn results_count_votes_and_voters() {
+    let poll = poll(MESSAGE_ID, 0);
+    let votes = [vote(1, 0), vote(2, 0), vote(3, 1), vote(4, 7)];
+
+    // Votes for options that don't exist are ignored
+    assert_eq!(tally(&poll, &votes), [2, 1]);
+
+    let lang = Lang::new(Arc::new(Locales::bundled()), "en-US");
+    let embed =
+        serde_json::to_string(&results_embed(&Theme::default(), &lang, &poll, &votes)).unwrap();
+    assert!(embed.contains("🏆 Pizza"));
+    assert!(embed.contains("<@1>, <@2>"));
+
+    let anonymous = Poll {
+        anonymous: true,
+        ..poll
+    };
+    let embed = serde_json::to_string(&results_embed(&Theme::default(), &lang, &anonymous, &votes))
+        .unwrap();
+    assert!(!embed.contains("<@1>"));
+}
+
+#[test]
+fn results_fit_in_an_embed() {
+    let poll = Poll {
+        question: "?".repeat(256),
+        options: (0..10).map(|i| format!("{i}").repeat(80)).collect(),
+        ..poll(MESSAGE_ID, 0)
+    };
+    let votes: Vec<_> = (0..5000)
+        .map(|user_id| vote(10_000_000_000_000_000 + user_id, (user_id % 10) as i16))
+        .collect();
+
+    let lang = Lang::new(Arc::new(Locales::bundled()), "en-US");
+    let embed =
+        serde_json::to_value(results_embed(&Theme::default(), &lang, &poll, &votes)).unwrap();
+    let text = |value: &serde_json::Value| value.as_str().map_or(0, |s| s.chars().count());
+    let fields = embed["fields"].as_array().unwrap();
+    let length = text(&embed["title"])
+        + text(&embed["description"])
+        + text(&embed["author"]["name"])
+        + fields
+            .iter()
+            .map(|field| text(&field["name"]) + text(&field["value"]))
+            .sum::<usize>();
+
+    assert!(length <= 6000, "{length}");
+    assert!(fields.iter().all(|field| text(&field["value"]) <= 1024));
+    // Every option still lists some of its voters
+    assert!(fields
+        .iter()
+        .all(|field| field["value"].as_str().unwrap().contains("<@")));
+}
+
+#[tokio::test]
+async fn due_polls_are_closed_with_results() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.create_poll(&poll(MESSAGE_ID, -5)).await;
+    let later = poll(MESSAGE_ID + 1, 30);
+    db.create_poll(&later).await;
+    db.toggle_poll_vote(MessageId::new(MESSAGE_ID), UserId::new(1), 1)
+        .await;
+
+    let locales = Arc::new(Locales::bundled());
+    let next = close_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &ShardConfig::Auto,
+        Duration::from_mins(1),
+    )
+    .await;
+
+    let edit = mock
+        .find(
+            &Method::PATCH,
+            &format!("/channels/{CHANNEL_ID}/messages/{MESSAGE_ID}"),
+        )
+        .expect("poll was not closed");
+    assert!(edit.body.contains("🏆 Pasta"));
+    assert!(edit.body.contains(r#""components":[]"#));
+
+    assert_eq!(db.get_poll(MessageId::new(MESSAGE_ID)).await, None);
+    assert!(db
+        .get_poll_votes(MessageId::new(MESSAGE_ID))
+        .await
+        .is_empty());
+    assert_eq!(next, Some(later.closes_at));
+}
+
+#[tokio::test]
+async fn polls_are_kept_when_closing_fails() {
+    let mock = MockDiscord::start().await;
+    let path = format!("/channels/{CHANNEL_ID}/messages/{MESSAGE_ID}");
+    mock.fail(Method::PATCH, &path, StatusCode::INTERNAL_SERVER_ERROR);
+    let db = MemoryDatabase::default();
+    db.create_poll(&poll(MESSAGE_ID, -5)).await;
+
+    let locales = Arc::new(Locales::bundled());
+    close_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &ShardConfig::Auto,
+        Duration::from_mins(1),
+    )
+    .await;
+
+    assert!(mock.find(&Method::PATCH, &path).is_some());
+    assert!(db.get_poll(MessageId::new(MESSAGE_ID)).await.is_some());
+}
+
+#[tokio::test]
+async fn polls_whose_message_is_gone_are_dropped() {
+    let mock = MockDiscord::start().await;
+    mock.fail(
+        Method::PATCH,
+        &format!("/channels/{CHANNEL_ID}/messages/{MESSAGE_ID}"),
+        StatusCode::NOT_FOUND,
+    );
+    let db = MemoryDatabase::default();
+    db.create_poll(&poll(MESSAGE_ID, -5)).await;
+
+    let locales = Arc::new(Locales::bundled());
+    close_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &ShardConfig::Auto,
+        Duration::from_mins(1),
+    )
+    .await;
+
+    assert_eq!(db.get_poll(MessageId::new(MESSAGE_ID)).await, None);
+}
+
+#[tokio::test]
+async fn polls_of_other_processes_are_left_alone() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.create_poll(&poll(MESSAGE_ID, -5)).await;
+
+    let shard_config = ShardConfig::Range {
+        first: 1,
+        last: 1,
+        total: 2,
+    };
+    assert!(!shard_config.owns_guild(GUILD_ID));
+
+    let locales = Arc::new(Locales::bundled());
+    close_due(
+        &mock.http(),
+        &db,
+        &locales,
+        &shard_config,
+        Duration::from_mins(1),
+    )
+    .await;
+
+    assert!(mock.requests().is_empty());
+    assert_eq!(db.polls.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn polls_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool.clone());
+    db.update_settings(&Guild::new(GUILD_ID)).await;
+    let message_id = MessageId::new(MESSAGE_ID);
+
+    let due = poll(MESSAGE_ID, -5);
+    assert!(db.create_poll(&due).await);
+    assert!(
+        db.create_poll(&Poll {
+            multi_choice: true,
+            ..poll(MESSAGE_ID + 1, 60 * 60)
+        })
+        .await
+    );
+    assert!(!db.create_poll(&due).await);
+
+    let stored = db.get_poll(message_id).await.unwrap();
+    assert_eq!(stored.options, due.options);
+    assert_eq!(
+        stored.closes_at.unix_timestamp(),
+        due.closes_at.unix_timestamp()
+    );
+
+    let closing = db.get_polls_closing_before(OffsetDateTime::now_utc()).await;
+    assert_eq!(closing.len(), 1);
+    assert_eq!(closing[0].message_id, message_id);
+
+    // Single choice votes move, clicking the same option again takes it back
+    assert_eq!(
+        db.toggle_poll_vote(message_id, UserId::new(1), 0).await,
+        Some(vec![0])
+    );
+    assert_eq!(
+        db.toggle_poll_vote(message_id, UserId::new(1), 1).await,
+        Some(vec![1])
+    );
+    assert_eq!(
+        db.toggle_poll_vote(message_id, UserId::new(2), 0).await,
+        Some(vec![0])
+    );
+    let mut votes = db.get_poll_votes(message_id).await;
+    votes.sort_by_key(|v| v.user_id);
+    assert_eq!(votes, [vote(1, 1), vote(2, 0)]);
+
+    assert_eq!(
+        db.toggle_poll_vote(message_id, UserId::new(2), 0).await,
+        Some(Vec::new())
+    );
+    assert_eq!(db.get_poll_votes(message_id).await, [vote(1, 1)]);
+
+    // Clicks at the same time are applied one after the other
+    let (first, second) = tokio::join!(
+        db.toggle_poll_vote(message_id, UserId::new(3), 0),
+        db.toggle_poll_vote(message_id, UserId::new(3), 1),
+    );
+    assert!(first.is_some() && second.is_some());
+    assert_eq!(
+        db.get_poll_votes(message_id)
+            .await
+            .iter()
+            .filter(|v| v.user_id == UserId::new(3))
+            .count(),
+        1
+    );
+
+    // The schema holds single choice polls to one vote per member too
+    assert!(sqlx::query(
+        "INSERT INTO poll_votes (message_id, user_id, option, multi_choice) VALUES ($1, 1, 0, FALSE)"
+    )
+    .bind(i64::from(message_id))
+    .execute(&pool)
+    .await
+    .is_err());
+
+    let multi_id = MessageId::new(MESSAGE_ID + 1);
+    assert_eq!(
+        db.toggle_poll_vote(multi_id, UserId::new(1), 1).await,
+        Some(vec![1])
+    );
+    assert_eq!(
+        db.toggle_poll_vote(multi_id, UserId::new(1), 0).await,
+        Some(vec![0, 1])
+    );
+    assert_eq!(
+        db.toggle_poll_vote(MessageId::new(MESSAGE_ID + 2), UserId::new(1), 0)
+            .await,
+        None
+    );
+
+    db.delete_poll(message_id).await;
+    assert_eq!(db.get_poll(message_id).await, None);
+    assert!(db.get_poll_votes(message_id).await.is_empty());
+}
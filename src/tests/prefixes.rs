@@ -0,0 +1,28 @@
+use crate::framework::split_prefix;
+use poise::serenity_prelude::UserId;
+
+const BOT_ID: UserId = UserId::new(42);
+
+#[test]
+fn prefix_is_split_from_the_command() {
+    assert_eq!(
+        split_prefix("!ban <@1> spam", "!", None),
+        Some(("!", "ban <@1> spam"))
+    );
+    assert_eq!(split_prefix("?ban", "!", None), None);
+    assert_eq!(split_prefix("r!cat", "r!", None), Some(("r!", "cat")));
+}
+
+#[test]
+fn mentions_only_count_when_enabled() {
+    assert_eq!(
+        split_prefix("<@42> cat", "!", Some(BOT_ID)),
+        Some(("<@42>", "cat"))
+    );
+    assert_eq!(
+        split_prefix("<@!42>  cat", "!", Some(BOT_ID)),
+        Some(("<@!42>", "cat"))
+    );
+    assert_eq!(split_prefix("<@42> cat", "!", None), None);
+    assert_eq!(split_prefix("<@43> cat", "!", Some(BOT_ID)), None);
+}
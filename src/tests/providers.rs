@@ -0,0 +1,218 @@
+use crate::{
+    providers::{
+        CircuitBreaker, GuardedProvider, ImageProvider, JsonImageProvider, ProviderConfig,
+    },
+    structs::Error,
+};
+use axum::{http::StatusCode, routing::get, Json, Router};
+use poise::serenity_prelude::async_trait;
+use serde_json::{json, Value};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::net::TcpListener;
+
+/// Serves `/cats` in thecatapi's shape, `/duck` in random-d.uk's shape, `/flaky`
+/// which fails only the first time, and `/empty` which has no image.
+async fn serve() -> String {
+    let flaky_calls = Arc::new(AtomicU32::new(0));
+
+    let app = Router::new()
+        .route(
+            "/cats",
+            get(|| async { Json(json!([{ "id": "a", "url": "https://img/cat.png" }])) }),
+        )
+        .route(
+            "/duck",
+            get(|| async { Json(json!({ "url": "https://img/duck.gif" })) }),
+        )
+        .route(
+            "/flaky",
+            get(move || async move {
+                if flaky_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(StatusCode::INTERNAL_SERVER_ERROR)
+                } else {
+                    Ok(Json(json!({ "image": "https://img/fox.jpg" })))
+                }
+            }),
+        )
+        .route("/empty", get(|| async { Json(Value::Null) }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    url
+}
+
+fn provider(url: String, pointer: &str, retries: u32) -> JsonImageProvider {
+    JsonImageProvider::new(ProviderConfig {
+        name: String::from("test"),
+        description: String::from("Shows a test image"),
+        title: String::from("Here's a test image"),
+        url,
+        pointer: String::from(pointer),
+        timeout_secs: 5,
+        retries,
+        failure_threshold: 3,
+        open_secs: 30,
+        fallback_size: 50,
+    })
+}
+
+#[tokio::test]
+async fn parses_responses_with_the_configured_pointer() {
+    let base = serve().await;
+    let client = reqwest::Client::new();
+
+    let cat = provider(format!("{base}/cats"), "/0/url", 0);
+    let duck = provider(format!("{base}/duck"), "/url", 0);
+
+    assert_eq!(cat.fetch(&client).await.unwrap(), "https://img/cat.png");
+    assert_eq!(duck.fetch(&client).await.unwrap(), "https://img/duck.gif");
+}
+
+#[tokio::test]
+async fn retries_failed_requests() {
+    let client = reqwest::Client::new();
+
+    let base = serve().await;
+    assert!(provider(format!("{base}/flaky"), "/image", 0)
+        .fetch(&client)
+        .await
+        .is_err());
+
+    let base = serve().await;
+    assert_eq!(
+        provider(format!("{base}/flaky"), "/image", 1)
+            .fetch(&client)
+            .await
+            .unwrap(),
+        "https://img/fox.jpg"
+    );
+}
+
+#[tokio::test]
+async fn missing_image_is_an_error() {
+    let base = serve().await;
+    let client = reqwest::Client::new();
+
+    assert!(provider(format!("{base}/empty"), "/url", 0)
+        .fetch(&client)
+        .await
+        .is_err());
+}
+
+#[test]
+fn bundled_config_is_valid() {
+    let providers: Vec<ProviderConfig> =
+        serde_json::from_str(include_str!("../../providers.json")).unwrap();
+
+    assert!(providers.iter().any(|p| p.name == "cat"));
+    assert!(providers
+        .iter()
+        .all(|p| p.name.chars().all(|c| c.is_ascii_lowercase())));
+}
+
+/// A provider whose availability is switched by the test, counting its calls.
+#[derive(Default)]
+struct FakeProvider {
+    down: AtomicBool,
+    calls: AtomicU32,
+}
+
+#[async_trait]
+impl ImageProvider for FakeProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn title(&self) -> &'static str {
+        "Here's a fake image"
+    }
+
+    async fn fetch(&self, _: &reqwest::Client) -> Result<String, Error> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+
+        if self.down.load(Ordering::SeqCst) {
+            Err("down".into())
+        } else {
+            Ok(format!("https://img/{call}.png"))
+        }
+    }
+}
+
+fn guarded(open_for: Duration) -> (Arc<FakeProvider>, GuardedProvider) {
+    let fake = Arc::new(FakeProvider::default());
+    let guarded = GuardedProvider::new(fake.clone(), CircuitBreaker::new(2, open_for), 10);
+    (fake, guarded)
+}
+
+#[test]
+fn breaker_opens_after_threshold_and_allows_one_trial() {
+    let breaker = CircuitBreaker::new(2, Duration::ZERO);
+
+    breaker.record_failure();
+    assert!(breaker.allow());
+
+    breaker.record_failure();
+    // The open period has already passed, so exactly one trial goes through
+    assert!(breaker.allow());
+
+    breaker.record_success();
+    assert!(breaker.allow());
+    assert!(breaker.allow());
+}
+
+#[test]
+fn open_breaker_fails_fast() {
+    let breaker = CircuitBreaker::new(1, Duration::from_mins(1));
+
+    breaker.record_failure();
+
+    assert!(!breaker.allow());
+}
+
+#[tokio::test]
+async fn open_circuit_skips_the_api_and_serves_cached_images() {
+    let client = reqwest::Client::new();
+    let (fake, guarded) = guarded(Duration::from_mins(1));
+
+    let cached = guarded.fetch(&client).await.unwrap();
+
+    fake.down.store(true, Ordering::SeqCst);
+    assert_eq!(guarded.fetch(&client).await.unwrap(), cached);
+    assert_eq!(guarded.fetch(&client).await.unwrap(), cached);
+
+    // Two failures opened the circuit, so this one never reaches the API
+    assert_eq!(guarded.fetch(&client).await.unwrap(), cached);
+    assert_eq!(fake.calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn outage_without_cached_images_is_an_error() {
+    let client = reqwest::Client::new();
+    let (fake, guarded) = guarded(Duration::from_mins(1));
+
+    fake.down.store(true, Ordering::SeqCst);
+
+    assert!(guarded.fetch(&client).await.is_err());
+}
+
+#[tokio::test]
+async fn circuit_closes_once_the_api_recovers() {
+    let client = reqwest::Client::new();
+    let (fake, guarded) = guarded(Duration::ZERO);
+
+    fake.down.store(true, Ordering::SeqCst);
+    assert!(guarded.fetch(&client).await.is_err());
+    assert!(guarded.fetch(&client).await.is_err());
+
+    fake.down.store(false, Ordering::SeqCst);
+    assert_eq!(guarded.fetch(&client).await.unwrap(), "https://img/2.png");
+    assert_eq!(guarded.fetch(&client).await.unwrap(), "https://img/3.png");
+}
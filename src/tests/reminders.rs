@@ -0,0 +1,230 @@
+use super::{
+    from_now,
+    mock_discord::{MockDiscord, DM_CHANNEL_ID},
+};
+use crate::{
+    database::{memory::MemoryDatabase, PostgresDatabase, ReminderRepository},
+    i18n::Locales,
+    reminders::deliver_due,
+    structs::{Reminder, ShardConfig},
+    utils::{format_duration, parse_duration},
+};
+use axum::http::{Method, StatusCode};
+use poise::serenity_prelude::{ChannelId, GuildId, UserId};
+use sqlx::{types::time::OffsetDateTime, PgPool};
+use std::{sync::Arc, time::Duration};
+
+const USER_ID: u64 = 200;
+const CHANNEL_ID: u64 = 500;
+
+/// A reminder due `due_in` seconds from now, negative if it's overdue.
+fn reminder(guild_id: Option<u64>, text: &str, due_in: i64) -> Reminder {
+    Reminder {
+        id: 0,
+        user_id: UserId::new(USER_ID),
+        guild_id: guild_id.map(GuildId::new),
+        channel_id: guild_id.map(|_| ChannelId::new(CHANNEL_ID)),
+        text: text.to_string(),
+        created_at: from_now(-3 * 60 * 60),
+        remind_at: from_now(due_in),
+    }
+}
+
+async fn run(
+    mock: &MockDiscord,
+    db: &MemoryDatabase,
+    shard_config: &ShardConfig,
+) -> Option<OffsetDateTime> {
+    let locales = Arc::new(Locales::bundled());
+    deliver_due(
+        &mock.http(),
+        db,
+        &locales,
+        shard_config,
+        Duration::from_mins(1),
+    )
+    .await
+}
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration("10m"), Some(Duration::from_mins(10)));
+    assert_eq!(parse_duration("1h30m"), Some(Duration::from_mins(90)));
+    assert_eq!(parse_duration("2d 12h"), Some(Duration::from_hours(60)));
+    assert_eq!(parse_duration("1 week"), Some(Duration::from_hours(7 * 24)));
+    assert_eq!(parse_duration("5 Minutes"), Some(Duration::from_mins(5)));
+
+    for invalid in [
+        "",
+        "10",
+        "m",
+        "0m",
+        "10x",
+        "1h -5m",
+        "99999999999999999999w",
+    ] {
+        assert_eq!(parse_duration(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn formats_durations() {
+    assert_eq!(format_duration(Duration::ZERO), "0s");
+    assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+    assert_eq!(format_duration(Duration::from_mins(150)), "2h 30m");
+    assert_eq!(
+        format_duration(Duration::from_hours(24 * 8 + 1)),
+        "1w 1d 1h"
+    );
+}
+
+#[tokio::test]
+async fn due_reminders_are_delivered_and_deleted() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(Some(100), "water the plants", -5))
+        .await;
+    let later = reminder(Some(100), "feed the cat", 30);
+    db.add_reminder(&later).await;
+
+    let next = run(&mock, &db, &ShardConfig::Auto).await;
+
+    let post = mock
+        .find(&Method::POST, &format!("/channels/{CHANNEL_ID}/messages"))
+        .expect("reminder was not sent");
+    assert!(post.body.contains("water the plants"));
+    assert!(post.body.contains(&format!("<@{USER_ID}>")));
+    assert!(!post.body.contains("offline"));
+
+    let remaining = db.get_reminders(UserId::new(USER_ID)).await;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].text, "feed the cat");
+    assert_eq!(next, Some(later.remind_at));
+}
+
+#[tokio::test]
+async fn reminders_missed_while_offline_say_so() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(Some(100), "stand up", -2 * 60 * 60))
+        .await;
+
+    run(&mock, &db, &ShardConfig::Auto).await;
+
+    let post = mock
+        .find(&Method::POST, &format!("/channels/{CHANNEL_ID}/messages"))
+        .expect("reminder was not sent");
+    assert!(post.body.contains("I was offline"));
+    assert!(db.reminders.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reminders_without_channel_are_dmed() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(None, "call mom", -5)).await;
+
+    run(&mock, &db, &ShardConfig::Auto).await;
+
+    let dm = mock
+        .find(
+            &Method::POST,
+            &format!("/channels/{DM_CHANNEL_ID}/messages"),
+        )
+        .expect("reminder was not DMed");
+    assert!(dm.body.contains("call mom"));
+}
+
+#[tokio::test]
+async fn reminders_are_dmed_when_their_channel_is_gone() {
+    let mock = MockDiscord::start().await;
+    mock.fail(
+        Method::POST,
+        &format!("/channels/{CHANNEL_ID}/messages"),
+        StatusCode::NOT_FOUND,
+    );
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(Some(100), "call mom", -5)).await;
+
+    run(&mock, &db, &ShardConfig::Auto).await;
+
+    assert!(mock
+        .find(
+            &Method::POST,
+            &format!("/channels/{DM_CHANNEL_ID}/messages"),
+        )
+        .is_some());
+    assert!(db.reminders.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn reminders_are_kept_after_transient_errors() {
+    let mock = MockDiscord::start().await;
+    mock.fail(
+        Method::POST,
+        &format!("/channels/{CHANNEL_ID}/messages"),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    );
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(Some(100), "call mom", -5)).await;
+
+    run(&mock, &db, &ShardConfig::Auto).await;
+
+    // Not DMed either, the channel gets another try on the next pass
+    assert!(mock
+        .find(
+            &Method::POST,
+            &format!("/channels/{DM_CHANNEL_ID}/messages"),
+        )
+        .is_none());
+    assert_eq!(db.reminders.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn reminders_of_other_processes_are_left_alone() {
+    let mock = MockDiscord::start().await;
+    let db = MemoryDatabase::default();
+    db.add_reminder(&reminder(None, "call mom", -5)).await;
+
+    let shard_config = ShardConfig::Range {
+        first: 1,
+        last: 1,
+        total: 2,
+    };
+    assert!(!shard_config.owns_dms());
+    assert!(ShardConfig::Auto.owns_dms());
+
+    run(&mock, &db, &shard_config).await;
+
+    assert!(mock.requests().is_empty());
+    assert_eq!(db.reminders.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn reminders_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    let user_id = UserId::new(USER_ID);
+
+    let due = db.add_reminder(&reminder(None, "due", -5)).await.unwrap();
+    let later = db
+        .add_reminder(&reminder(Some(100), "later", 60 * 60))
+        .await
+        .unwrap();
+
+    let reminders = db.get_reminders(user_id).await;
+    assert_eq!(
+        reminders.iter().map(|r| r.id).collect::<Vec<_>>(),
+        [due, later]
+    );
+    assert_eq!(reminders[1].channel_id, Some(ChannelId::new(CHANNEL_ID)));
+
+    let before = db.get_reminders_before(OffsetDateTime::now_utc()).await;
+    assert_eq!(before.len(), 1);
+    assert_eq!(before[0].text, "due");
+
+    assert!(!db.cancel_reminder(UserId::new(1), later).await);
+    assert!(db.cancel_reminder(user_id, later).await);
+    db.delete_reminder(due).await;
+    assert!(db.get_reminders(user_id).await.is_empty());
+}
@@ -0,0 +1,204 @@
+use crate::{
+    database::{GuildRepository, PostgresDatabase, RolePanelRepository},
+    i18n::{Lang, Locales},
+    role_panels::{button_id, menu_id, panel_components, parse_custom_id, parse_emoji, plan},
+    structs::{Guild, PanelRole, RolePanel, RolePanelKind},
+    utils::highest_role_position,
+};
+use poise::serenity_prelude::{
+    ChannelId, Guild as SerenityGuild, GuildId, MessageId, ReactionType, Role, RoleId,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const GUILD_ID: u64 = 100;
+const BOT_ROLE: u64 = 10;
+const MOD_ROLE: u64 = 20;
+const PING_ROLE: u64 = 30;
+const INTEGRATION_ROLE: u64 = 40;
+
+fn role(id: u64, position: u16, managed: bool) -> Role {
+    let mut role = Role::default();
+    role.id = RoleId::new(id);
+    role.guild_id = GuildId::new(GUILD_ID);
+    role.position = position;
+    role.managed = managed;
+    role
+}
+
+/// @everyone, a ping role below the bot, and a mod role above it.
+fn guild() -> SerenityGuild {
+    let mut guild = SerenityGuild::default();
+    guild.id = GuildId::new(GUILD_ID);
+
+    for role in [
+        role(GUILD_ID, 0, false),
+        role(PING_ROLE, 1, false),
+        role(INTEGRATION_ROLE, 2, true),
+        role(BOT_ROLE, 3, false),
+        role(MOD_ROLE, 4, false),
+    ] {
+        guild.roles.insert(role.id, role);
+    }
+
+    guild
+}
+
+fn panel(kind: RolePanelKind, roles: u64) -> RolePanel {
+    RolePanel {
+        id: 0,
+        guild_id: GuildId::new(GUILD_ID),
+        channel_id: ChannelId::new(500),
+        message_id: MessageId::new(600),
+        kind,
+        title: String::from("Pronouns"),
+        description: None,
+        roles: (1..=roles)
+            .map(|id| PanelRole {
+                role_id: RoleId::new(id),
+                label: format!("Role {id}"),
+                emoji: None,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn custom_ids_round_trip() {
+    assert_eq!(
+        parse_custom_id(&button_id(7, RoleId::new(30))),
+        Some((7, Some(RoleId::new(30))))
+    );
+    assert_eq!(parse_custom_id(&menu_id(7)), Some((7, None)));
+
+    for invalid in [
+        "",
+        "rolepanel",
+        "rolepanel:x",
+        "rolepanel:1:0",
+        "rolepanel:1:2:3",
+        "poll:1",
+    ] {
+        assert_eq!(parse_custom_id(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn emoji_are_parsed() {
+    assert_eq!(
+        parse_emoji(" 🦀 "),
+        Some(ReactionType::Unicode(String::from("🦀")))
+    );
+    assert!(matches!(
+        parse_emoji("<:ferris:123>"),
+        Some(ReactionType::Custom { .. })
+    ));
+
+    for invalid in ["", "crab", "🦀 crab", "<:ferris>"] {
+        assert_eq!(parse_emoji(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn highest_position_ignores_unknown_roles() {
+    let guild = guild();
+
+    assert_eq!(highest_role_position(&guild, &[]), 0);
+    assert_eq!(
+        highest_role_position(&guild, &[RoleId::new(PING_ROLE), RoleId::new(MOD_ROLE)]),
+        4
+    );
+    assert_eq!(highest_role_position(&guild, &[RoleId::new(999)]), 0);
+}
+
+#[test]
+fn clicks_toggle_roles_below_the_bot() {
+    let guild = guild();
+    let bot_roles = [RoleId::new(BOT_ROLE)];
+
+    let changes = plan(&guild, &bot_roles, &[], &[RoleId::new(PING_ROLE)]);
+    assert_eq!(changes.added, [RoleId::new(PING_ROLE)]);
+    assert!(changes.removed.is_empty() && changes.refused.is_empty());
+
+    let changes = plan(
+        &guild,
+        &bot_roles,
+        &[RoleId::new(PING_ROLE)],
+        &[RoleId::new(PING_ROLE)],
+    );
+    assert_eq!(changes.removed, [RoleId::new(PING_ROLE)]);
+    assert!(changes.added.is_empty() && changes.refused.is_empty());
+
+    let refused = [
+        RoleId::new(MOD_ROLE),
+        RoleId::new(BOT_ROLE),
+        RoleId::new(INTEGRATION_ROLE),
+        RoleId::new(GUILD_ID),
+        RoleId::new(999),
+    ];
+    let changes = plan(&guild, &bot_roles, &[], &refused);
+    assert_eq!(changes.refused, refused);
+    assert!(changes.added.is_empty() && changes.removed.is_empty());
+}
+
+#[test]
+fn buttons_are_split_into_rows() {
+    let lang = Lang::new(Arc::new(Locales::bundled()), "en-US");
+
+    assert!(panel_components(&lang, &panel(RolePanelKind::Buttons, 0)).is_empty());
+    assert_eq!(
+        panel_components(&lang, &panel(RolePanelKind::Buttons, 12)).len(),
+        3
+    );
+    assert_eq!(
+        panel_components(&lang, &panel(RolePanelKind::Menu, 25)).len(),
+        1
+    );
+}
+
+#[sqlx::test]
+async fn role_panels_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    let guild_id = GuildId::new(GUILD_ID);
+    db.insert_guild(&Guild::new(guild_id)).await;
+
+    let id = db
+        .create_role_panel(&panel(RolePanelKind::Menu, 0))
+        .await
+        .unwrap();
+
+    let ping = PanelRole {
+        role_id: RoleId::new(PING_ROLE),
+        label: String::from("Pings"),
+        emoji: Some(String::from("🔔")),
+    };
+    let mods = PanelRole {
+        role_id: RoleId::new(MOD_ROLE),
+        label: String::from("Mods"),
+        emoji: None,
+    };
+    db.add_panel_role(id, &ping).await;
+    db.add_panel_role(id, &mods).await;
+
+    // Adding a role again updates it in place
+    let renamed = PanelRole {
+        label: String::from("Notifications"),
+        ..ping.clone()
+    };
+    db.add_panel_role(id, &renamed).await;
+
+    let stored = db.get_role_panel(guild_id, id).await.unwrap();
+    assert_eq!(stored.kind, RolePanelKind::Menu);
+    assert_eq!(stored.message_id, MessageId::new(600));
+    assert_eq!(stored.roles, [renamed, mods]);
+
+    assert!(db.get_role_panel(GuildId::new(999), id).await.is_none());
+
+    assert!(db.remove_panel_role(id, RoleId::new(MOD_ROLE)).await);
+    assert!(!db.remove_panel_role(id, RoleId::new(MOD_ROLE)).await);
+    assert_eq!(db.get_role_panels(guild_id).await[0].roles.len(), 1);
+
+    assert!(db.delete_role_panel(guild_id, id).await);
+    assert!(!db.delete_role_panel(guild_id, id).await);
+    assert!(db.get_role_panels(guild_id).await.is_empty());
+}
@@ -0,0 +1,230 @@
+use crate::{
+    database::{
+        cache::MAX_USER_LOCALES, memory::MemoryDatabase, GuildRepository, PostgresDatabase,
+        SettingsCache, SettingsRepository, UserSettingsRepository,
+    },
+    structs::Guild,
+};
+use poise::serenity_prelude::{ChannelId, GuildId, UserId};
+use sqlx::PgPool;
+use std::sync::{atomic::Ordering, Arc};
+
+const GUILD_ID: GuildId = GuildId::new(100);
+
+fn cache() -> (Arc<MemoryDatabase>, SettingsCache) {
+    let inner = Arc::new(MemoryDatabase::default());
+    let cache = SettingsCache::new(inner.clone());
+    (inner, cache)
+}
+
+fn set_modlog_behind_cache(inner: &MemoryDatabase, channel_id: u64) {
+    inner.guilds.lock().unwrap().insert(
+        GUILD_ID,
+        Guild {
+            mod_id: Some(ChannelId::new(channel_id)),
+            ..Guild::new(GUILD_ID)
+        },
+    );
+}
+
+#[tokio::test]
+async fn settings_are_loaded_once() {
+    let (inner, cache) = cache();
+    set_modlog_behind_cache(&inner, 1);
+
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(1))
+    );
+
+    set_modlog_behind_cache(&inner, 2);
+
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(1))
+    );
+}
+
+#[tokio::test]
+async fn eviction_reloads_settings() {
+    let (inner, cache) = cache();
+    set_modlog_behind_cache(&inner, 1);
+    cache.get_settings(GUILD_ID).await;
+
+    set_modlog_behind_cache(&inner, 2);
+    cache.evict(GUILD_ID);
+
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(2))
+    );
+}
+
+#[tokio::test]
+async fn updates_are_written_through() {
+    let (inner, cache) = cache();
+
+    cache
+        .update_settings(&Guild {
+            mod_id: Some(ChannelId::new(3)),
+            ..Guild::new(GUILD_ID)
+        })
+        .await;
+
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(3))
+    );
+    assert_eq!(
+        inner.get_guild(GUILD_ID).await.unwrap().unwrap().mod_id,
+        Some(ChannelId::new(3))
+    );
+}
+
+#[tokio::test]
+async fn missing_guilds_are_cached_until_written() {
+    let (inner, cache) = cache();
+
+    assert!(cache.get_settings(GUILD_ID).await.mod_id.is_none());
+    assert!(cache.get_guild(GUILD_ID).await.unwrap().is_none());
+
+    // The defaults are cached, so a row appearing behind the cache isn't seen
+    set_modlog_behind_cache(&inner, 4);
+    assert!(cache.get_settings(GUILD_ID).await.mod_id.is_none());
+
+    cache
+        .update_settings(&Guild {
+            mod_id: Some(ChannelId::new(5)),
+            ..Guild::new(GUILD_ID)
+        })
+        .await;
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(5))
+    );
+}
+
+#[tokio::test]
+async fn inserting_a_missing_guild_caches_it_as_stored() {
+    let (_, cache) = cache();
+
+    assert!(cache.get_guild(GUILD_ID).await.unwrap().is_none());
+    assert!(cache.insert_guild(&Guild::new(GUILD_ID)).await);
+    assert!(cache.get_guild(GUILD_ID).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn failed_guild_lookups_are_not_cached() {
+    let (inner, cache) = cache();
+
+    inner.fail_reads.store(true, Ordering::Relaxed);
+    assert!(cache.get_guild(GUILD_ID).await.is_err());
+    assert!(cache.get_settings(GUILD_ID).await.mod_id.is_none());
+
+    inner.fail_reads.store(false, Ordering::Relaxed);
+    set_modlog_behind_cache(&inner, 6);
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(6))
+    );
+}
+
+#[tokio::test]
+async fn failed_updates_are_not_cached() {
+    let (inner, cache) = cache();
+    set_modlog_behind_cache(&inner, 1);
+    cache.get_settings(GUILD_ID).await;
+
+    inner.fail_writes.store(true, Ordering::Relaxed);
+    let saved = cache
+        .update_settings(&Guild {
+            mod_id: Some(ChannelId::new(2)),
+            ..Guild::new(GUILD_ID)
+        })
+        .await;
+
+    assert!(!saved);
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(1))
+    );
+}
+
+#[tokio::test]
+async fn failed_user_locale_lookups_are_not_cached() {
+    let (inner, cache) = cache();
+    let user_id = UserId::new(1);
+
+    inner.fail_reads.store(true, Ordering::Relaxed);
+    assert!(cache.get_user_locale(user_id).await.is_err());
+
+    inner.fail_reads.store(false, Ordering::Relaxed);
+    inner
+        .user_locales
+        .lock()
+        .unwrap()
+        .insert(user_id, String::from("de"));
+    assert_eq!(
+        cache.get_user_locale(user_id).await.unwrap().as_deref(),
+        Some("de")
+    );
+}
+
+#[tokio::test]
+async fn user_locale_cache_is_bounded() {
+    let (inner, cache) = cache();
+    let first = UserId::new(1);
+
+    for id in 1..=MAX_USER_LOCALES as u64 {
+        cache.get_user_locale(UserId::new(id)).await.unwrap();
+    }
+
+    // The first user is still cached, so this change behind the cache isn't seen
+    inner
+        .user_locales
+        .lock()
+        .unwrap()
+        .insert(first, String::from("de"));
+    assert_eq!(cache.get_user_locale(first).await.unwrap(), None);
+
+    // Caching one more user makes room by dropping the oldest entries
+    cache
+        .get_user_locale(UserId::new(MAX_USER_LOCALES as u64 + 1))
+        .await
+        .unwrap();
+    assert_eq!(
+        cache.get_user_locale(first).await.unwrap().as_deref(),
+        Some("de")
+    );
+}
+
+#[tokio::test]
+async fn inserting_an_existing_guild_keeps_its_settings() {
+    let (inner, cache) = cache();
+    set_modlog_behind_cache(&inner, 5);
+
+    cache.insert_guild(&Guild::new(GUILD_ID)).await;
+
+    assert_eq!(
+        cache.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(5))
+    );
+}
+
+#[sqlx::test]
+async fn inserting_an_existing_guild_in_postgres_keeps_its_settings(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+
+    assert!(db.insert_guild(&Guild::new(GUILD_ID)).await);
+    db.update_settings(&Guild {
+        mod_id: Some(ChannelId::new(5)),
+        ..Guild::new(GUILD_ID)
+    })
+    .await;
+    assert!(db.insert_guild(&Guild::new(GUILD_ID)).await);
+
+    assert_eq!(
+        db.get_settings(GUILD_ID).await.mod_id,
+        Some(ChannelId::new(5))
+    );
+}
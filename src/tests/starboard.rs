@@ -0,0 +1,99 @@
+use crate::{
+    database::{PostgresDatabase, SettingsRepository, StarboardRepository},
+    embeds::DESCRIPTION_LIMIT,
+    i18n::{Lang, Locales},
+    starboard::{is_star, plan, repost_embed, Action},
+    structs::{Guild, StarboardPost, Theme},
+};
+use poise::serenity_prelude::{ChannelId, GuildId, Message, MessageId, ReactionType};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const GUILD_ID: GuildId = GuildId::new(100);
+
+fn post(stars: i32) -> StarboardPost {
+    StarboardPost {
+        guild_id: GUILD_ID,
+        channel_id: ChannelId::new(200),
+        message_id: MessageId::new(300),
+        starboard_channel_id: ChannelId::new(400),
+        starboard_message_id: MessageId::new(500),
+        stars,
+    }
+}
+
+#[test]
+fn only_stars_count() {
+    assert!(is_star(&ReactionType::Unicode("⭐".to_string())));
+    assert!(!is_star(&ReactionType::Unicode("🌟".to_string())));
+}
+
+#[test]
+fn posts_follow_the_star_count() {
+    assert_eq!(plan(2, 3, None), Action::Nothing);
+    assert_eq!(plan(3, 3, None), Action::Post);
+    assert_eq!(plan(4, 3, Some(&post(3))), Action::Edit);
+    assert_eq!(plan(3, 3, Some(&post(3))), Action::Nothing);
+    assert_eq!(plan(2, 3, Some(&post(3))), Action::Remove);
+
+    // Raising the threshold takes down posts on their next change
+    assert_eq!(plan(4, 5, Some(&post(3))), Action::Remove);
+}
+
+#[test]
+fn long_messages_fit_in_a_repost() {
+    let lang = Lang::new(Arc::new(Locales::bundled()), "en-US");
+    let description = |content: String| {
+        let mut message = Message::default();
+        message.content = content;
+
+        let embed =
+            serde_json::to_value(repost_embed(&lang, &Theme::default(), &message, GUILD_ID))
+                .unwrap();
+        embed["description"].as_str().unwrap().to_string()
+    };
+
+    let truncated = description("ä".repeat(DESCRIPTION_LIMIT));
+    assert_eq!(truncated.chars().count(), DESCRIPTION_LIMIT);
+    assert!(truncated.contains("…\n\n"));
+
+    let short = description(String::from("Hello"));
+    assert!(short.starts_with("Hello\n\n"));
+}
+
+#[sqlx::test]
+async fn starboard_posts_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    db.update_settings(&Guild::new(GUILD_ID)).await;
+
+    assert_eq!(db.get_starboard_post(MessageId::new(300)).await, None);
+
+    db.set_starboard_post(&post(3)).await;
+    db.set_starboard_post(&post(5)).await;
+    assert_eq!(
+        db.get_starboard_post(MessageId::new(300)).await,
+        Some(post(5))
+    );
+
+    db.delete_starboard_post(MessageId::new(300)).await;
+    assert_eq!(db.get_starboard_post(MessageId::new(300)).await, None);
+}
+
+#[sqlx::test]
+async fn starboard_settings_are_stored(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+
+    assert_eq!(db.get_settings(GUILD_ID).await.starboard_id, None);
+    assert_eq!(db.get_settings(GUILD_ID).await.starboard_threshold, 3);
+
+    db.update_settings(&Guild {
+        starboard_id: Some(ChannelId::new(400)),
+        starboard_threshold: 7,
+        ..Guild::new(GUILD_ID)
+    })
+    .await;
+
+    let guild = db.get_settings(GUILD_ID).await;
+    assert_eq!(guild.starboard_id, Some(ChannelId::new(400)));
+    assert_eq!(guild.starboard_threshold, 7);
+}
@@ -0,0 +1,117 @@
+use crate::{
+    database::{GuildRepository, PostgresDatabase, TagRepository},
+    structs::{Guild, Tag, Theme},
+    tags::{body, normalize_name, render, Placeholders, TagBody},
+};
+use poise::serenity_prelude::{ChannelId, GuildId, User, UserId};
+use sqlx::{types::time::OffsetDateTime, PgPool};
+
+const GUILD_ID: u64 = 100;
+
+fn user() -> User {
+    let mut user = User::default();
+    user.id = UserId::new(300);
+    user.name = String::from("ferris");
+    user
+}
+
+fn tag(name: &str, content: &str, embed: bool) -> Tag {
+    Tag {
+        guild_id: GuildId::new(GUILD_ID),
+        name: name.to_string(),
+        content: content.to_string(),
+        embed,
+        owner_id: UserId::new(200),
+        uses: 0,
+        created_at: OffsetDateTime::now_utc(),
+    }
+}
+
+#[test]
+fn names_are_normalized() {
+    assert_eq!(normalize_name(" Rules "), Some(String::from("rules")));
+    assert_eq!(normalize_name("faq-2_b"), Some(String::from("faq-2_b")));
+
+    for invalid in ["", "two words", "emoji🦀", "no!", &"a".repeat(33)] {
+        assert_eq!(normalize_name(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn placeholders_are_filled_in() {
+    let user = user();
+    let placeholders = Placeholders {
+        user: &user,
+        server: "Rust",
+        channel_id: ChannelId::new(500),
+    };
+
+    assert_eq!(
+        render(
+            "Welcome {user} ({user.name}) to {server}, see {channel}! {unknown}",
+            &placeholders
+        ),
+        "Welcome <@300> (ferris) to Rust, see <#500>! {unknown}"
+    );
+}
+
+#[test]
+fn text_tags_fit_in_a_message() {
+    let user = user();
+    let placeholders = Placeholders {
+        user: &user,
+        server: "Rust",
+        channel_id: ChannelId::new(500),
+    };
+
+    let TagBody::Text(text) = body(
+        &Theme::default(),
+        &tag("spam", &"{user}".repeat(400), false),
+        &placeholders,
+    ) else {
+        panic!("text tag was posted as an embed");
+    };
+    assert_eq!(text.chars().count(), 2000);
+
+    assert!(matches!(
+        body(
+            &Theme::default(),
+            &tag("rules", "Be nice", true),
+            &placeholders
+        ),
+        TagBody::Embed(_)
+    ));
+}
+
+#[sqlx::test]
+async fn tags_are_stored_in_postgres(pool: PgPool) {
+    let db = PostgresDatabase::new(pool);
+    let guild_id = GuildId::new(GUILD_ID);
+    db.insert_guild(&Guild::new(guild_id)).await;
+
+    assert!(db.create_tag(&tag("rules", "Be nice", false)).await);
+    assert!(!db.create_tag(&tag("rules", "Be mean", false)).await);
+    assert!(db.create_tag(&tag("faq", "Read the docs", true)).await);
+
+    db.use_tag(guild_id, "rules").await;
+    db.use_tag(guild_id, "rules").await;
+    db.update_tag(&tag("rules", "Be very nice", true)).await;
+
+    let rules = db.get_tag(guild_id, "rules").await.unwrap();
+    assert_eq!(rules.content, "Be very nice");
+    assert!(rules.embed);
+    assert_eq!(rules.uses, 2);
+    assert_eq!(rules.owner_id, UserId::new(200));
+
+    let names: Vec<_> = db
+        .get_tags(guild_id)
+        .await
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    assert_eq!(names, ["faq", "rules"]);
+
+    assert!(db.delete_tag(guild_id, "faq").await);
+    assert!(!db.delete_tag(guild_id, "faq").await);
+    assert!(db.get_tag(guild_id, "faq").await.is_none());
+}
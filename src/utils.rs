@@ -1,42 +1,98 @@
 use crate::{
-    database::{get_guild, insert_guild, insert_infraction},
-    structs::{Context, Error, Guild, Infraction, InfractionType, ReqwestClientContainer},
+    cooldowns::Cooldowns,
+    database::{get_database, Database},
+    embeds::{embed, get_theme, EmbedKind},
+    giveaways::Giveaways,
+    i18n::{get_lang, resolve, Lang, Locales},
+    levels::Levels,
+    modlog::{ModlogPost, ModlogQueue},
+    permissions::PermissionOverrides,
+    polls::Polls,
+    providers::ImageProviders,
+    reminders::Reminders,
+    role_panels::assignable,
+    shutdown::Shutdown,
+    starboard::Starboard,
+    structs::{
+        Context, CooldownsContainer, Error, GiveawaysContainer, Guild, ImageProvidersContainer,
+        Infraction, InfractionType, LevelsContainer, LocalesContainer, ModlogContainer,
+        PermissionOverridesContainer, PollsContainer, RemindersContainer, ReqwestClientContainer,
+        ShutdownContainer, StarboardContainer, Theme,
+    },
 };
+use fluent::fluent_args;
 use poise::{
     serenity_prelude::{
-        ChannelId, Color, Context as SerenityContext, CreateEmbed, CreateEmbedAuthor,
-        CreateMessage, Member, RoleId, Timestamp, User, UserId,
+        ChannelId, ComponentInteraction, Context as SerenityContext, CreateEmbed,
+        CreateEmbedAuthor, CreateInteractionResponse, CreateInteractionResponseMessage,
+        CreateMessage, EditInteractionResponse, EditMember, Error as SerenityError,
+        Guild as SerenityGuild, GuildId, Http, Member, Role, RoleId, StatusCode, Timestamp, User,
+        UserId,
     },
     CreateReply,
 };
 use reqwest::Client;
+use sqlx::types::time::OffsetDateTime;
+use std::{sync::Arc, time::Duration};
 use tracing::error;
 
 pub async fn manageable(ctx: Context<'_>, member: &Member, target: &Member) -> bool {
-    let member_highest_role = highest_role_position(ctx, &member.roles);
-    let target_highest_role = highest_role_position(ctx, &target.roles);
+    let guild = ctx.guild().unwrap();
+    let member_highest_role = highest_role_position(&guild, &member.roles);
+    let target_highest_role = highest_role_position(&guild, &target.roles);
 
-    if member_highest_role < target_highest_role || target.user.id == ctx.guild().unwrap().owner_id
-    {
+    if member_highest_role < target_highest_role || target.user.id == guild.owner_id {
         return false;
     }
 
     true
 }
 
-fn highest_role_position(ctx: Context<'_>, roles: &Vec<RoleId>) -> u16 {
-    let guild = ctx.guild().unwrap();
-    let mut highest_role_pos = 0;
+/// Checks that both the bot and the member can give out the role, telling the
+/// member if not.
+pub async fn check_assignable(ctx: Context<'_>, lang: &Lang, role: &Role) -> Result<bool, Error> {
+    let mention = format!("<@&{}>", role.id);
+    let bot_id = ctx.cache().current_user().id;
+    let bot_roles = ctx.guild_id().unwrap().member(ctx, bot_id).await?.roles;
+    let author_roles = ctx
+        .author_member()
+        .await
+        .map(|m| m.roles.clone())
+        .unwrap_or_default();
 
-    for role_id in roles {
-        let role = guild.roles.get(role_id).unwrap();
+    let error = {
+        let guild = ctx.guild().unwrap();
 
-        if highest_role_pos < role.position {
-            highest_role_pos = role.position;
+        if role.managed || role.id.get() == guild.id.get() {
+            Some("role-managed")
+        } else if !assignable(&guild, &bot_roles, role.id) {
+            Some("role-too-high")
+        } else if guild.owner_id != ctx.author().id
+            && role.position >= highest_role_position(&guild, &author_roles)
+        {
+            Some("role-above-you")
+        } else {
+            None
         }
+    };
+
+    if let Some(error) = error {
+        let msg = lang.tr_with(error, &fluent_args!["role" => mention]);
+        send_error_msg(ctx, &msg).await;
+        return Ok(false);
     }
 
-    highest_role_pos
+    Ok(true)
+}
+
+/// The position of the highest of `roles`, or 0 (the @everyone role) for none.
+pub fn highest_role_position(guild: &SerenityGuild, roles: &[RoleId]) -> u16 {
+    roles
+        .iter()
+        .filter_map(|role_id| guild.roles.get(role_id))
+        .map(|role| role.position)
+        .max()
+        .unwrap_or_default()
 }
 
 pub async fn get_member(ctx: Context<'_>, id: UserId) -> Member {
@@ -51,194 +107,448 @@ pub async fn get_member(ctx: Context<'_>, id: UserId) -> Member {
     }
 }
 
+/// Parses durations such as `90s`, `10m`, `1h30m` or `2d 12h`. Units are
+/// seconds, minutes, hours, days and weeks, by their first letter or spelled
+/// out. Returns `None` for anything else, including zero.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let mut total: u64 = 0;
+    let mut rest = input.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+
+        let amount: u64 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let seconds = match rest[..letters].to_lowercase().as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+            "d" | "day" | "days" => 24 * 60 * 60,
+            "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+
+        total = total.checked_add(amount.checked_mul(seconds)?)?;
+        rest = rest[letters..].trim_start();
+    }
+
+    (total > 0).then(|| Duration::from_secs(total))
+}
+
+/// Formats a duration the way [`parse_duration`] reads them, such as `1d 2h 30m`.
+pub fn format_duration(duration: Duration) -> String {
+    let mut seconds = duration.as_secs();
+    let mut parts = Vec::new();
+
+    for (unit, length) in [
+        ("w", 7 * 24 * 60 * 60),
+        ("d", 24 * 60 * 60),
+        ("h", 60 * 60),
+        ("m", 60),
+        ("s", 1),
+    ] {
+        if seconds >= length {
+            parts.push(format!("{}{unit}", seconds / length));
+            seconds %= length;
+        }
+    }
+
+    if parts.is_empty() {
+        return String::from("0s");
+    }
+
+    parts.join(" ")
+}
+
+/// Everything needed to carry out a moderation action, independent of how it was invoked.
+pub struct ModAction<'a> {
+    pub guild_id: GuildId,
+    pub guild_name: &'a str,
+    pub moderator_id: UserId,
+    pub target: &'a User,
+    pub reason: &'a str,
+    pub kind: InfractionType,
+    /// How long a mute lasts.
+    pub duration: Option<Duration>,
+}
+
 pub async fn handle_moderation(
     ctx: Context<'_>,
     mod_type: InfractionType,
     user: &User,
-    reason: &String,
+    reason: &str,
+    duration: Option<Duration>,
 ) -> Result<(), Error> {
-    send_mod_msg_to_user(ctx, &mod_type, user, reason).await;
-    send_mod_msg_to_channel(ctx, &mod_type, user, reason).await;
+    let guild_name = ctx.guild().unwrap().name.clone();
 
-    let infraction = Infraction {
-        guild_id: ctx.guild_id().unwrap().to_string(),
-        member_id: user.id.to_string(),
-        moderator_id: ctx.author().id.to_string(),
-        reason: reason.to_string(),
-        infraction_type: mod_type,
-        created_at: None,
+    let action = ModAction {
+        guild_id: ctx.guild_id().unwrap(),
+        guild_name: &guild_name,
+        moderator_id: ctx.author().id,
+        target: user,
+        reason,
+        kind: mod_type,
+        duration,
     };
 
-    insert_guild(
-        ctx.serenity_context(),
-        &Guild {
-            id: ctx.guild_id().unwrap().to_string(),
-            mod_id: None,
-            audit_id: None,
-            welcome_id: None,
-        },
-    )
-    .await;
-    insert_infraction(ctx.serenity_context(), &infraction).await;
+    let db = get_database(ctx.serenity_context()).await;
+    let locales = get_locales(ctx.serenity_context()).await;
+    let modlog = get_modlog_queue(ctx.serenity_context()).await;
 
-    let guild = if let Some(g) =
-        get_guild(ctx.serenity_context(), &ctx.guild_id().unwrap().to_string()).await
-    {
-        g
-    } else {
-        let guild = Guild {
-            id: ctx.guild_id().unwrap().to_string(),
-            mod_id: None,
-            audit_id: None,
-            welcome_id: None,
-        };
+    apply_moderation(ctx.http(), db.as_ref(), &locales, &modlog, &action).await?;
+
+    let lang = get_lang(ctx).await;
+    let theme = get_theme(ctx).await;
+    ctx.send(CreateReply::default().embed(mod_action_embed(&lang, &theme, &action)))
+        .await?;
 
-        insert_guild(ctx.serenity_context(), &guild).await;
+    Ok(())
+}
 
-        guild
+/// DMs the target, records the infraction, queues the modlog post and then
+/// performs the action itself. The DM uses the target's language and the
+/// modlog post the guild's.
+pub async fn apply_moderation(
+    http: &Http,
+    db: &dyn Database,
+    locales: &Arc<Locales>,
+    modlog: &ModlogQueue,
+    action: &ModAction<'_>,
+) -> Result<(), Error> {
+    let theme = db.get_settings(action.guild_id).await.theme;
+    let target_lang = resolve(
+        db,
+        locales.clone(),
+        Some(action.guild_id),
+        Some(action.target.id),
+    )
+    .await;
+    send_mod_msg_to_user(http, &target_lang, &theme, action).await;
+
+    let infraction = Infraction {
+        guild_id: action.guild_id,
+        member_id: action.target.id,
+        moderator_id: action.moderator_id,
+        reason: action.reason.to_string(),
+        infraction_type: action.kind,
+        created_at: None,
     };
 
-    if guild.mod_id.is_some() {
-        let mod_id = guild.mod_id.unwrap();
-        let modlog_id = ChannelId::from(mod_id.parse::<u64>().unwrap());
+    if let Some(modlog_id) = record_infraction(db, &infraction).await {
+        let guild_lang = resolve(db, locales.clone(), Some(action.guild_id), None).await;
+        send_mod_msg_to_modlog(modlog, &guild_lang, &theme, action, modlog_id);
+    }
+
+    match action.kind {
+        InfractionType::Ban => {
+            action
+                .guild_id
+                .ban_with_reason(http, action.target.id, 7, action.reason)
+                .await?;
+        }
+        InfractionType::Kick => {
+            action
+                .guild_id
+                .kick_with_reason(http, action.target.id, action.reason)
+                .await?;
+        }
+        InfractionType::Mute => {
+            if let Some(duration) = action.duration {
+                let until = OffsetDateTime::now_utc() + duration;
+                let until = Timestamp::from_unix_timestamp(until.unix_timestamp())?;
 
-        send_mod_msg_to_modlog(&ctx, &mod_type, user, reason, modlog_id).await;
+                action
+                    .guild_id
+                    .edit_member(
+                        http,
+                        action.target.id,
+                        EditMember::new()
+                            .disable_communication_until_datetime(until)
+                            .audit_log_reason(action.reason),
+                    )
+                    .await?;
+            }
+        }
+        InfractionType::Warn => {}
     }
 
     Ok(())
 }
 
-pub async fn send_mod_msg_to_user(
-    ctx: Context<'_>,
-    mod_type: &InfractionType,
-    user: &User,
-    reason: &String,
-) {
+/// Stores the infraction, making sure its guild exists first, and returns the
+/// guild's modlog channel if one is configured.
+pub async fn record_infraction(db: &dyn Database, infraction: &Infraction) -> Option<ChannelId> {
+    db.insert_guild(&Guild::new(infraction.guild_id)).await;
+    db.insert_infraction(infraction).await;
+
+    db.get_settings(infraction.guild_id).await.mod_id
+}
+
+/// The catalog selector value for an infraction type.
+pub fn infraction_key(kind: InfractionType) -> &'static str {
+    match kind {
+        InfractionType::Ban => "ban",
+        InfractionType::Kick => "kick",
+        InfractionType::Mute => "mute",
+        InfractionType::Warn => "warn",
+    }
+}
+
+pub async fn send_mod_msg_to_user(http: &Http, lang: &Lang, theme: &Theme, action: &ModAction<'_>) {
+    let user = action.target;
+
     if user.bot {
         return;
     }
 
-    let mod_type = match mod_type {
-        InfractionType::Ban => "banned",
-        InfractionType::Kick => "kicked",
-        InfractionType::Mute => "muted",
-        InfractionType::Warn => "warned",
-    };
+    let title = lang.tr_with(
+        "mod-dm-title",
+        &fluent_args!["action" => infraction_key(action.kind), "server" => action.guild_name],
+    );
 
-    if let Err(e) = user
-        .dm(
-            ctx,
-            CreateMessage::default().embed(
-                CreateEmbed::new()
-                    .author(
-                        CreateEmbedAuthor::new(format!(
-                            "You have been {} from {}",
-                            mod_type,
-                            ctx.guild().unwrap().name
-                        ))
-                        .icon_url(user.face()),
-                    )
-                    .field("Reason", reason.to_string(), true)
-                    .timestamp(Timestamp::now())
-                    .color(Color::BLUE),
-            ),
-        )
-        .await
-    {
+    let mut embed = theme
+        .embed(EmbedKind::Primary)
+        .author(CreateEmbedAuthor::new(title).icon_url(user.face()))
+        .field(lang.tr("mod-field-reason"), action.reason, true);
+
+    if let Some(duration) = action.duration {
+        embed = embed.field(
+            lang.tr("mod-field-duration"),
+            format_duration(duration),
+            true,
+        );
+    }
+
+    if let Err(e) = user.dm(http, CreateMessage::default().embed(embed)).await {
         error!("Error: {}", e);
     }
 }
 
-pub async fn send_mod_msg_to_channel(
-    ctx: Context<'_>,
-    mod_type: &InfractionType,
-    user: &User,
-    reason: &String,
-) {
-    let mod_type = match mod_type {
-        InfractionType::Ban => "Banned",
-        InfractionType::Kick => "Kicked",
-        InfractionType::Mute => "Muted",
-        InfractionType::Warn => "Warned",
-    };
+pub fn mod_action_embed(lang: &Lang, theme: &Theme, action: &ModAction<'_>) -> CreateEmbed {
+    let user = action.target;
 
-    if let Err(e) = ctx
-        .send(
-            CreateReply::default().embed(
-                CreateEmbed::new()
-                    .author(
-                        CreateEmbedAuthor::new(format!("{} {}", mod_type, user.name))
-                            .icon_url(user.face()),
-                    )
-                    .field("User", format!("<@{}>", user.id), true)
-                    .field("Reason", reason.to_string(), true)
-                    .timestamp(Timestamp::now())
-                    .color(Color::BLUE),
-            ),
-        )
-        .await
-    {
-        error!("Error: {}", e);
+    let title = lang.tr_with(
+        "mod-action-title",
+        &fluent_args!["action" => infraction_key(action.kind), "user" => user.name.as_str()],
+    );
+
+    let embed = theme
+        .embed(EmbedKind::Success)
+        .author(CreateEmbedAuthor::new(title).icon_url(user.face()))
+        .field(lang.tr("mod-field-user"), format!("<@{}>", user.id), true)
+        .field(lang.tr("mod-field-reason"), action.reason, true);
+
+    match action.duration {
+        Some(duration) => embed.field(
+            lang.tr("mod-field-duration"),
+            format_duration(duration),
+            true,
+        ),
+        None => embed,
     }
 }
 
-pub async fn send_mod_msg_to_modlog(
-    ctx: &Context<'_>,
-    mod_type: &InfractionType,
-    user: &User,
-    reason: &String,
+pub fn send_mod_msg_to_modlog(
+    modlog: &ModlogQueue,
+    lang: &Lang,
+    theme: &Theme,
+    action: &ModAction<'_>,
     modlog_id: ChannelId,
 ) {
-    let mod_type = match mod_type {
-        InfractionType::Ban => "Banned",
-        InfractionType::Kick => "Kicked",
-        InfractionType::Mute => "Muted",
-        InfractionType::Warn => "Warned",
-    };
+    modlog.push(ModlogPost {
+        channel_id: modlog_id,
+        embed: mod_action_embed(lang, theme, action),
+    });
+}
 
-    let channel = ctx.http().get_channel(modlog_id).await;
+pub async fn get_reqwest_client(ctx: &SerenityContext) -> Client {
+    ctx.data
+        .read()
+        .await
+        .get::<ReqwestClientContainer>()
+        .unwrap()
+        .clone()
+}
 
-    if channel.is_err() {
-        return error!("Modlog channel not found");
-    }
+pub async fn get_image_providers(ctx: &SerenityContext) -> Arc<ImageProviders> {
+    ctx.data
+        .read()
+        .await
+        .get::<ImageProvidersContainer>()
+        .unwrap()
+        .clone()
+}
 
-    if let Err(e) = ctx
-        .send(
-            CreateReply::default().embed(
-                CreateEmbed::new()
-                    .author(
-                        CreateEmbedAuthor::new(format!("{} {}", mod_type, user.name))
-                            .icon_url(user.face()),
-                    )
-                    .field("User", format!("<@{}>", user.id), true)
-                    .field("Reason", reason.to_string(), true)
-                    .timestamp(Timestamp::now())
-                    .color(Color::BLUE),
-            ),
-        )
+pub async fn get_modlog_queue(ctx: &SerenityContext) -> ModlogQueue {
+    ctx.data
+        .read()
         .await
-    {
-        error!("Error: {}", e);
-    }
+        .get::<ModlogContainer>()
+        .unwrap()
+        .clone()
 }
 
-pub async fn get_reqwest_client(ctx: &SerenityContext) -> Client {
+pub async fn get_cooldowns(ctx: &SerenityContext) -> Arc<Cooldowns> {
     ctx.data
         .read()
         .await
-        .get::<ReqwestClientContainer>()
+        .get::<CooldownsContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_locales(ctx: &SerenityContext) -> Arc<Locales> {
+    ctx.data
+        .read()
+        .await
+        .get::<LocalesContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_permission_overrides(ctx: &SerenityContext) -> Arc<PermissionOverrides> {
+    ctx.data
+        .read()
+        .await
+        .get::<PermissionOverridesContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_reminders(ctx: &SerenityContext) -> Arc<Reminders> {
+    ctx.data
+        .read()
+        .await
+        .get::<RemindersContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_polls(ctx: &SerenityContext) -> Arc<Polls> {
+    ctx.data
+        .read()
+        .await
+        .get::<PollsContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_giveaways(ctx: &SerenityContext) -> Arc<Giveaways> {
+    ctx.data
+        .read()
+        .await
+        .get::<GiveawaysContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_levels(ctx: &SerenityContext) -> Arc<Levels> {
+    ctx.data
+        .read()
+        .await
+        .get::<LevelsContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_starboard(ctx: &SerenityContext) -> Arc<Starboard> {
+    ctx.data
+        .read()
+        .await
+        .get::<StarboardContainer>()
+        .unwrap()
+        .clone()
+}
+
+pub async fn get_shutdown(ctx: &SerenityContext) -> Arc<Shutdown> {
+    ctx.data
+        .read()
+        .await
+        .get::<ShutdownContainer>()
         .unwrap()
         .clone()
 }
 
+/// Acknowledges a component click with a private response that shows as
+/// thinking, so handling it can take longer than Discord waits for an answer.
+/// Returns `false` if the click couldn't be acknowledged.
+pub async fn defer_ephemeral(ctx: &SerenityContext, interaction: &ComponentInteraction) -> bool {
+    let result = interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Defer(
+                CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await;
+
+    if let Err(e) = &result {
+        error!("Error: {}", e);
+    }
+
+    result.is_ok()
+}
+
+/// Answers a click acknowledged with [`defer_ephemeral`].
+pub async fn respond(
+    ctx: &SerenityContext,
+    interaction: &ComponentInteraction,
+    embed: CreateEmbed,
+) {
+    if let Err(e) = interaction
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await
+    {
+        error!("Error: {}", e);
+    }
+}
+
+/// Whether Discord refused a request for good, because the target is gone or
+/// we lost access to it, so retrying it later won't help.
+pub fn is_permanent(error: &SerenityError) -> bool {
+    match error {
+        SerenityError::Http(e) => matches!(
+            e.status_code(),
+            Some(StatusCode::FORBIDDEN | StatusCode::NOT_FOUND)
+        ),
+        _ => false,
+    }
+}
+
+/// Whether a request failed because its target, such as a message, was deleted.
+pub fn is_gone(error: &SerenityError) -> bool {
+    match error {
+        SerenityError::Http(e) => e.status_code() == Some(StatusCode::NOT_FOUND),
+        _ => false,
+    }
+}
+
 pub async fn send_error_msg(ctx: Context<'_>, msg: &str) {
+    let lang = get_lang(ctx).await;
+
     if let Err(e) = ctx
         .send(
             CreateReply::default().embed(
-                CreateEmbed::new()
-                    .author(CreateEmbedAuthor::new("Error").icon_url(ctx.author().face()))
-                    .description(msg)
-                    .color(Color::RED),
+                embed(ctx, EmbedKind::Error)
+                    .await
+                    .author(
+                        CreateEmbedAuthor::new(lang.tr("error-title"))
+                            .icon_url(ctx.author().face()),
+                    )
+                    .description(msg),
             ),
         )
         .await